@@ -0,0 +1,43 @@
+/* C interface of timecode-coder, built with the "ffi" feature */
+#ifndef TIMECODE_CODER_H
+#define TIMECODE_CODER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct LtcFfiDecoder LtcFfiDecoder;
+
+typedef struct {
+    uint8_t hours;
+    uint8_t minutes;
+    uint8_t seconds;
+    uint8_t frames;
+    /* 24, 25, 30, 50 or 60. 0 if unknown */
+    uint8_t frames_per_second;
+    /* 1 for 29.97 fps drop-frame timecode, which has 30 in frames_per_second, else 0 */
+    uint8_t drop_frame;
+} LtcFfiTimecodeFrame;
+
+/* Creates a decoder. Needs to be freed with ltc_decoder_destroy. Returns NULL if sample_rate is 0 */
+LtcFfiDecoder *ltc_decoder_create(uint32_t sample_rate);
+
+/* Frees a decoder. NULL is ignored */
+void ltc_decoder_destroy(LtcFfiDecoder *decoder);
+
+/* Decodes len samples. Writes up to out_capacity frames to out and returns how many were written */
+size_t ltc_decoder_push_samples(LtcFfiDecoder *decoder, const int32_t *samples, size_t len,
+                                LtcFfiTimecodeFrame *out, size_t out_capacity);
+
+/* Same as ltc_decoder_push_samples for float samples in the range -1.0 to 1.0 */
+size_t ltc_decoder_push_samples_f32(LtcFfiDecoder *decoder, const float *samples, size_t len,
+                                    LtcFfiTimecodeFrame *out, size_t out_capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,110 @@
+//! Command line tool to decode LTC from WAV files and to generate LTC test tone
+
+use std::process::ExitCode;
+
+use timecode_coder::{FramesPerSecond, TimecodeFrame};
+use timecode_coder::frame_list::{write_frame_list_file, FrameListFormat};
+use timecode_coder::ltc_encoder::LtcEncoder;
+use timecode_coder::wav_file::decode_wav_file;
+
+const USAGE: &str = "Usage:
+  ltc-tool decode <file.wav> [channel]
+  ltc-tool export <file.wav> <out.csv|out.edl> [channel]
+  ltc-tool generate <HH:MM:SS:FF> <24|25|30|29.97|50|60> <duration in seconds> <out.wav> [sample rate]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("decode") => decode(&args[1..]),
+        Some("export") => export(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints every decoded frame with the sample index it was decoded at
+fn decode(args: &[String]) -> Result<(), String> {
+    let file = args.first().ok_or(USAGE)?;
+    let channel = parse_channel(args.get(1))?;
+    let frames = decode_wav_file(file, channel).map_err(|e| e.to_string())?;
+    for (sample_index, frame) in frames {
+        println!("{}\t{}\t{:?}", sample_index, frame, frame.frames_per_second);
+    }
+    Ok(())
+}
+
+/// Writes the decoded frames as CSV or EDL, depending on the extension of the output file
+fn export(args: &[String]) -> Result<(), String> {
+    let (Some(file), Some(list)) = (args.first(), args.get(1)) else {
+        return Err(USAGE.to_string());
+    };
+    let format = if list.to_lowercase().ends_with(".edl") { FrameListFormat::Edl } else { FrameListFormat::Csv };
+    let channel = parse_channel(args.get(2))?;
+    let written = write_frame_list_file(file, channel, list, format).map_err(|e| e.to_string())?;
+    println!("{} entries written to {}", written, list);
+    Ok(())
+}
+
+fn parse_channel(arg: Option<&String>) -> Result<u16, String> {
+    match arg {
+        Some(channel) => channel.parse().map_err(|_| format!("Invalid channel {}", channel)),
+        None => Ok(0),
+    }
+}
+
+/// Writes a mono 16bit WAV file with LTC
+fn generate(args: &[String]) -> Result<(), String> {
+    if args.len() < 4 {
+        return Err(USAGE.to_string());
+    }
+    let frames_per_second = match args[1].as_str() {
+        "24" => FramesPerSecond::TwentyFour,
+        "25" => FramesPerSecond::TwentyFive,
+        "30" => FramesPerSecond::Thirty,
+        "29.97" => FramesPerSecond::ThirtyDropFrame,
+        "50" => FramesPerSecond::Fifty,
+        "60" => FramesPerSecond::Sixty,
+        fps => return Err(format!("Unsupported frame-rate {}", fps)),
+    };
+    let start = parse_timecode(&args[0], frames_per_second)?;
+    let duration_s: f64 = args[2].parse().ok()
+        .filter(|duration: &f64| duration.is_finite() && *duration > 0.0)
+        .ok_or_else(|| format!("Invalid duration {}", args[2]))?;
+    let sample_rate: u32 = match args.get(4) {
+        Some(rate) => rate.parse().ok().filter(|rate| *rate > 0).ok_or_else(|| format!("Invalid sample rate {}", rate))?,
+        None => 48_000,
+    };
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&args[3], spec).map_err(|e| e.to_string())?;
+    // -6dBFS
+    let mut encoder = LtcEncoder::new(start, sample_rate, i16::MAX / 2, i16::MIN / 2).ok_or("Unknown frame-rate")?;
+    for _ in 0..(duration_s * sample_rate as f64) as u64 {
+        writer.write_sample(encoder.next_sample()).map_err(|e| e.to_string())?;
+    }
+    writer.finalize().map_err(|e| e.to_string())
+}
+
+/// Parses HH:MM:SS:FF or HH:MM:SS;FF
+fn parse_timecode(value: &str, frames_per_second: FramesPerSecond) -> Result<TimecodeFrame, String> {
+    let fields: Vec<u8> = value.split([':', ';'])
+        .map(|field| field.parse::<u8>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid timecode {}", value))?;
+    if fields.len() != 4 {
+        return Err(format!("Invalid timecode {}", value));
+    }
+    TimecodeFrame::try_new(fields[0], fields[1], fields[2], fields[3], frames_per_second)
+        .map_err(|e| format!("Invalid timecode {}: {}", value, e))
+}
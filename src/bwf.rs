@@ -0,0 +1,323 @@
+//! Helpers for the time reference of Broadcast Wave (BWF) files, which tells at how many samples
+//! since midnight a file starts
+
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::TimecodeFrame;
+use crate::wav_file::{for_each_frame, WavDecodeError};
+
+/// Errors that may occur when checking the time reference of a BWF file
+#[derive(Debug)]
+pub enum BwfError {
+    Io(std::io::Error),
+    /// The timecode could not be decoded from the file
+    Wav(WavDecodeError),
+    /// The file is no RIFF WAVE file
+    NotAWavFile,
+    /// The file has no bext chunk
+    NoTimeReference,
+    /// No frame with known frame-rate was decoded
+    NoTimecode,
+}
+
+impl Display for BwfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BwfError::Io(e) => write!(f, "Could not read file: {}", e),
+            BwfError::Wav(e) => write!(f, "{}", e),
+            BwfError::NotAWavFile => write!(f, "Not a WAV file"),
+            BwfError::NoTimeReference => write!(f, "No bext chunk with time reference"),
+            BwfError::NoTimecode => write!(f, "No timecode decoded"),
+        }
+    }
+}
+
+impl std::error::Error for BwfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BwfError::Io(e) => Some(e),
+            BwfError::Wav(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for BwfError {
+    fn from(e: std::io::Error) -> Self {
+        BwfError::Io(e)
+    }
+}
+
+impl From<WavDecodeError> for BwfError {
+    fn from(e: WavDecodeError) -> Self {
+        BwfError::Wav(e)
+    }
+}
+
+impl From<hound::Error> for BwfError {
+    fn from(e: hound::Error) -> Self {
+        BwfError::Wav(WavDecodeError::Wav(e))
+    }
+}
+
+/// Result of comparing the time reference in the bext chunk with the decoded LTC
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TimeReferenceCheck {
+    /// Samples since midnight at the start of the file according to the bext chunk
+    pub time_reference: u64,
+    /// Samples since midnight at the start of the file according to the LTC
+    pub ltc_time_reference: u64,
+}
+
+impl TimeReferenceCheck {
+    /// How many samples the LTC is later than the time reference
+    pub fn offset_samples(&self) -> i64 {
+        self.ltc_time_reference as i64 - self.time_reference as i64
+    }
+    /// Tells if the time reference and the LTC differ by no more than the tolerance. The start of a
+    /// frame is detected at the accuracy of a few samples, so the tolerance should not be zero
+    pub fn agrees(&self, tolerance_samples: u64) -> bool {
+        self.offset_samples().unsigned_abs() <= tolerance_samples
+    }
+}
+
+/// Offset of TimeReferenceLow in the bext chunk, after description, originator, originator
+/// reference, origination date and origination time
+const TIME_REFERENCE_OFFSET: u64 = 256 + 32 + 32 + 10 + 8;
+/// Size of a bext chunk without coding history
+const BEXT_SIZE: u32 = 602;
+
+/// Compares the time reference in the bext chunk of a BWF file with the LTC on a channel (0 is the
+/// first channel)
+pub fn check_time_reference_file<P: AsRef<Path>>(path: P, channel: u16) -> Result<TimeReferenceCheck, BwfError> {
+    check_time_reference(BufReader::new(File::open(path)?), channel)
+}
+
+/// Same as check_time_reference_file, but reads the BWF data from any reader
+pub fn check_time_reference<R: Read + Seek>(mut reader: R, channel: u16) -> Result<TimeReferenceCheck, BwfError> {
+    let time_reference = read_time_reference(&mut reader)?.ok_or(BwfError::NoTimeReference)?;
+    reader.seek(SeekFrom::Start(0))?;
+    let ltc_time_reference = ltc_time_reference(reader, channel)?;
+    Ok(TimeReferenceCheck { time_reference, ltc_time_reference })
+}
+
+/// Stamps a WAV file from its LTC on a channel (0 is the first channel): computes the time reference
+/// from the first decoded frame and writes it to the bext chunk. Returns the time reference
+pub fn write_time_reference_file<P: AsRef<Path>>(path: P, channel: u16) -> Result<u64, BwfError> {
+    write_time_reference(&mut OpenOptions::new().read(true).write(true).open(path)?, channel)
+}
+
+/// Same as write_time_reference_file for any file-like data. The time reference of an existing
+/// bext chunk is updated in place, all other fields are kept. Otherwise an empty bext chunk with
+/// the time reference is appended after the audio data
+pub fn write_time_reference<F: Read + Write + Seek>(file: &mut F, channel: u16) -> Result<u64, BwfError> {
+    let existing = find_chunk(file, b"bext")?;
+    file.seek(SeekFrom::Start(0))?;
+    let time_reference = ltc_time_reference(BufReader::new(&mut *file), channel)?;
+    match existing {
+        Some((offset, size)) if size as u64 >= TIME_REFERENCE_OFFSET + 8 => {
+            file.seek(SeekFrom::Start(offset + TIME_REFERENCE_OFFSET))?;
+            file.write_all(&time_reference.to_le_bytes())?;
+        }
+        // A bext chunk too short to hold the time reference is left as is and can't be read
+        Some(_) => return Err(BwfError::NoTimeReference),
+        None => append_bext(file, time_reference)?,
+    }
+    Ok(time_reference)
+}
+
+/// Appends a bext chunk that only holds the time reference and updates the RIFF size
+fn append_bext<F: Write + Seek>(file: &mut F, time_reference: u64) -> Result<(), BwfError> {
+    // Chunks start at even positions
+    if file.seek(SeekFrom::End(0))? % 2 == 1 {
+        file.write_all(&[0])?;
+    }
+    let mut bext = [0; BEXT_SIZE as usize];
+    bext[TIME_REFERENCE_OFFSET as usize..TIME_REFERENCE_OFFSET as usize + 8].copy_from_slice(&time_reference.to_le_bytes());
+    file.write_all(b"bext")?;
+    file.write_all(&BEXT_SIZE.to_le_bytes())?;
+    file.write_all(&bext)?;
+    let riff_size = u32::try_from(file.stream_position()? - 8).map_err(|_| BwfError::NotAWavFile)?;
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&riff_size.to_le_bytes())?;
+    Ok(())
+}
+
+/// Reads the time reference of the bext chunk. None if the file has no bext chunk or if it is too
+/// short to hold the time reference
+pub fn read_time_reference<R: Read + Seek>(reader: &mut R) -> Result<Option<u64>, BwfError> {
+    let Some((offset, size)) = find_chunk(reader, b"bext")? else {
+        return Ok(None);
+    };
+    if (size as u64) < TIME_REFERENCE_OFFSET + 8 {
+        return Ok(None);
+    }
+    reader.seek(SeekFrom::Start(offset + TIME_REFERENCE_OFFSET))?;
+    let mut time_reference = [0; 8];
+    reader.read_exact(&mut time_reference)?;
+    Ok(Some(u64::from_le_bytes(time_reference)))
+}
+
+/// The time reference at which the file has to start, so the first decoded frame starts at the
+/// time of day of its timecode. Drop-frame timecode is counted at 29.97 fps, all other frame-rates
+/// at their nominal rate. Timecode that passed midnight within the file wraps to the day before
+pub fn ltc_time_reference<R: Read>(reader: R, channel: u16) -> Result<u64, BwfError> {
+    let mut first_frame = None;
+    let sample_rate = for_each_frame(hound::WavReader::new(reader)?, channel, |_, start, frame| {
+        if first_frame.is_none() && frame.frames_per_second.frame_rate().is_some() {
+            first_frame = Some((start, frame));
+        }
+    })?;
+    let (start, frame) = first_frame.ok_or(BwfError::NoTimecode)?;
+    let samples = samples_since_midnight(&frame, sample_rate).ok_or(BwfError::NoTimecode)?;
+    match samples.checked_sub(start) {
+        Some(time_reference) => Ok(time_reference),
+        // The day of drop-frame timecode is shorter than 24 hours
+        None => Ok(samples_per_day(&frame, sample_rate).ok_or(BwfError::NoTimecode)? + samples - start),
+    }
+}
+
+/// Samples from midnight to the start of the frame, rounded
+fn samples_since_midnight(frame: &TimecodeFrame, sample_rate: u32) -> Option<u64> {
+    frames_to_samples(frame.to_frame_count()?, frame, sample_rate)
+}
+
+/// Samples from midnight to the next midnight of the timecode, rounded
+fn samples_per_day(frame: &TimecodeFrame, sample_rate: u32) -> Option<u64> {
+    frames_to_samples(frame.frames_per_second.frames_per_day()?, frame, sample_rate)
+}
+
+/// Samples of a number of frames at the frame-rate of the frame, rounded
+fn frames_to_samples(frames: u32, frame: &TimecodeFrame, sample_rate: u32) -> Option<u64> {
+    let frame_rate = frame.frames_per_second.frame_rate()?;
+    let numerator = frame_rate.numerator() as u64;
+    Some((frames as u64 * frame_rate.denominator() as u64 * sample_rate as u64 + numerator / 2) / numerator)
+}
+
+/// Searches the chunks of a RIFF WAVE file for the id. Returns the position of its data and its
+/// size
+pub(crate) fn find_chunk<R: Read + Seek>(reader: &mut R, id: &[u8; 4]) -> Result<Option<(u64, u32)>, BwfError> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err(BwfError::NotAWavFile);
+    }
+    let mut position = 12;
+    let mut chunk_header = [0; 8];
+    loop {
+        reader.seek(SeekFrom::Start(position))?;
+        match reader.read_exact(&mut chunk_header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let size = u32::from_le_bytes([chunk_header[4], chunk_header[5], chunk_header[6], chunk_header[7]]);
+        if &chunk_header[0..4] == id {
+            return Ok(Some((position + 8, size)));
+        }
+        // Chunks are padded to an even size
+        position += 8 + size as u64 + (size as u64 & 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::bwf::{append_bext, check_time_reference, ltc_time_reference, read_time_reference, write_time_reference, BwfError};
+    use crate::FramesPerSecond::{ThirtyDropFrame, TwentyFive};
+    use crate::ltc_decoder::test_signal::{ltc_samples, wav_data, SignalShape};
+    use crate::TimecodeFrame;
+
+    /// A mono WAV file with LTC and a bext chunk after the data chunk
+    fn bwf(start: &TimecodeFrame, samples_per_bit: f32, time_reference: Option<u64>) -> Vec<u8> {
+        let mut data = wav_data(ltc_samples(start, 10, &SignalShape::new(samples_per_bit)));
+        if let Some(time_reference) = time_reference {
+            append_bext(&mut data, time_reference).unwrap();
+        }
+        data.into_inner()
+    }
+
+    #[test]
+    fn test_check_time_reference() {
+        let ten_hours = 10 * 3600 * 48_000;
+        let file = bwf(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 24.0, Some(ten_hours));
+        let check = check_time_reference(Cursor::new(file), 0).unwrap();
+        assert_eq!(check.time_reference, ten_hours);
+        assert!(check.agrees(2), "{}", check.offset_samples());
+        // The LTC is a second later than the time reference
+        let file = bwf(&TimecodeFrame::new(10, 0, 1, 0, TwentyFive), 24.0, Some(ten_hours));
+        let check = check_time_reference(Cursor::new(file), 0).unwrap();
+        assert!((check.offset_samples() - 48_000).abs() <= 2, "{}", check.offset_samples());
+        assert!(!check.agrees(2));
+    }
+
+    #[test]
+    fn test_drop_frame() {
+        // 00:10:00;00 is 17982 frames at 29.97 fps
+        let time_reference = 17_982 * 1001 * 48_000 / 30_000;
+        let file = bwf(&TimecodeFrame::new(0, 10, 0, 0, ThirtyDropFrame), 48_000.0 * 1001.0 / 30_000.0 / 80.0, Some(time_reference));
+        let check = check_time_reference(Cursor::new(file), 0).unwrap();
+        assert!(check.agrees(2), "{}", check.offset_samples());
+    }
+
+    #[test]
+    fn test_drop_frame_midnight() {
+        // The first frame starts a second into the file, so the file starts the day before
+        let samples_per_frame = 48_000.0 * 1001.0 / 30_000.0;
+        let silence = core::iter::repeat_n(0, 48_000);
+        let ltc = ltc_samples(&TimecodeFrame::new(0, 0, 0, 0, ThirtyDropFrame), 10, &SignalShape::new(samples_per_frame / 80.0));
+        let file = wav_data(silence.chain(ltc));
+        // 2589408 frames at 29.97 fps
+        let samples_per_day = 2_589_408 * 1001 * 48_000 / 30_000;
+        let time_reference = ltc_time_reference(file, 0).unwrap();
+        assert!(time_reference.abs_diff(samples_per_day - 48_000) <= 2, "{}", time_reference);
+    }
+
+    #[test]
+    fn test_write_time_reference() {
+        let ten_hours = 10 * 3600 * 48_000;
+        // Appends a bext chunk
+        let mut file = Cursor::new(bwf(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 24.0, None));
+        let time_reference = write_time_reference(&mut file, 0).unwrap();
+        assert!(time_reference.abs_diff(ten_hours) <= 2, "{}", time_reference);
+        assert_eq!(read_time_reference(&mut file).unwrap(), Some(time_reference));
+        let riff_size = u32::from_le_bytes(file.get_ref()[4..8].try_into().unwrap());
+        assert_eq!(riff_size as usize, file.get_ref().len() - 8);
+        assert!(check_time_reference(&mut file, 0).unwrap().agrees(0));
+        // Updates the time reference in place
+        let mut file = Cursor::new(bwf(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 24.0, Some(0)));
+        let size = file.get_ref().len();
+        assert_eq!(write_time_reference(&mut file, 0).unwrap(), time_reference);
+        assert_eq!(file.get_ref().len(), size);
+        assert_eq!(read_time_reference(&mut file).unwrap(), Some(time_reference));
+    }
+
+    #[test]
+    fn test_no_time_reference() {
+        let file = bwf(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 24.0, None);
+        assert_eq!(read_time_reference(&mut Cursor::new(&file)).unwrap(), None);
+        assert!(matches!(check_time_reference(Cursor::new(file), 0), Err(BwfError::NoTimeReference)));
+        assert!(matches!(read_time_reference(&mut Cursor::new(b"RIFX\0\0\0\0WAVE")), Err(BwfError::NotAWavFile)));
+    }
+
+    #[test]
+    fn test_short_bext_chunk() {
+        // A bext chunk without time reference, followed by a chunk that would be read as it
+        let mut file = bwf(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 24.0, None);
+        file.extend(b"bext");
+        file.extend(10_u32.to_le_bytes());
+        file.extend([0; 10]);
+        file.extend(b"junk");
+        file.extend(400_u32.to_le_bytes());
+        file.extend([0xFF; 400]);
+        assert_eq!(read_time_reference(&mut Cursor::new(&file)).unwrap(), None);
+        assert!(matches!(check_time_reference(Cursor::new(&file), 0), Err(BwfError::NoTimeReference)));
+        assert!(matches!(write_time_reference(&mut Cursor::new(file), 0), Err(BwfError::NoTimeReference)));
+    }
+}
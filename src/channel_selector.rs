@@ -0,0 +1,160 @@
+//! Decodes all channels of multichannel input and follows the channel that decodes best, e.g. for
+//! ingest stations where operators patch LTC inconsistently
+
+use crate::ltc_decoder::{DecodeErrorKind, LtcDecoder, LtcDecoderConfig, Sample, TimecodeSink};
+use crate::TimecodeFrame;
+
+/// Tracks how well a channel decodes. Rises with every frame and falls with every error and
+/// every frame duration without a frame
+#[derive(Clone, Default)]
+struct ChannelScore {
+    score: u8,
+    frame: Option<TimecodeFrame>,
+    samples_without_frame: u32,
+}
+
+impl ChannelScore {
+    const MAX: u8 = 100;
+    const STEP: u8 = 10;
+}
+
+impl TimecodeSink for ChannelScore {
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        self.score = (self.score + Self::STEP).min(Self::MAX);
+        self.frame = Some(frame.clone());
+        self.samples_without_frame = 0;
+    }
+    fn on_error(&mut self, _kind: DecodeErrorKind) {
+        self.score = self.score.saturating_sub(Self::STEP);
+    }
+}
+
+/// Runs a decoder per channel and returns the frames of the channel with the best decode success
+/// rate. All channels have to be sampled with the same clock
+pub struct ChannelSelectingDecoder<T: Sample, const N: usize> {
+    decoders: [LtcDecoder<T>; N],
+    scores: [ChannelScore; N],
+    active: usize,
+    hysteresis: u8,
+    /// Duration of a frame at the lowest frame-rate
+    max_frame_samples: u32,
+}
+
+impl<T: Sample, const N: usize> ChannelSelectingDecoder<T, N> {
+    /// Score (0 to 100) another channel needs above the active one to become active
+    const DEFAULT_HYSTERESIS: u8 = 30;
+
+    pub fn new(sampling_rate: u32) -> Self {
+        Self::new_with_config(sampling_rate, LtcDecoderConfig::default())
+    }
+    pub fn new_with_config(sampling_rate: u32, config: LtcDecoderConfig) -> Self {
+        Self {
+            decoders: core::array::from_fn(|_| LtcDecoder::new_with_config(sampling_rate, config.clone())),
+            scores: core::array::from_fn(|_| ChannelScore::default()),
+            active: 0,
+            hysteresis: Self::DEFAULT_HYSTERESIS,
+            max_frame_samples: sampling_rate / 24,
+        }
+    }
+    /// Sets the score (0 to 100) another channel needs above the active one to become active. Every
+    /// decoded frame adds 10 to the score of a channel. Every decode error and every frame duration
+    /// without a frame subtracts 10
+    pub fn with_hysteresis(mut self, hysteresis: u8) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Push the samples of all channels that were taken at the same time. Returns the frames of the
+    /// active channel
+    pub fn push_samples(&mut self, samples: &[T; N]) -> Option<TimecodeFrame> {
+        for ((decoder, score), sample) in self.decoders.iter_mut().zip(self.scores.iter_mut()).zip(samples) {
+            score.frame = None;
+            decoder.push_sample_to_sink(*sample, 0, score);
+            score.samples_without_frame += 1;
+            if score.samples_without_frame > self.max_frame_samples {
+                score.on_error(DecodeErrorKind::Overdue);
+                score.samples_without_frame = 0;
+            }
+        }
+        self.active = self.select_channel();
+        self.scores.get(self.active)?.frame.clone()
+    }
+    /// Like push_samples for a buffer with N interleaved channels. The frames of the active channel
+    /// are passed to the sink
+    pub fn push_interleaved<S: TimecodeSink>(&mut self, buffer: &[T], sink: &mut S) {
+        for sample_frame in buffer.chunks_exact(N.max(1)) {
+            let Ok(samples) = sample_frame.try_into() else {
+                continue;
+            };
+            if let Some(frame) = self.push_samples(samples) {
+                sink.on_frame(&frame);
+            }
+        }
+    }
+
+    /// The channel whose frames are returned, 0 for the first channel
+    pub fn active_channel(&self) -> usize {
+        self.active
+    }
+    /// How well a channel decodes from 0 to 100. None if the channel does not exist
+    pub fn score(&self, channel: usize) -> Option<u8> {
+        self.scores.get(channel).map(|score| score.score)
+    }
+    pub fn decoders(&self) -> &[LtcDecoder<T>; N] {
+        &self.decoders
+    }
+
+    /// Switches only if another channel is better by more than the hysteresis
+    fn select_channel(&self) -> usize {
+        let active_score = self.score(self.active).unwrap_or(0);
+        let best = (0..N).max_by_key(|channel| (self.scores[*channel].score, core::cmp::Reverse(*channel)));
+        match best {
+            Some(best) if self.scores[best].score > active_score.saturating_add(self.hysteresis) => best,
+            _ => self.active,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::channel_selector::ChannelSelectingDecoder;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::CallbackSink;
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_select_channel() {
+        let marginal = ltc_samples(&TimecodeFrame::new(1, 0, 0, 0, TwentyFive), 40, &SignalShape::new(24.0));
+        let good = ltc_samples(&TimecodeFrame::new(2, 0, 0, 0, TwentyFive), 40, &SignalShape::new(24.0));
+        let mut decoder = ChannelSelectingDecoder::<i32, 3>::new(48_000);
+        let mut frames = Vec::new();
+        for i in 0..marginal.len() {
+            // Channel 0 is silent, channel 1 drops every sixth frame and channel 2 starts late
+            let channel_1 = if (i / 1920) % 6 == 5 { 0 } else { marginal[i] };
+            let channel_2 = if i < 20 * 1920 { 0 } else { good[i] };
+            frames.extend(decoder.push_samples(&[0, channel_1, channel_2]).map(|frame| (i, decoder.active_channel(), frame)));
+            if i == 19 * 1920 {
+                assert_eq!(decoder.active_channel(), 1);
+            }
+        }
+        assert_eq!(decoder.active_channel(), 2);
+        assert_eq!(decoder.score(2), Some(100));
+        assert!(decoder.score(1).unwrap() < 100);
+        assert_eq!(decoder.score(0), Some(0));
+        assert!(frames.iter().all(|(_, channel, frame)| frame.hours as usize == *channel));
+        assert_eq!(frames.last().unwrap().2, TimecodeFrame::new(2, 0, 1, 14, TwentyFive));
+    }
+
+    #[test]
+    fn test_interleaved() {
+        let samples = ltc_samples(&TimecodeFrame::new(2, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let buffer: Vec<i32> = samples.iter().flat_map(|sample| [0, *sample]).collect();
+        let mut decoder = ChannelSelectingDecoder::<i32, 2>::new(48_000).with_hysteresis(0);
+        let mut frames = Vec::new();
+        decoder.push_interleaved(&buffer, &mut CallbackSink::new(|frame: &TimecodeFrame| frames.push(frame.clone())));
+        assert_eq!(decoder.active_channel(), 1);
+        // The first frame selects the channel and is returned
+        assert_eq!(frames.len(), 9);
+    }
+}
@@ -0,0 +1,113 @@
+//! Measures offset and drift of received timecode against a local transport, to drive a varispeed
+//! resampler or a transport servo
+
+use crate::clock_mapping::ClockMapping;
+use crate::TimecodeFrame;
+
+/// Offset and drift of the received timecode against the local position
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ChaseState {
+    /// How many frames the timecode is ahead of the local position. Negative if it is behind
+    pub offset_frames: f64,
+    /// How much faster the timecode runs than the local clock, e.g. 0.001 for 0.1% faster
+    pub drift: f64,
+}
+
+/// Compares decoded frames with the position of a local transport. The local position is counted in
+/// ticks (e.g. samples) since 00:00:00:00 and has to advance with the local clock
+pub struct ChaseController {
+    ticks_per_second: u64,
+    clock: ClockMapping,
+    state: Option<ChaseState>,
+}
+
+impl ChaseController {
+    /// Constructor for a local position with ticks_per_second ticks in a second
+    pub fn new(ticks_per_second: u64) -> Self {
+        Self {
+            ticks_per_second,
+            clock: ClockMapping::new(ticks_per_second),
+            state: None,
+        }
+    }
+
+    /// Call this every time the decoder returns a frame, with the local position at the sample that
+    /// completed the frame. Returns the smoothed offset and drift. None if the frame-rate is unknown
+    pub fn frame_received(&mut self, frame: &TimecodeFrame, local_position: u64) -> Option<ChaseState> {
+        self.clock.frame_received(frame, local_position);
+        let frame_rate = frame.frames_per_second.frame_rate()?;
+        let timecode_position = self.clock.position_at(local_position)?;
+        let local_frames = local_position as f64 * frame_rate.numerator() as f64 / (frame_rate.denominator() as f64 * self.ticks_per_second as f64);
+        let state = ChaseState {
+            offset_frames: timecode_position - local_frames,
+            drift: self.clock.speed()? - 1.0,
+        };
+        self.state = Some(state);
+        Some(state)
+    }
+
+    /// The last offset and drift. None if no frame was received
+    pub fn state(&self) -> Option<ChaseState> {
+        self.state
+    }
+
+    /// Speed for a varispeed resampler that compensates the drift and closes the offset within
+    /// correction_time_s seconds. 1.0 if no frame was received
+    pub fn varispeed_ratio(&self, correction_time_s: f64) -> f64 {
+        let (Some(state), Some(frame_duration_ticks)) = (self.state, self.clock.last_frame_duration_ticks()) else {
+            return 1.0;
+        };
+        let frames_per_second = self.ticks_per_second as f64 / frame_duration_ticks;
+        1.0 + state.drift + state.offset_frames / (correction_time_s * frames_per_second)
+    }
+
+    /// Forgets all received frames, e.g. after the local transport was relocated
+    pub fn reset(&mut self) {
+        self.clock.reset();
+        self.state = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chase_controller::ChaseController;
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_offset() {
+        let mut controller = ChaseController::new(48_000);
+        assert_eq!(controller.varispeed_ratio(1.0), 1.0);
+        let mut frame = TimecodeFrame::new(0, 0, 10, 0, TwentyFive);
+        // The local transport is one frame behind. The frame completes at 64/80 of its duration
+        let mut local_position = 9 * 48_000 + 24 * 1920 + 1536;
+        let mut state = None;
+        for _ in 0..50 {
+            state = controller.frame_received(&frame, local_position);
+            frame.add_frame();
+            local_position += 1920;
+        }
+        let state = state.unwrap();
+        assert!((state.offset_frames - 1.0).abs() < 1e-6);
+        assert!(state.drift.abs() < 1e-9);
+        assert!((controller.varispeed_ratio(2.0) - 1.02).abs() < 1e-6);
+        controller.reset();
+        assert!(controller.state().is_none());
+    }
+
+    #[test]
+    fn test_drift() {
+        let mut controller = ChaseController::new(48_000);
+        let mut frame = TimecodeFrame::new(1, 0, 0, 0, TwentyFive);
+        // The timecode runs 0.05% slower than the local clock
+        let frame_ticks = 1920.0 * 1.0005;
+        let start = 3600 * 48_000;
+        for i in 0..2000 {
+            controller.frame_received(&frame, start + (i as f64 * frame_ticks) as u64);
+            frame.add_frame();
+        }
+        let state = controller.state().unwrap();
+        assert!((state.drift + 0.0005).abs() < 0.0001);
+        assert!(state.offset_frames < 0.0);
+    }
+}
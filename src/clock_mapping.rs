@@ -0,0 +1,244 @@
+//! Maps decoded frames to a local clock, so the current timecode can be told at any time
+
+use crate::TimecodeFrame;
+
+/// A frame is decoded after its 64 data bits, which is 64/80 of the frame duration
+const FRAME_COMPLETE_FRACTION: f64 = 64.0 / 80.0;
+/// Frames that are further off the smoothed mapping restart the mapping
+const MAX_ERROR_FRAMES: f64 = 2.0;
+/// Gains of the delay-locked loop that smooths the jitter of the local clock. They result from a
+/// bandwidth of 0.02 times the frame-rate
+const POSITION_GAIN: f64 = 0.178;
+const RATE_GAIN: f64 = 0.0158;
+
+/// Associates decoded frames with the ticks of a local clock and smooths the jitter of the ticks.
+/// Ticks are caller-supplied (e.g. from a hardware timer), so this works without std
+#[derive(Clone)]
+pub struct ClockMapping {
+    ticks_per_second: u64,
+    /// The frame-rate of the last frame. Position and rate are only valid for this frame-rate
+    last_frame: Option<TimecodeFrame>,
+    /// Smoothed position (frames since midnight) at the tick of the last frame
+    anchor_position: f64,
+    anchor_tick: u64,
+    /// Smoothed speed of the timecode in frames per tick
+    frames_per_tick: f64,
+    /// Tick and unsmoothed position of the first frame since the mapping restarted. The drift is
+    /// measured from there, so the jitter of single frames averages out over time
+    drift_reference: (u64, f64),
+    /// Unsmoothed position of the last frame
+    last_position: f64,
+}
+
+impl ClockMapping {
+    /// Constructor for a clock with ticks_per_second ticks in a second
+    pub fn new(ticks_per_second: u64) -> Self {
+        Self {
+            ticks_per_second,
+            last_frame: None,
+            anchor_position: 0.0,
+            anchor_tick: 0,
+            frames_per_tick: 0.0,
+            drift_reference: (0, 0.0),
+            last_position: 0.0,
+        }
+    }
+
+    /// Call this every time the decoder returns a frame, with the tick of the sample that completed
+    /// the frame. Frames with unknown frame-rate are ignored
+    pub fn frame_received(&mut self, frame: &TimecodeFrame, tick: u64) {
+        let (Some(frame_count), Some(frame_rate)) = (frame.to_frame_count(), frame.frames_per_second.frame_rate()) else {
+            return;
+        };
+        let position = frame_count as f64 + FRAME_COMPLETE_FRACTION;
+        match self.position_at(tick) {
+            Some(predicted) if self.is_same_frame_rate(frame) && tick > self.anchor_tick && (position - predicted).abs() < MAX_ERROR_FRAMES => {
+                let error = position - predicted;
+                self.frames_per_tick += RATE_GAIN * error / (tick - self.anchor_tick) as f64;
+                self.anchor_position = predicted + POSITION_GAIN * error;
+            }
+            _ => {
+                // First frame, jump or frame-rate change
+                self.frames_per_tick = frame_rate.numerator() as f64 / (frame_rate.denominator() as f64 * self.ticks_per_second as f64);
+                self.anchor_position = position;
+                self.drift_reference = (tick, position);
+            }
+        }
+        self.last_position = position;
+        self.anchor_tick = tick;
+        self.last_frame = Some(frame.clone());
+    }
+
+    /// Frames since midnight (with fraction) at a tick. None if no frame was received
+    pub fn position_at(&self, tick: u64) -> Option<f64> {
+        self.last_frame.as_ref()?;
+        Some(self.anchor_position + (tick as f64 - self.anchor_tick as f64) * self.frames_per_tick)
+    }
+
+    /// The timecode at a tick and how far (0.0..1.0) the tick is into the frame. None if no frame
+    /// was received
+    pub fn timecode_at(&self, tick: u64) -> Option<(TimecodeFrame, f32)> {
+        let last_frame = self.last_frame.as_ref()?;
+        let frames_per_day = last_frame.frames_per_second.frames_per_day()? as f64;
+        let position = self.position_at(tick)? % frames_per_day;
+        let position = if position < 0.0 { position + frames_per_day } else { position };
+        let frame = TimecodeFrame::from_frame_count(position as u32, last_frame.frames_per_second.clone())?;
+        Some((frame, (position - (position as u32) as f64) as f32))
+    }
+
+    /// Speed of the timecode relative to the local clock, 1.0 if both run at the same speed. None if
+    /// no frame was received
+    pub fn speed(&self) -> Option<f64> {
+        let frame_rate = self.last_frame.as_ref()?.frames_per_second.frame_rate()?;
+        let nominal_frames_per_tick = frame_rate.numerator() as f64 / (frame_rate.denominator() as f64 * self.ticks_per_second as f64);
+        Some(self.frames_per_tick / nominal_frames_per_tick)
+    }
+
+    /// Long-term deviation of the timecode from its nominal speed relative to the local clock in
+    /// parts per million, measured since the first frame after the last jump. Positive if the
+    /// timecode runs faster. None if the frames of less than a second were received
+    pub fn drift_ppm(&self) -> Option<f64> {
+        let frame_rate = self.last_frame.as_ref()?.frames_per_second.frame_rate()?;
+        let (reference_tick, reference_position) = self.drift_reference;
+        let ticks = self.anchor_tick - reference_tick;
+        if ticks < self.ticks_per_second {
+            return None;
+        }
+        let nominal_frames = ticks as f64 * frame_rate.numerator() as f64 / (frame_rate.denominator() as f64 * self.ticks_per_second as f64);
+        Some(((self.last_position - reference_position) / nominal_frames - 1.0) * 1_000_000.0)
+    }
+
+    /// The last received frame with known frame-rate
+    pub fn last_frame(&self) -> Option<&TimecodeFrame> {
+        self.last_frame.as_ref()
+    }
+
+    /// The tick of the last received frame. None if no frame was received
+    pub fn last_frame_tick(&self) -> Option<u64> {
+        self.last_frame.as_ref().map(|_| self.anchor_tick)
+    }
+
+    /// The smoothed duration of a frame in ticks. None if no frame was received
+    pub fn last_frame_duration_ticks(&self) -> Option<f64> {
+        self.last_frame.as_ref()?;
+        Some(1.0 / self.frames_per_tick)
+    }
+
+    /// Forgets all received frames, e.g. after the signal got lost
+    pub fn reset(&mut self) {
+        self.last_frame = None;
+    }
+
+    fn is_same_frame_rate(&self, frame: &TimecodeFrame) -> bool {
+        self.last_frame.as_ref().map(|last| last.frames_per_second == frame.frames_per_second).unwrap_or(false)
+    }
+}
+
+/// ClockMapping that uses the monotonic clock of the host
+#[cfg(feature = "std")]
+pub struct InstantClockMapping {
+    origin: std::time::Instant,
+    mapping: ClockMapping,
+}
+
+#[cfg(feature = "std")]
+impl InstantClockMapping {
+    const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+    pub fn new() -> Self {
+        Self {
+            origin: std::time::Instant::now(),
+            mapping: ClockMapping::new(Self::NANOS_PER_SECOND),
+        }
+    }
+    /// Call this every time the decoder returns a frame, with the time the frame was completed
+    pub fn frame_received(&mut self, frame: &TimecodeFrame, instant: std::time::Instant) {
+        self.mapping.frame_received(frame, self.tick(instant));
+    }
+    /// The timecode at an instant and how far (0.0..1.0) the instant is into the frame
+    pub fn timecode_at(&self, instant: std::time::Instant) -> Option<(TimecodeFrame, f32)> {
+        self.mapping.timecode_at(self.tick(instant))
+    }
+    /// The timecode right now
+    pub fn now(&self) -> Option<(TimecodeFrame, f32)> {
+        self.timecode_at(std::time::Instant::now())
+    }
+    /// The underlying mapping with nanoseconds since creation as ticks
+    pub fn mapping(&self) -> &ClockMapping {
+        &self.mapping
+    }
+    pub fn reset(&mut self) {
+        self.mapping.reset();
+    }
+    fn tick(&self, instant: std::time::Instant) -> u64 {
+        instant.saturating_duration_since(self.origin).as_nanos() as u64
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for InstantClockMapping {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    use crate::clock_mapping::ClockMapping;
+    use crate::FramesPerSecond::{Thirty, TwentyFive, Unknown};
+    use crate::TimecodeFrame;
+
+    /// Ticks in microseconds
+    const TICKS_PER_SECOND: u64 = 1_000_000;
+
+    #[test]
+    fn test_exact_ticks() {
+        let mut mapping = ClockMapping::new(TICKS_PER_SECOND);
+        assert!(mapping.timecode_at(0).is_none());
+        let mut frame = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        for i in 0..100 {
+            mapping.frame_received(&frame, 32_000 + i * 40_000);
+            frame.add_frame();
+        }
+        let (timecode, sub_frame) = mapping.timecode_at(4_010_000).unwrap();
+        assert_eq!(timecode, TimecodeFrame::new(10, 0, 4, 0, TwentyFive));
+        assert!((sub_frame - 0.25).abs() < 0.001);
+        assert!((mapping.speed().unwrap() - 1.0).abs() < 1e-9);
+        assert!(mapping.drift_ppm().unwrap().abs() < 1e-6);
+        assert_eq!(mapping.last_frame_tick(), Some(32_000 + 99 * 40_000));
+    }
+
+    #[test]
+    fn test_jitter_and_speed() {
+        let mut mapping = ClockMapping::new(TICKS_PER_SECOND);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut frame = TimecodeFrame::new(1, 0, 0, 0, Thirty);
+        // The timecode runs 0.1% faster than the local clock
+        let frame_ticks = 1_000_000.0 / 30.0 / 1.001;
+        for i in 0..3000 {
+            let tick = (i as f64 * frame_ticks) as u64 + rng.gen_range(0..3000);
+            mapping.frame_received(&frame, tick);
+            frame.add_frame();
+        }
+        assert!((mapping.speed().unwrap() - 1.001).abs() < 0.0002);
+        assert!((mapping.drift_ppm().unwrap() - 1000.0).abs() < 10.0, "{:?}", mapping.drift_ppm());
+        let exact = mapping.position_at((3000.0 * frame_ticks) as u64).unwrap() - (3000.0 + 0.8 + 30.0 * 3600.0);
+        assert!(exact.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_jump_resets() {
+        let mut mapping = ClockMapping::new(TICKS_PER_SECOND);
+        mapping.frame_received(&TimecodeFrame::new(1, 0, 0, 0, TwentyFive), 0);
+        mapping.frame_received(&TimecodeFrame::new(2, 0, 0, 0, TwentyFive), 40_000);
+        assert_eq!(mapping.drift_ppm(), None);
+        assert_eq!(mapping.timecode_at(40_000).unwrap().0, TimecodeFrame::new(2, 0, 0, 0, TwentyFive));
+        mapping.frame_received(&TimecodeFrame::new(3, 0, 0, 0, Unknown), 80_000);
+        assert_eq!(mapping.timecode_at(80_000).unwrap().0, TimecodeFrame::new(2, 0, 0, 1, TwentyFive));
+        mapping.reset();
+        assert!(mapping.timecode_at(80_000).is_none());
+    }
+}
@@ -0,0 +1,128 @@
+//! Decodes timecode live from an audio input device via cpal
+
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc::Receiver;
+
+use cpal::{Device, InputCallbackInfo, SampleFormat, Stream, StreamConfig};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+use crate::ltc_decoder::{float_to_int_sample, LtcDecoder};
+use crate::TimecodeFrame;
+
+/// Errors that may occur when opening an audio input for decoding timecode
+#[derive(Debug)]
+pub enum CpalInputError {
+    /// The host has no default input device
+    NoInputDevice,
+    /// The selected channel does not exist on the device
+    ChannelOutOfRange { channel: u16, channel_count: u16 },
+    /// Only f32 and i16 samples are supported
+    UnsupportedSampleFormat(SampleFormat),
+    /// The default config of the device could not be read
+    DefaultStreamConfig(cpal::DefaultStreamConfigError),
+    /// The input stream could not be built
+    BuildStream(cpal::BuildStreamError),
+    /// The input stream could not be started
+    PlayStream(cpal::PlayStreamError),
+}
+
+impl Display for CpalInputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CpalInputError::NoInputDevice => write!(f, "No input device available"),
+            CpalInputError::ChannelOutOfRange { channel, channel_count } => write!(f, "Channel {} selected but the device only has {} channels", channel, channel_count),
+            CpalInputError::UnsupportedSampleFormat(format) => write!(f, "Unsupported sample format {}", format),
+            CpalInputError::DefaultStreamConfig(e) => write!(f, "Could not read input config: {}", e),
+            CpalInputError::BuildStream(e) => write!(f, "Could not build input stream: {}", e),
+            CpalInputError::PlayStream(e) => write!(f, "Could not start input stream: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CpalInputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CpalInputError::DefaultStreamConfig(e) => Some(e),
+            CpalInputError::BuildStream(e) => Some(e),
+            CpalInputError::PlayStream(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A running input stream that decodes timecode of one channel. The stream stops when this is dropped
+pub struct LtcInputStream {
+    stream: Stream,
+}
+
+impl LtcInputStream {
+    /// Opens the default input device of the default host and calls `on_frame` (on the audio
+    /// thread) for every decoded frame
+    pub fn open_default<F>(channel: u16, on_frame: F) -> Result<Self, CpalInputError>
+        where F: FnMut(TimecodeFrame) + Send + 'static {
+        let device = cpal::default_host().default_input_device().ok_or(CpalInputError::NoInputDevice)?;
+        Self::open(&device, channel, on_frame)
+    }
+
+    /// Same as open_default, but delivers the frames through a channel instead of a callback
+    pub fn open_default_with_receiver(channel: u16) -> Result<(Self, Receiver<TimecodeFrame>), CpalInputError> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let stream = Self::open_default(channel, move |frame| {
+            // The receiver may be dropped while the stream is running
+            let _ = sender.send(frame);
+        })?;
+        Ok((stream, receiver))
+    }
+
+    /// Opens a device with its default input config and calls `on_frame` (on the audio thread)
+    /// for every decoded frame of the selected channel (0 is the first channel)
+    pub fn open<F>(device: &Device, channel: u16, mut on_frame: F) -> Result<Self, CpalInputError>
+        where F: FnMut(TimecodeFrame) + Send + 'static {
+        let supported_config = device.default_input_config().map_err(CpalInputError::DefaultStreamConfig)?;
+        let sample_format = supported_config.sample_format();
+        let config: StreamConfig = supported_config.into();
+        if channel >= config.channels {
+            return Err(CpalInputError::ChannelOutOfRange { channel, channel_count: config.channels });
+        }
+        let channel = channel as usize;
+        let channel_count = config.channels as usize;
+        let error_callback = |_| {
+            // Stream errors show up as missing frames to the user
+        };
+        let stream = match sample_format {
+            SampleFormat::F32 => {
+                let mut decoder = LtcDecoder::<i32>::new(config.sample_rate.0);
+                device.build_input_stream(&config, move |data: &[f32], _: &InputCallbackInfo| {
+                    for sample in data.iter().skip(channel).step_by(channel_count) {
+                        if let Some(frame) = decoder.get_timecode_frame(float_to_int_sample(*sample)) {
+                            on_frame(frame);
+                        }
+                    }
+                }, error_callback, None)
+            }
+            SampleFormat::I16 => {
+                let mut decoder = LtcDecoder::<i16>::new(config.sample_rate.0);
+                device.build_input_stream(&config, move |data: &[i16], _: &InputCallbackInfo| {
+                    for sample in data.iter().skip(channel).step_by(channel_count) {
+                        if let Some(frame) = decoder.get_timecode_frame(*sample) {
+                            on_frame(frame);
+                        }
+                    }
+                }, error_callback, None)
+            }
+            format => return Err(CpalInputError::UnsupportedSampleFormat(format)),
+        }.map_err(CpalInputError::BuildStream)?;
+        stream.play().map_err(CpalInputError::PlayStream)?;
+        Ok(Self { stream })
+    }
+
+    /// Pauses decoding. Not all hosts support pausing
+    pub fn pause(&self) -> Result<(), cpal::PauseStreamError> {
+        self.stream.pause()
+    }
+
+    /// Resumes decoding after pause
+    pub fn play(&self) -> Result<(), CpalInputError> {
+        self.stream.play().map_err(CpalInputError::PlayStream)
+    }
+}
@@ -0,0 +1,127 @@
+//! Decodes many LTC feeds at once, e.g. in trucks and master control rooms that monitor every
+//! incoming feed
+
+use crate::ltc_decoder::{CallbackSink, DecoderStats, LtcDecoder, LtcDecoderConfig, Sample, TimecodeSink};
+use crate::{SyncState, TimecodeFrame};
+
+/// Status of an input of a `DecoderPool`
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct InputStatus {
+    /// The timecode at the last pushed sample, see `LtcDecoder::extrapolated_now`
+    pub timecode: Option<TimecodeFrame>,
+    pub stats: DecoderStats,
+}
+
+/// Runs a decoder per input, where the inputs are independent feeds that may carry different
+/// timecode. Unlike `channel_selector::ChannelSelectingDecoder`, the frames of every input are
+/// returned
+pub struct DecoderPool<T: Sample, const N: usize> {
+    decoders: [LtcDecoder<T>; N],
+}
+
+impl<T: Sample, const N: usize> DecoderPool<T, N> {
+    pub fn new(sampling_rate: u32) -> Self {
+        Self::new_with_config(sampling_rate, LtcDecoderConfig::default())
+    }
+    pub fn new_with_config(sampling_rate: u32, config: LtcDecoderConfig) -> Self {
+        Self {
+            decoders: core::array::from_fn(|_| LtcDecoder::new_with_config(sampling_rate, config.clone())),
+        }
+    }
+
+    /// Decodes a buffer with N interleaved channels, one per input, and passes the output of every
+    /// input to its sink. The inputs are decoded one after another, so the callbacks of an input
+    /// come before those of the next input
+    pub fn push_interleaved<S: TimecodeSink>(&mut self, buffer: &[T], sinks: &mut [S; N]) {
+        for (input, (decoder, sink)) in self.decoders.iter_mut().zip(sinks.iter_mut()).enumerate() {
+            decoder.push_samples_strided(buffer, N, input, sink);
+        }
+    }
+    /// Like push_interleaved, but calls a closure with the input (0 for the first) for every
+    /// decoded frame of all inputs
+    pub fn push_interleaved_with<F: FnMut(usize, &TimecodeFrame)>(&mut self, buffer: &[T], mut on_frame: F) {
+        for (input, decoder) in self.decoders.iter_mut().enumerate() {
+            decoder.push_samples_strided(buffer, N, input, &mut CallbackSink::new(|frame: &TimecodeFrame| on_frame(input, frame)));
+        }
+    }
+    /// Decodes a buffer of a single input, for sources that deliver a buffer per channel. Nothing
+    /// happens if the input does not exist
+    pub fn push_input<S: TimecodeSink>(&mut self, input: usize, samples: &[T], sink: &mut S) {
+        if let Some(decoder) = self.decoders.get_mut(input) {
+            decoder.push_samples(samples, sink);
+        }
+    }
+
+    /// The status of every input
+    pub fn status(&self) -> [InputStatus; N] {
+        core::array::from_fn(|input| {
+            let decoder = &self.decoders[input];
+            InputStatus {
+                timecode: decoder.extrapolated_now().map(|(frame, _)| frame),
+                stats: decoder.stats(),
+            }
+        })
+    }
+    /// Number of inputs that are locked
+    pub fn locked_count(&self) -> usize {
+        self.decoders.iter().filter(|decoder| decoder.sync_state() == SyncState::Locked).count()
+    }
+    /// The decoders with their input, e.g. to poll sync state changes of every input
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut LtcDecoder<T>)> {
+        self.decoders.iter_mut().enumerate()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &LtcDecoder<T>)> {
+        self.decoders.iter().enumerate()
+    }
+    /// None if the input does not exist
+    pub fn decoder(&self, input: usize) -> Option<&LtcDecoder<T>> {
+        self.decoders.get(input)
+    }
+    pub fn decoder_mut(&mut self, input: usize) -> Option<&mut LtcDecoder<T>> {
+        self.decoders.get_mut(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::{Thirty, TwentyFive};
+    use crate::decoder_pool::DecoderPool;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::CallbackSink;
+    use crate::{SyncState, TimecodeFrame};
+
+    #[test]
+    fn test_pool() {
+        let first = ltc_samples(&TimecodeFrame::new(1, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let second = ltc_samples(&TimecodeFrame::new(2, 0, 0, 0, Thirty), 12, &SignalShape::new(20.0));
+        // The third input is silent
+        let buffer: Vec<i32> = first.iter().zip(&second).flat_map(|(first, second)| [*first, *second, 0]).collect();
+        let mut pool = DecoderPool::<i32, 3>::new(48_000);
+        let mut frames = Vec::new();
+        for chunk in buffer.chunks(3 * 480) {
+            pool.push_interleaved_with(chunk, |input, frame| frames.push((input, frame.clone())));
+        }
+        assert_eq!(frames.iter().filter(|(input, _)| *input == 0).count(), 9);
+        assert_eq!(frames.iter().filter(|(input, _)| *input == 1).count(), 11);
+        assert!(frames.contains(&(1, TimecodeFrame::new(2, 0, 0, 11, Thirty))));
+        let status = pool.status();
+        assert_eq!(status[0].stats.sync_state, SyncState::Locked);
+        assert_eq!(status[1].stats.frames_decoded, 11);
+        assert_eq!(status[2].timecode, None);
+        assert_eq!(status[2].stats.sync_state, SyncState::Unlocked);
+        assert_eq!(pool.locked_count(), 2);
+    }
+
+    #[test]
+    fn test_push_input() {
+        let samples = ltc_samples(&TimecodeFrame::new(1, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut pool = DecoderPool::<i32, 2>::new(48_000);
+        let mut frames = Vec::new();
+        pool.push_input(1, &samples, &mut CallbackSink::new(|frame: &TimecodeFrame| frames.push(frame.clone())));
+        pool.push_input(2, &samples, &mut CallbackSink::new(|_: &TimecodeFrame| panic!()));
+        assert_eq!(frames.len(), 9);
+        assert_eq!(pool.decoder(0).map(|decoder| decoder.stats().frames_decoded), Some(0));
+        assert_eq!(pool.iter().map(|(_, decoder)| decoder.stats().frames_decoded).sum::<u64>(), 9);
+    }
+}
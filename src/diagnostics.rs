@@ -0,0 +1,538 @@
+//! Renders what the decoder saw, to find out why a recording does not decode
+
+use std::fmt::Write as _;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use crate::ltc_decoder::{DecodeErrorKind, DecoderMonitor, Sample};
+use crate::TimecodeFrame;
+
+/// A `DecoderMonitor` that records a window of samples and renders it as SVG with the threshold,
+/// the threshold-crosses, the decoded bits, the errors, the sync-words and the decoded frames
+pub struct AudioImage<T: Sample> {
+    start: u64,
+    width: usize,
+    /// Index of the last sample passed to on_sample
+    index: Option<u64>,
+    samples: Vec<T>,
+    /// The threshold valid from a sample index on. The threshold before the window is kept at the
+    /// start of the window
+    thresholds: Vec<(u64, T)>,
+    threshold_crosses: Vec<u64>,
+    bits: Vec<(u64, bool)>,
+    errors: Vec<(u64, DecodeErrorKind)>,
+    /// Where sync-words completed. The last one before the window is kept to label the first frame
+    sync_words: Vec<u64>,
+    frames: Vec<(u64, TimecodeFrame)>,
+}
+
+impl<T: Sample> AudioImage<T> {
+    /// SVG viewers struggle with wider images
+    pub const MAX_WIDTH: usize = 16_384;
+    const HEIGHT: usize = 200;
+    /// Space for the labels of the bits below the signal and the frames above the signal
+    const LABEL_HEIGHT: usize = 20;
+
+    /// Records `width` samples starting at the sample index `start`. The width is limited to
+    /// MAX_WIDTH
+    pub fn new(start: u64, width: usize) -> Self {
+        Self {
+            start,
+            width: width.min(Self::MAX_WIDTH),
+            index: None,
+            samples: Vec::new(),
+            thresholds: Vec::new(),
+            threshold_crosses: Vec::new(),
+            bits: Vec::new(),
+            errors: Vec::new(),
+            sync_words: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// The index of the first sample of the window
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Tells if all samples of the window have been recorded
+    pub fn is_complete(&self) -> bool {
+        self.samples.len() == self.width
+    }
+
+    /// Ends the window at the last recorded sample
+    fn close(&mut self) {
+        self.width = self.samples.len();
+    }
+
+    /// The window that follows this one. The current threshold and the last sync-word are carried
+    /// over, so the first frame of the next window is labeled as well
+    fn next_window(&self, width: usize) -> Self {
+        let start = self.start + self.samples.len() as u64;
+        let mut next = Self::new(start, width);
+        next.index = self.index;
+        next.thresholds.extend(self.thresholds.last().map(|(_, threshold)| (start, *threshold)));
+        next.sync_words.extend(self.sync_words.last());
+        next
+    }
+
+    /// Renders the recorded window. One sample is one pixel wide
+    pub fn to_svg(&self) -> String {
+        let values: Vec<f64> = self.samples.iter().map(|sample| sample.to_f64().unwrap_or(0.0)).collect();
+        let max = values.iter().cloned().fold(f64::MIN, f64::max);
+        let min = values.iter().cloned().fold(f64::MAX, f64::min);
+        let range = if max > min { max - min } else { 1.0 };
+        let top = Self::LABEL_HEIGHT;
+        let bottom = Self::LABEL_HEIGHT + Self::HEIGHT;
+        let y = |value: f64| (max - value) / range * (Self::HEIGHT - 10) as f64 + 5.0 + top as f64;
+        let x = |index: u64| (index - self.start) as f64;
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}">"#, self.samples.len().max(1), bottom + Self::LABEL_HEIGHT);
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+        for cross in &self.threshold_crosses {
+            let _ = writeln!(svg, r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="lightgray"/>"#, x(*cross), top, bottom);
+        }
+        let mut points = String::new();
+        for (i, value) in values.iter().enumerate() {
+            let _ = write!(points, "{},{:.1} ", i, y(*value));
+        }
+        let _ = writeln!(svg, r#"<polyline points="{}" fill="none" stroke="black"/>"#, points.trim_end());
+        for (i, (index, threshold)) in self.thresholds.iter().enumerate() {
+            let end = self.thresholds.get(i + 1).map(|(index, _)| *index).unwrap_or(self.start + self.samples.len() as u64);
+            let threshold = y(threshold.to_f64().unwrap_or(0.0));
+            let _ = writeln!(svg, r#"<line x1="{}" y1="{2:.1}" x2="{}" y2="{2:.1}" stroke="blue"/>"#, x(*index), x(end), threshold);
+        }
+        for (index, bit) in &self.bits {
+            let _ = writeln!(svg, r#"<text x="{}" y="{}" font-size="12" text-anchor="middle">{}</text>"#, x(*index), bottom + 15, *bit as u8);
+        }
+        for (index, kind) in &self.errors {
+            let _ = writeln!(svg, r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="red"><title>{3:?}</title></line>"#, x(*index), top, bottom, kind);
+        }
+        for sync_word in self.sync_words.iter().filter(|index| **index >= self.start) {
+            let _ = writeln!(svg, r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="green" stroke-width="2"/>"#, x(*sync_word), top, bottom);
+        }
+        for (index, frame) in &self.frames {
+            // The data of the frame follows the last sync-word
+            let data_start = self.sync_words.iter().rev().find(|sync_word| *sync_word < index).map(|sync_word| x((*sync_word).max(self.start))).unwrap_or(0.0);
+            let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="green" fill-opacity="0.1"/>"#, data_start, top, x(*index) - data_start, Self::HEIGHT);
+            let _ = writeln!(svg, r#"<text x="{}" y="15" font-size="12" text-anchor="middle">{}</text>"#, (data_start + x(*index)) / 2.0, frame);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Writes the SVG of to_svg to a file
+    pub fn write_svg<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
+
+    /// The index of the last sample if it is within the window
+    fn index_in_window(&self) -> Option<u64> {
+        self.index.filter(|index| *index >= self.start && *index < self.start + self.width as u64)
+    }
+}
+
+impl<T: Sample> DecoderMonitor<T> for AudioImage<T> {
+    fn on_sample(&mut self, sample: T) {
+        let index = self.index.map(|index| index + 1).unwrap_or(0);
+        self.index = Some(index);
+        if self.index_in_window().is_some() {
+            self.samples.push(sample);
+        }
+    }
+    fn on_threshold(&mut self, threshold: T) {
+        match self.index_in_window() {
+            Some(index) => self.thresholds.push((index, threshold)),
+            None if self.samples.is_empty() => self.thresholds = vec![(self.start, threshold)],
+            None => {}
+        }
+    }
+    fn on_threshold_cross(&mut self, _samples: usize) {
+        if let Some(index) = self.index_in_window() {
+            self.threshold_crosses.push(index);
+        }
+    }
+    fn on_bit(&mut self, bit: bool) {
+        if let Some(index) = self.index_in_window() {
+            self.bits.push((index, bit));
+        }
+    }
+    fn on_error(&mut self, kind: DecodeErrorKind) {
+        if let Some(index) = self.index_in_window() {
+            self.errors.push((index, kind));
+        }
+    }
+    fn on_sync_word(&mut self) {
+        match self.index_in_window() {
+            Some(index) => self.sync_words.push(index),
+            None if self.samples.is_empty() => self.sync_words = self.index.into_iter().collect(),
+            None => {}
+        }
+    }
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        if let Some(index) = self.index_in_window() {
+            self.frames.push((index, frame.clone()));
+        }
+    }
+}
+
+/// Where `PagedAudioImage` starts a new page
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PageBreak {
+    /// Every page has this number of samples, at most `AudioImage::MAX_WIDTH`
+    Samples(usize),
+    /// Every page ends with a decoded frame. Pages without frames end at `AudioImage::MAX_WIDTH`
+    Frame,
+}
+
+/// Records the whole signal as consecutive `AudioImage`s and passes every completed page to a
+/// closure, e.g. to write it to a file
+pub struct PagedAudioImage<T: Sample, F: FnMut(AudioImage<T>)> {
+    page_break: PageBreak,
+    page: AudioImage<T>,
+    on_page: F,
+}
+
+impl<T: Sample, F: FnMut(AudioImage<T>)> PagedAudioImage<T, F> {
+    pub fn new(page_break: PageBreak, on_page: F) -> Self {
+        let page_break = match page_break {
+            PageBreak::Samples(samples) => PageBreak::Samples(samples.clamp(1, AudioImage::<T>::MAX_WIDTH)),
+            PageBreak::Frame => PageBreak::Frame,
+        };
+        Self {
+            page_break,
+            page: AudioImage::new(0, Self::page_width(page_break)),
+            on_page,
+        }
+    }
+
+    /// Passes the last, incomplete page to the closure
+    pub fn finish(mut self) {
+        self.page.close();
+        if !self.page.samples.is_empty() {
+            (self.on_page)(self.page);
+        }
+    }
+
+    fn page_width(page_break: PageBreak) -> usize {
+        match page_break {
+            PageBreak::Samples(samples) => samples,
+            PageBreak::Frame => AudioImage::<T>::MAX_WIDTH,
+        }
+    }
+
+    fn next_page(&mut self) {
+        self.page.close();
+        let next = self.page.next_window(Self::page_width(self.page_break));
+        (self.on_page)(core::mem::replace(&mut self.page, next));
+    }
+}
+
+impl<T: Sample, F: FnMut(AudioImage<T>)> DecoderMonitor<T> for PagedAudioImage<T, F> {
+    fn on_sample(&mut self, sample: T) {
+        if self.page.is_complete() {
+            self.next_page();
+        }
+        self.page.on_sample(sample);
+    }
+    fn on_threshold(&mut self, threshold: T) {
+        self.page.on_threshold(threshold);
+    }
+    fn on_threshold_cross(&mut self, samples: usize) {
+        self.page.on_threshold_cross(samples);
+    }
+    fn on_bit(&mut self, bit: bool) {
+        self.page.on_bit(bit);
+    }
+    fn on_error(&mut self, kind: DecodeErrorKind) {
+        self.page.on_error(kind);
+    }
+    fn on_sync_word(&mut self) {
+        self.page.on_sync_word();
+    }
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        self.page.on_frame(frame);
+        if self.page_break == PageBreak::Frame {
+            self.page.close();
+        }
+    }
+}
+
+/// What the decoder did at a sample index
+#[derive(PartialEq, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum DiagnosticRecord<T: Sample> {
+    Threshold { index: u64, threshold: T },
+    /// `samples` is the length of the period since the last threshold-cross
+    ThresholdCross { index: u64, samples: usize },
+    Bit { index: u64, bit: bool },
+    Error { index: u64, kind: DecodeErrorKind },
+    SyncWord { index: u64 },
+    Frame { index: u64, frame: TimecodeFrame },
+}
+
+impl<T: Sample> DiagnosticRecord<T> {
+    /// The index of the sample at which the record was made
+    pub fn index(&self) -> u64 {
+        match self {
+            DiagnosticRecord::Threshold { index, .. }
+            | DiagnosticRecord::ThresholdCross { index, .. }
+            | DiagnosticRecord::Bit { index, .. }
+            | DiagnosticRecord::Error { index, .. }
+            | DiagnosticRecord::SyncWord { index }
+            | DiagnosticRecord::Frame { index, .. } => *index,
+        }
+    }
+}
+
+/// Receives the records of a `RecordMonitor`
+pub trait RecordTarget<T: Sample> {
+    fn record(&mut self, record: DiagnosticRecord<T>);
+}
+
+/// Records are dropped once the receiver is gone
+impl<T: Sample> RecordTarget<T> for std::sync::mpsc::Sender<DiagnosticRecord<T>> {
+    fn record(&mut self, record: DiagnosticRecord<T>) {
+        let _ = self.send(record);
+    }
+}
+
+/// A `DecoderMonitor` that passes a `DiagnosticRecord` for everything except the samples to a
+/// target
+pub struct RecordMonitor<T: Sample, R: RecordTarget<T>> {
+    target: R,
+    /// Number of samples passed to on_sample
+    index: u64,
+    _sample: PhantomData<T>,
+}
+
+/// Sends the records over a channel, e.g. to a GUI scope that visualizes the decoding live
+pub type ChannelMonitor<T> = RecordMonitor<T, std::sync::mpsc::Sender<DiagnosticRecord<T>>>;
+
+impl<T: Sample, R: RecordTarget<T>> RecordMonitor<T, R> {
+    pub fn new(target: R) -> Self {
+        Self { target, index: 0, _sample: PhantomData }
+    }
+
+    pub fn into_target(self) -> R {
+        self.target
+    }
+
+    /// The index of the last sample
+    fn last_index(&self) -> u64 {
+        self.index.saturating_sub(1)
+    }
+}
+
+impl<T: Sample, R: RecordTarget<T>> DecoderMonitor<T> for RecordMonitor<T, R> {
+    fn on_sample(&mut self, _sample: T) {
+        self.index += 1;
+    }
+    fn on_threshold(&mut self, threshold: T) {
+        self.target.record(DiagnosticRecord::Threshold { index: self.last_index(), threshold });
+    }
+    fn on_threshold_cross(&mut self, samples: usize) {
+        self.target.record(DiagnosticRecord::ThresholdCross { index: self.last_index(), samples });
+    }
+    fn on_bit(&mut self, bit: bool) {
+        self.target.record(DiagnosticRecord::Bit { index: self.last_index(), bit });
+    }
+    fn on_error(&mut self, kind: DecodeErrorKind) {
+        self.target.record(DiagnosticRecord::Error { index: self.last_index(), kind });
+    }
+    fn on_sync_word(&mut self) {
+        self.target.record(DiagnosticRecord::SyncWord { index: self.last_index() });
+    }
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        self.target.record(DiagnosticRecord::Frame { index: self.last_index(), frame: frame.clone() });
+    }
+}
+
+/// File format of `DiagnosticExporter`
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    /// One row per record with the columns index, event and value
+    Csv,
+    /// An array of objects with the keys index, event and value
+    Json,
+}
+
+/// Writes records as CSV or JSON, e.g. for analysis in Python or a spreadsheet. Use it as target
+/// of a `RecordMonitor` or write records received from a `ChannelMonitor`
+pub struct DiagnosticExporter<W: Write> {
+    writer: W,
+    format: ExportFormat,
+    records_written: u64,
+    /// The first error while writing as RecordTarget. It is returned by finish
+    error: Option<std::io::Error>,
+}
+
+impl<W: Write> DiagnosticExporter<W> {
+    /// Writes the CSV header or the start of the JSON array
+    pub fn new(mut writer: W, format: ExportFormat) -> std::io::Result<Self> {
+        match format {
+            ExportFormat::Csv => writeln!(writer, "index,event,value")?,
+            ExportFormat::Json => writeln!(writer, "[")?,
+        }
+        Ok(Self { writer, format, records_written: 0, error: None })
+    }
+
+    pub fn write_record<T: Sample>(&mut self, record: &DiagnosticRecord<T>) -> std::io::Result<()> {
+        let (event, value) = match record {
+            DiagnosticRecord::Threshold { threshold, .. } => ("threshold", Some(threshold.to_string())),
+            DiagnosticRecord::ThresholdCross { samples, .. } => ("threshold_cross", Some(samples.to_string())),
+            DiagnosticRecord::Bit { bit, .. } => ("bit", Some((*bit as u8).to_string())),
+            DiagnosticRecord::Error { kind, .. } => ("error", Some(format!("{:?}", kind))),
+            DiagnosticRecord::SyncWord { .. } => ("sync_word", None),
+            DiagnosticRecord::Frame { frame, .. } => ("frame", Some(frame.to_string())),
+        };
+        match self.format {
+            ExportFormat::Csv => writeln!(self.writer, "{},{},{}", record.index(), event, value.unwrap_or_default())?,
+            ExportFormat::Json => {
+                let separator = if self.records_written == 0 { "" } else { ",\n" };
+                let value = match (value, record) {
+                    (Some(text), DiagnosticRecord::Frame { .. } | DiagnosticRecord::Error { .. }) => format!("\"{}\"", text),
+                    (Some(number), _) => number,
+                    (None, _) => "null".to_string(),
+                };
+                write!(self.writer, r#"{}{{"index":{},"event":"{}","value":{}}}"#, separator, record.index(), event, value)?
+            }
+        }
+        self.records_written += 1;
+        Ok(())
+    }
+
+    /// Ends the JSON array and returns the writer. Fails with the first error that occurred while
+    /// writing as RecordTarget
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if self.format == ExportFormat::Json {
+            writeln!(self.writer, "\n]")?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<T: Sample, W: Write> RecordTarget<T> for DiagnosticExporter<W> {
+    fn record(&mut self, record: DiagnosticRecord<T>) {
+        if self.error.is_none() {
+            self.error = self.write_record(&record).err();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::{AudioImage, ChannelMonitor, DiagnosticExporter, DiagnosticRecord, ExportFormat, PageBreak, PagedAudioImage, RecordMonitor};
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig};
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_audio_image() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), AudioImage::new(2000, 3000));
+        for sample in samples {
+            decoder.get_timecode_frame(sample);
+        }
+        let image = decoder.monitor();
+        assert!(image.is_complete());
+        let svg = image.to_svg();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        // A frame of 1920 samples has 80 bits, so the window contains 125 bits
+        let bits = svg.matches(r#"y="235""#).count();
+        assert!((124..=126).contains(&bits), "{} bits", bits);
+        assert!(svg.contains(r#"stroke="blue""#));
+        assert!(!svg.contains(r#"stroke="red""#));
+        // The data of the frame decoded at 3456 starts at 1920, before the window. The next
+        // sync-word completes at 3840
+        assert_eq!(svg.matches(r#"stroke="green""#).count(), 1);
+        assert!(svg.contains(r#"<text x="728" y="15" font-size="12" text-anchor="middle">10:00:00:01</text>"#));
+        assert_eq!(AudioImage::<i32>::new(0, 100_000).width, AudioImage::<i32>::MAX_WIDTH);
+    }
+
+    #[test]
+    fn test_paged_audio_image() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let mut pages = Vec::new();
+        let mut monitor = PagedAudioImage::new(PageBreak::Samples(1000), |page: AudioImage<i32>| pages.push(page));
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), &mut monitor);
+        for sample in &samples {
+            decoder.get_timecode_frame(*sample);
+        }
+        monitor.finish();
+        assert_eq!(pages.len(), samples.len().div_ceil(1000));
+        assert!(pages.iter().enumerate().all(|(i, page)| page.start() == i as u64 * 1000));
+        assert_eq!(pages.last().unwrap().samples.len(), samples.len() % 1000);
+    }
+
+    #[test]
+    fn test_audio_image_per_frame() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let mut pages = Vec::new();
+        let mut monitor = PagedAudioImage::new(PageBreak::Frame, |page: AudioImage<i32>| pages.push(page));
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), &mut monitor);
+        for sample in &samples {
+            decoder.get_timecode_frame(*sample);
+        }
+        monitor.finish();
+        // Four frames are decoded, the last page has no frame
+        assert_eq!(pages.len(), 5);
+        assert_eq!(pages[0].start(), 0);
+        assert_eq!(pages[1].start(), 3457);
+        assert_eq!(pages[2].start(), 3457 + 1920);
+        for (i, page) in pages.iter().take(4).enumerate() {
+            let label = format!(">10:00:00:{:02}</text>", i + 1);
+            assert!(page.to_svg().contains(&label), "{}", label);
+        }
+        assert_eq!(pages.iter().map(|page| page.samples.len()).sum::<usize>(), samples.len());
+    }
+
+    #[test]
+    fn test_channel_monitor() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 3, &SignalShape::new(24.0));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), ChannelMonitor::new(sender));
+        for sample in &samples {
+            decoder.get_timecode_frame(*sample);
+        }
+        drop(decoder);
+        let records: Vec<DiagnosticRecord<i32>> = receiver.iter().collect();
+        assert!(records.windows(2).all(|records| records[0].index() <= records[1].index()));
+        let frames: Vec<&DiagnosticRecord<i32>> = records.iter().filter(|record| matches!(record, DiagnosticRecord::Frame { .. })).collect();
+        assert_eq!(frames, [&DiagnosticRecord::Frame { index: 3456, frame: TimecodeFrame::new(10, 0, 0, 1, TwentyFive) }, &DiagnosticRecord::Frame { index: 5376, frame: TimecodeFrame::new(10, 0, 0, 2, TwentyFive) }]);
+        assert!(records.iter().filter(|record| matches!(record, DiagnosticRecord::Bit { .. })).count() > 150);
+    }
+
+    #[test]
+    fn test_export() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 3, &SignalShape::new(24.0));
+        for format in [ExportFormat::Csv, ExportFormat::Json] {
+            let mut monitor = RecordMonitor::new(DiagnosticExporter::new(Vec::new(), format).unwrap());
+            let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), &mut monitor);
+            for sample in &samples {
+                decoder.get_timecode_frame(*sample);
+            }
+            let output = String::from_utf8(monitor.into_target().finish().unwrap()).unwrap();
+            match format {
+                ExportFormat::Csv => {
+                    assert!(output.starts_with("index,event,value\n"));
+                    assert!(output.contains("\n3456,frame,10:00:00:01\n"));
+                    assert!(output.lines().skip(1).all(|line| line.split(',').count() == 3));
+                }
+                ExportFormat::Json => {
+                    assert!(output.starts_with("[\n{") && output.ends_with("}\n]\n"));
+                    assert!(output.contains(r#"{"index":3456,"event":"frame","value":"10:00:00:01"}"#));
+                    assert!(output.contains(r#""event":"sync_word","value":null}"#));
+                    assert!(output.contains(r#""event":"bit","value":1}"#));
+                }
+            }
+        }
+    }
+}
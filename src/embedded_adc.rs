@@ -0,0 +1,195 @@
+//! Adapter that reads samples from an embedded-hal ADC channel and decodes them
+
+use core::marker::PhantomData;
+
+use embedded_hal::adc::{Channel, OneShot};
+
+use crate::ltc_decoder::{LtcDecoder, Sample};
+use crate::TimecodeFrame;
+
+/// The sample rate the ADC is read at. LTC at 30fps has half-bits of 1/4800s, which need to be
+/// sampled a few times to tell half-bits and bits apart
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SamplingCadence {
+    sample_rate_hz: u32,
+}
+
+/// The sample rate is too low to decode LTC reliably
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SampleRateTooLow {
+    pub sample_rate_hz: u32,
+}
+
+impl SamplingCadence {
+    /// Lowest sample rate that is accepted. Gives about 4 samples per half-bit at 30fps
+    pub const MIN_SAMPLE_RATE_HZ: u32 = 19_200;
+    /// Sample rate that gives enough samples per half-bit to cope with jitter of the timer
+    pub const RECOMMENDED_SAMPLE_RATE_HZ: u32 = 48_000;
+
+    /// Constructor. Fails if the sample rate is too low for decoding LTC
+    pub const fn new(sample_rate_hz: u32) -> Result<Self, SampleRateTooLow> {
+        if sample_rate_hz < Self::MIN_SAMPLE_RATE_HZ {
+            return Err(SampleRateTooLow { sample_rate_hz });
+        }
+        Ok(Self { sample_rate_hz })
+    }
+    /// The recommended cadence
+    pub const fn recommended() -> Self {
+        Self { sample_rate_hz: Self::RECOMMENDED_SAMPLE_RATE_HZ }
+    }
+    /// The sample rate in Hz
+    pub const fn sample_rate_hz(&self) -> u32 {
+        self.sample_rate_hz
+    }
+    /// The period between two samples in nanoseconds (rounded)
+    pub const fn period_ns(&self) -> u32 {
+        (1_000_000_000 + self.sample_rate_hz / 2) / self.sample_rate_hz
+    }
+    /// The number of timer ticks between two samples for a timer running at `timer_clock_hz`.
+    /// The actual sample rate differs from the requested one if the timer clock is not a multiple
+    /// of it, which is why the decoder should be created with `actual_sample_rate_hz`.
+    /// Returns None if the timer is too slow to tick at least once per sample
+    pub const fn timer_ticks(&self, timer_clock_hz: u32) -> Option<u32> {
+        let sample_rate_hz = self.sample_rate_hz as u64;
+        if sample_rate_hz == 0 {
+            return None;
+        }
+        // Rounded, in u64 so timer clocks close to u32::MAX don't overflow
+        let ticks = (timer_clock_hz as u64 + sample_rate_hz / 2) / sample_rate_hz;
+        if ticks == 0 || ticks > u32::MAX as u64 {
+            return None;
+        }
+        Some(ticks as u32)
+    }
+    /// The sample rate a timer running at `timer_clock_hz` actually produces with `timer_ticks`.
+    /// Returns None if the timer is too slow to tick at least once per sample
+    pub const fn actual_sample_rate_hz(&self, timer_clock_hz: u32) -> Option<u32> {
+        match self.timer_ticks(timer_clock_hz) {
+            Some(ticks) => Some(timer_clock_hz / ticks),
+            None => None,
+        }
+    }
+}
+
+/// Reads one ADC channel and feeds the samples into a LtcDecoder. `poll` needs to be called at
+/// the rate of the SamplingCadence, e.g. from a timer interrupt
+pub struct AdcLtcReader<ADC, Word, PIN, A>
+    where Word: Sample, PIN: Channel<ADC>, A: OneShot<ADC, Word, PIN> {
+    adc: A,
+    pin: PIN,
+    decoder: LtcDecoder<Word>,
+    _adc: PhantomData<ADC>,
+}
+
+impl<ADC, Word, PIN, A> AdcLtcReader<ADC, Word, PIN, A>
+    where Word: Sample, PIN: Channel<ADC>, A: OneShot<ADC, Word, PIN> {
+    /// Constructor. The cadence has to match the rate `poll` is called at
+    pub fn new(adc: A, pin: PIN, cadence: SamplingCadence) -> Self {
+        Self {
+            adc,
+            pin,
+            decoder: LtcDecoder::new(cadence.sample_rate_hz()),
+            _adc: PhantomData,
+        }
+    }
+    /// Reads one sample from the ADC and decodes it. Returns `nb::Error::WouldBlock` while the
+    /// conversion is not finished, in which case poll has to be called again before the next
+    /// sample is due
+    pub fn poll(&mut self) -> nb::Result<Option<TimecodeFrame>, A::Error> {
+        let sample = self.adc.read(&mut self.pin)?;
+        Ok(self.decoder.get_timecode_frame(sample))
+    }
+    /// Returns the ADC and the pin
+    pub fn release(self) -> (A, PIN) {
+        (self.adc, self.pin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal::adc::{Channel, OneShot};
+
+    use crate::embedded_adc::{AdcLtcReader, SamplingCadence, SampleRateTooLow};
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::TimecodeFrame;
+
+    struct MockAdc {
+        samples: Vec<u16>,
+        index: usize,
+        busy: bool,
+    }
+
+    struct MockPin;
+
+    impl Channel<MockAdc> for MockPin {
+        type ID = u8;
+
+        fn channel() -> u8 {
+            0
+        }
+    }
+
+    impl OneShot<MockAdc, u16, MockPin> for MockAdc {
+        type Error = ();
+
+        fn read(&mut self, _pin: &mut MockPin) -> nb::Result<u16, ()> {
+            // Every conversion needs two reads
+            self.busy = !self.busy;
+            if self.busy {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.index += 1;
+            self.samples.get(self.index - 1).copied().ok_or(nb::Error::Other(()))
+        }
+    }
+
+    #[test]
+    fn test_sampling_cadence() {
+        assert_eq!(SamplingCadence::new(8_000), Err(SampleRateTooLow { sample_rate_hz: 8_000 }));
+        let cadence = SamplingCadence::recommended();
+        assert_eq!(cadence.period_ns(), 20_833);
+        assert_eq!(cadence.timer_ticks(72_000_000), Some(1_500));
+        assert_eq!(cadence.actual_sample_rate_hz(72_000_000), Some(48_000));
+        assert_eq!(cadence.actual_sample_rate_hz(80_000_000), Some(47_990));
+    }
+
+    #[test]
+    fn test_sampling_cadence_edge_cases() {
+        let cadence = SamplingCadence::recommended();
+        // Timer clocks close to u32::MAX don't overflow
+        assert_eq!(cadence.timer_ticks(u32::MAX), Some(89_478));
+        assert_eq!(cadence.actual_sample_rate_hz(u32::MAX), Some(48_000));
+        // Timers that are too slow to tick once per sample
+        assert_eq!(cadence.timer_ticks(23_999), None);
+        assert_eq!(cadence.actual_sample_rate_hz(23_999), None);
+        assert_eq!(cadence.timer_ticks(0), None);
+        assert_eq!(cadence.actual_sample_rate_hz(0), None);
+        assert_eq!(cadence.timer_ticks(24_000), Some(1));
+        assert_eq!(cadence.actual_sample_rate_hz(24_000), Some(24_000));
+        // A sample rate of 0 can't be constructed with new, but doesn't divide by zero either
+        let cadence = SamplingCadence { sample_rate_hz: 0 };
+        assert_eq!(cadence.timer_ticks(72_000_000), None);
+        assert_eq!(cadence.actual_sample_rate_hz(72_000_000), None);
+    }
+
+    #[test]
+    fn test_poll() {
+        let mut shape = SignalShape::new(24.0);
+        shape.high = 3_000;
+        shape.low = 1_000;
+        let samples = ltc_samples(&TimecodeFrame::new(1, 0, 0, 0, TwentyFive), 30, &shape);
+        let adc = MockAdc { samples: samples.iter().map(|s| *s as u16).collect(), index: 0, busy: false };
+        let mut reader = AdcLtcReader::new(adc, MockPin, SamplingCadence::recommended());
+        let mut frames = Vec::new();
+        loop {
+            match reader.poll() {
+                Ok(Some(frame)) => frames.push(frame),
+                Ok(None) | Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(())) => break,
+            }
+        }
+        assert_eq!(frames.len(), 29);
+        assert_eq!(frames.last(), Some(&TimecodeFrame::new(1, 0, 1, 4, TwentyFive)));
+    }
+}
@@ -0,0 +1,137 @@
+//! C interface to the decoder. See `include/timecode_coder.h` for the matching header.
+//! Build a shared library with `cargo rustc --release --features ffi --crate-type cdylib`
+
+use crate::ltc_decoder::{float_to_int_sample, LtcDecoder};
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// Opaque decoder handle for C
+pub struct LtcFfiDecoder {
+    decoder: LtcDecoder<i32>,
+}
+
+/// C compatible representation of a TimecodeFrame
+#[repr(C)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct LtcFfiTimecodeFrame {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    /// 24, 25, 30, 50 or 60. 0 if unknown
+    pub frames_per_second: u8,
+    /// 1 for 29.97 fps drop-frame timecode, which has 30 in frames_per_second, else 0
+    pub drop_frame: u8,
+}
+
+impl From<&TimecodeFrame> for LtcFfiTimecodeFrame {
+    fn from(frame: &TimecodeFrame) -> Self {
+        Self {
+            hours: frame.hours,
+            minutes: frame.minutes,
+            seconds: frame.seconds,
+            frames: frame.frames,
+            frames_per_second: frame.frames_per_second.frames_per_second_u8().unwrap_or(0),
+            drop_frame: (frame.frames_per_second == FramesPerSecond::ThirtyDropFrame) as u8,
+        }
+    }
+}
+
+/// Creates a decoder. Needs to be freed with `ltc_decoder_destroy`. Returns null if `sample_rate`
+/// is 0
+#[no_mangle]
+pub extern "C" fn ltc_decoder_create(sample_rate: u32) -> *mut LtcFfiDecoder {
+    if sample_rate == 0 {
+        return core::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(LtcFfiDecoder {
+        decoder: LtcDecoder::new(sample_rate),
+    }))
+}
+
+/// Frees a decoder created with `ltc_decoder_create`. Null is ignored
+///
+/// # Safety
+/// `decoder` must be null or a pointer returned by `ltc_decoder_create` that has not been freed yet
+#[no_mangle]
+pub unsafe extern "C" fn ltc_decoder_destroy(decoder: *mut LtcFfiDecoder) {
+    if !decoder.is_null() {
+        drop(Box::from_raw(decoder));
+    }
+}
+
+/// Decodes `len` integer samples. Writes up to `out_capacity` decoded frames to `out` and returns
+/// how many were written. Frames beyond `out_capacity` are dropped
+///
+/// # Safety
+/// `decoder` must be a valid decoder, `samples` must point to `len` samples and `out` to
+/// `out_capacity` frames
+#[no_mangle]
+pub unsafe extern "C" fn ltc_decoder_push_samples(decoder: *mut LtcFfiDecoder, samples: *const i32, len: usize, out: *mut LtcFfiTimecodeFrame, out_capacity: usize) -> usize {
+    push_samples(decoder, samples, len, out, out_capacity, |sample| sample)
+}
+
+/// Same as `ltc_decoder_push_samples` for float samples in the range -1.0 to 1.0
+///
+/// # Safety
+/// `decoder` must be a valid decoder, `samples` must point to `len` samples and `out` to
+/// `out_capacity` frames
+#[no_mangle]
+pub unsafe extern "C" fn ltc_decoder_push_samples_f32(decoder: *mut LtcFfiDecoder, samples: *const f32, len: usize, out: *mut LtcFfiTimecodeFrame, out_capacity: usize) -> usize {
+    push_samples(decoder, samples, len, out, out_capacity, float_to_int_sample)
+}
+
+/// Shared implementation of the push functions
+unsafe fn push_samples<S: Copy>(decoder: *mut LtcFfiDecoder, samples: *const S, len: usize, out: *mut LtcFfiTimecodeFrame, out_capacity: usize, convert: impl Fn(S) -> i32) -> usize {
+    let Some(decoder) = decoder.as_mut() else {
+        return 0;
+    };
+    if samples.is_null() {
+        return 0;
+    }
+    let samples = core::slice::from_raw_parts(samples, len);
+    let out: &mut [LtcFfiTimecodeFrame] = if out.is_null() { &mut [] } else { core::slice::from_raw_parts_mut(out, out_capacity) };
+    let mut written = 0;
+    for sample in samples {
+        if let Some(frame) = decoder.decoder.get_timecode_frame(convert(*sample)) {
+            if let Some(slot) = out.get_mut(written) {
+                *slot = (&frame).into();
+                written += 1;
+            }
+        }
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ffi::{ltc_decoder_create, ltc_decoder_destroy, ltc_decoder_push_samples, LtcFfiTimecodeFrame};
+    use crate::FramesPerSecond::{Thirty, ThirtyDropFrame, TwentyFive};
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_push_samples() {
+        let samples = ltc_samples(&TimecodeFrame::new(12, 0, 0, 0, TwentyFive), 20, &SignalShape::new(24.0));
+        let mut out = [LtcFfiTimecodeFrame::default(); 32];
+        unsafe {
+            let decoder = ltc_decoder_create(48_000);
+            let written = ltc_decoder_push_samples(decoder, samples.as_ptr(), samples.len(), out.as_mut_ptr(), out.len());
+            ltc_decoder_destroy(decoder);
+            assert_eq!(written, 19);
+        }
+        assert_eq!(out[18], LtcFfiTimecodeFrame { hours: 12, minutes: 0, seconds: 0, frames: 19, frames_per_second: 25, drop_frame: 0 });
+    }
+
+    #[test]
+    fn test_drop_frame() {
+        let frame: LtcFfiTimecodeFrame = (&TimecodeFrame::new(1, 0, 0, 2, ThirtyDropFrame)).into();
+        assert_eq!((frame.frames_per_second, frame.drop_frame), (30, 1));
+        let frame: LtcFfiTimecodeFrame = (&TimecodeFrame::new(1, 0, 0, 2, Thirty)).into();
+        assert_eq!((frame.frames_per_second, frame.drop_frame), (30, 0));
+    }
+
+    #[test]
+    fn test_invalid_sample_rate() {
+        assert!(ltc_decoder_create(0).is_null());
+    }
+}
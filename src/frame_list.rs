@@ -0,0 +1,183 @@
+//! Writes the frames decoded from a WAV file as CSV or EDL, e.g. as report for quality control
+
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::ltc_decoder::TimecodeFrameExt;
+use crate::segments::split_segments;
+use crate::wav_file::{for_each_frame_ext, WavDecodeError};
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// File format of the frame list
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum FrameListFormat {
+    /// One row per frame with the columns sample, start_sample, timecode, fps, sync_state,
+    /// consecutive_frames, parity_valid, unassigned_flags, drop_frame, color_frame and user_bits.
+    /// The sync state and the number of consecutive frames tell how far a frame can be trusted
+    Csv,
+    /// A CMX 3600 style EDL with one event per segment of continuous timecode (see
+    /// `segments::find_segments`). The source timecode is the LTC, the record timecode the
+    /// position in the file. Frames with unknown frame-rate are left out
+    Edl,
+}
+
+/// Errors that may occur when writing a frame list
+#[derive(Debug)]
+pub enum FrameListError {
+    Io(std::io::Error),
+    /// The timecode could not be decoded from the file
+    Wav(WavDecodeError),
+}
+
+impl Display for FrameListError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameListError::Io(e) => write!(f, "Could not write frame list: {}", e),
+            FrameListError::Wav(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for FrameListError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FrameListError::Io(e) => Some(e),
+            FrameListError::Wav(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for FrameListError {
+    fn from(e: std::io::Error) -> Self {
+        FrameListError::Io(e)
+    }
+}
+
+impl From<WavDecodeError> for FrameListError {
+    fn from(e: WavDecodeError) -> Self {
+        FrameListError::Wav(e)
+    }
+}
+
+/// Decodes a channel (0 is the first channel) of a WAV file and writes the frames to a file.
+/// Returns the number of rows (CSV) or events (EDL) written
+pub fn write_frame_list_file<P: AsRef<Path>, Q: AsRef<Path>>(wav_path: P, channel: u16, list_path: Q, format: FrameListFormat) -> Result<usize, FrameListError> {
+    let reader = hound::WavReader::open(wav_path).map_err(WavDecodeError::from)?;
+    let title = list_path.as_ref().file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    write_frame_list(reader, channel, BufWriter::new(File::create(list_path)?), format, &title)
+}
+
+/// Same as write_frame_list_file, but reads the WAV data from any reader and writes to any writer.
+/// The title is only written to EDLs
+pub fn write_frame_list<R: Read, W: Write>(reader: hound::WavReader<R>, channel: u16, mut writer: W, format: FrameListFormat, title: &str) -> Result<usize, FrameListError> {
+    let mut frames = Vec::new();
+    let sampling_rate = for_each_frame_ext(reader, channel, |index, frame| frames.push((index, frame)))?;
+    let written = match format {
+        FrameListFormat::Csv => write_csv(&frames, &mut writer)?,
+        FrameListFormat::Edl => write_edl(&frames, sampling_rate, &mut writer, title)?,
+    };
+    writer.flush()?;
+    Ok(written)
+}
+
+fn write_csv<W: Write>(frames: &[(u64, TimecodeFrameExt)], writer: &mut W) -> std::io::Result<usize> {
+    writeln!(writer, "sample,start_sample,timecode,fps,sync_state,consecutive_frames,parity_valid,unassigned_flags,drop_frame,color_frame,user_bits")?;
+    for (index, frame) in frames {
+        writeln!(writer, "{},{},{},{:?},{:?},{},{},{},{},{},{:08X}",
+                 index,
+                 frame.start_sample,
+                 frame.frame,
+                 frame.frame.frames_per_second,
+                 frame.sync_state,
+                 frame.consecutive_frames,
+                 frame.parity_valid,
+                 frame.unassigned_flags_set,
+                 frame.drop_frame_flag,
+                 frame.color_frame_flag,
+                 frame.user_bits.groups,
+        )?;
+    }
+    Ok(frames.len())
+}
+
+fn write_edl<W: Write>(frames: &[(u64, TimecodeFrameExt)], sampling_rate: u32, writer: &mut W, title: &str) -> std::io::Result<usize> {
+    let frames: Vec<_> = frames.iter()
+        .filter(|(_, frame)| frame.frame.frames_per_second != FramesPerSecond::Unknown)
+        .map(|(_, frame)| (frame.start_sample, frame.frame.clone()))
+        .collect();
+    let drop_frame = frames.first().is_some_and(|(_, frame)| frame.frames_per_second.is_drop_frame());
+    writeln!(writer, "TITLE: {}", title)?;
+    writeln!(writer, "FCM: {}", if drop_frame { "DROP FRAME" } else { "NON-DROP FRAME" })?;
+    let segments = split_segments(&frames, sampling_rate);
+    for (event, segment) in segments.iter().enumerate() {
+        let mut source_out = segment.end.clone();
+        source_out.add_frame();
+        let frames_per_second = segment.frames_per_second();
+        let record_in = record_frame_count(segment.sample_range.start, sampling_rate, frames_per_second);
+        let record_timecode = |count: u32| TimecodeFrame::from_frame_count(count, frames_per_second.clone()).map_or("--:--:--:--".to_string(), |frame| frame.to_string());
+        writeln!(writer)?;
+        writeln!(writer, "{:03}  AX       V     C        {} {} {} {}",
+                 event + 1,
+                 segment.start,
+                 source_out,
+                 record_timecode(record_in),
+                 record_timecode(record_in + segment.frame_count),
+        )?;
+        writeln!(writer, "* SAMPLES {}-{}", segment.sample_range.start, segment.sample_range.end)?;
+    }
+    Ok(segments.len())
+}
+
+/// The position of a sample in the file as number of frames, rounded down
+fn record_frame_count(sample: u64, sampling_rate: u32, frames_per_second: &FramesPerSecond) -> u32 {
+    let Some(frame_rate) = frames_per_second.frame_rate() else {
+        return 0;
+    };
+    (sample as u128 * frame_rate.numerator() as u128 / (sampling_rate as u128 * frame_rate.denominator() as u128)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::frame_list::{write_frame_list, FrameListFormat};
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, wav_data, SignalShape};
+    use crate::TimecodeFrame;
+
+    /// LTC from 10:00:00:00, a second of silence and LTC from 11:00:00:00
+    fn wav() -> hound::WavReader<Cursor<Vec<u8>>> {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0)).into_iter()
+            .chain(std::iter::repeat_n(0, 48_000))
+            .chain(ltc_samples(&TimecodeFrame::new(11, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0)));
+        hound::WavReader::new(wav_data(samples)).unwrap()
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut csv = Vec::new();
+        assert_eq!(write_frame_list(wav(), 0, &mut csv, FrameListFormat::Csv, "").unwrap(), 18);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 19);
+        assert_eq!(lines[1], "3456,1920,10:00:00:01,TwentyFive,Locking,1,true,false,false,false,00000000");
+        assert_eq!(lines[2], "5376,3840,10:00:00:02,TwentyFive,Locked,2,true,false,false,false,00000000");
+    }
+
+    #[test]
+    fn test_edl() {
+        let mut edl = Vec::new();
+        assert_eq!(write_frame_list(wav(), 0, &mut edl, FrameListFormat::Edl, "Tape 1").unwrap(), 2);
+        assert_eq!(String::from_utf8(edl).unwrap(), "TITLE: Tape 1
+FCM: NON-DROP FRAME
+
+001  AX       V     C        10:00:00:01 10:00:00:10 00:00:00:01 00:00:00:10
+* SAMPLES 1920-19200
+
+002  AX       V     C        11:00:00:01 11:00:00:10 00:00:01:11 00:00:01:20
+* SAMPLES 69120-86400
+");
+    }
+}
@@ -0,0 +1,182 @@
+//! Exact frame-rates as fraction, including rates that LTC can't carry
+
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+
+use crate::FramesPerSecond;
+
+/// A frame-rate as reduced fraction of frames per second
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameRate {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl FrameRate {
+    pub const FPS_23_976: FrameRate = FrameRate { numerator: 24_000, denominator: 1001 };
+    pub const FPS_24: FrameRate = FrameRate { numerator: 24, denominator: 1 };
+    pub const FPS_25: FrameRate = FrameRate { numerator: 25, denominator: 1 };
+    pub const FPS_29_97: FrameRate = FrameRate { numerator: 30_000, denominator: 1001 };
+    pub const FPS_30: FrameRate = FrameRate { numerator: 30, denominator: 1 };
+    pub const FPS_47_952: FrameRate = FrameRate { numerator: 48_000, denominator: 1001 };
+    pub const FPS_48: FrameRate = FrameRate { numerator: 48, denominator: 1 };
+    pub const FPS_50: FrameRate = FrameRate { numerator: 50, denominator: 1 };
+    pub const FPS_59_94: FrameRate = FrameRate { numerator: 60_000, denominator: 1001 };
+    pub const FPS_60: FrameRate = FrameRate { numerator: 60, denominator: 1 };
+
+    /// Constructor for any rate of numerator / denominator frames per second. The fraction is
+    /// reduced, so 48/2 equals 24/1. None if numerator or denominator is zero
+    pub const fn new(numerator: u32, denominator: u32) -> Option<Self> {
+        if numerator == 0 || denominator == 0 {
+            return None;
+        }
+        let gcd = Self::gcd(numerator, denominator);
+        Some(Self {
+            numerator: numerator / gcd,
+            denominator: denominator / gcd,
+        })
+    }
+    pub const fn numerator(&self) -> u32 {
+        self.numerator
+    }
+    pub const fn denominator(&self) -> u32 {
+        self.denominator
+    }
+    /// The frame-rate as floating point number, e.g. 29.97
+    pub fn fps(&self) -> f32 {
+        self.numerator as f32 / self.denominator as f32
+    }
+    /// The number of frame numbers in a second, e.g. 30 for 29.97
+    pub const fn frames_per_second_rounded_up(&self) -> u32 {
+        self.numerator.div_ceil(self.denominator)
+    }
+    /// The exact duration of one frame, rounded to nanoseconds
+    pub const fn frame_duration(&self) -> Duration {
+        let numerator = self.numerator as u64;
+        Duration::from_nanos((1_000_000_000 * self.denominator as u64 + numerator / 2) / numerator)
+    }
+    /// Tells if the rate is a NTSC rate that runs 1000/1001 slower than the rate it is named after
+    pub const fn is_ntsc(&self) -> bool {
+        self.denominator == 1001
+    }
+    const fn gcd(a: u32, b: u32) -> u32 {
+        if b == 0 {
+            a
+        } else {
+            Self::gcd(b, a % b)
+        }
+    }
+}
+
+impl Display for FrameRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{:.3}", self.fps())
+        }
+    }
+}
+
+/// The LTC rate for a frame-rate. 29.97 is drop-frame, as this is the only 29.97 rate LTC is decoded with
+impl TryFrom<FrameRate> for FramesPerSecond {
+    type Error = FrameRate;
+
+    fn try_from(frame_rate: FrameRate) -> Result<Self, Self::Error> {
+        match frame_rate {
+            FrameRate::FPS_24 => Ok(FramesPerSecond::TwentyFour),
+            FrameRate::FPS_25 => Ok(FramesPerSecond::TwentyFive),
+            FrameRate::FPS_29_97 => Ok(FramesPerSecond::ThirtyDropFrame),
+            FrameRate::FPS_30 => Ok(FramesPerSecond::Thirty),
+            FrameRate::FPS_50 => Ok(FramesPerSecond::Fifty),
+            FrameRate::FPS_60 => Ok(FramesPerSecond::Sixty),
+            _ => Err(frame_rate),
+        }
+    }
+}
+
+/// A set of LTC frame-rates, e.g. the rates used in a facility
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FramesPerSecondSet(u8);
+
+impl FramesPerSecondSet {
+    /// The empty set
+    pub const fn new() -> Self {
+        Self(0)
+    }
+    /// Adds a frame-rate
+    pub fn with(mut self, frames_per_second: FramesPerSecond) -> Self {
+        self.0 |= Self::mask(&frames_per_second);
+        self
+    }
+    pub fn contains(&self, frames_per_second: &FramesPerSecond) -> bool {
+        self.0 & Self::mask(frames_per_second) != 0
+    }
+    fn mask(frames_per_second: &FramesPerSecond) -> u8 {
+        1 << frames_per_second.clone() as u8
+    }
+}
+
+impl FromIterator<FramesPerSecond> for FramesPerSecondSet {
+    fn from_iter<I: IntoIterator<Item = FramesPerSecond>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::with)
+    }
+}
+
+impl<const N: usize> From<[FramesPerSecond; N]> for FramesPerSecondSet {
+    fn from(frames_per_second: [FramesPerSecond; N]) -> Self {
+        frames_per_second.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::frame_rate::{FrameRate, FramesPerSecondSet};
+    use crate::FramesPerSecond;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(FrameRate::new(48, 2), Some(FrameRate::FPS_24));
+        assert_eq!(FrameRate::new(60_000, 1001), Some(FrameRate::FPS_59_94));
+        assert_eq!(FrameRate::new(0, 1), None);
+        assert_eq!(FrameRate::new(1, 0), None);
+        let custom = FrameRate::new(1000, 3).unwrap();
+        assert_eq!((custom.numerator(), custom.denominator()), (1000, 3));
+        assert_eq!(custom.frames_per_second_rounded_up(), 334);
+    }
+
+    #[test]
+    fn test_values() {
+        assert!((FrameRate::FPS_47_952.fps() - 47.952).abs() < 0.001);
+        assert_eq!(FrameRate::FPS_29_97.frames_per_second_rounded_up(), 30);
+        assert_eq!(FrameRate::FPS_50.frame_duration(), Duration::from_millis(20));
+        assert_eq!(FrameRate::FPS_23_976.frame_duration(), Duration::from_nanos(41_708_333));
+        assert!(FrameRate::FPS_59_94.is_ntsc());
+        assert!(!FrameRate::FPS_60.is_ntsc());
+        assert_eq!(format!("{}", FrameRate::FPS_25), "25");
+        assert_eq!(format!("{}", FrameRate::FPS_23_976), "23.976");
+    }
+
+    #[test]
+    fn test_frames_per_second_conversion() {
+        for fps in [FramesPerSecond::TwentyFour, FramesPerSecond::TwentyFive, FramesPerSecond::Thirty, FramesPerSecond::ThirtyDropFrame, FramesPerSecond::Fifty, FramesPerSecond::Sixty] {
+            assert_eq!(FramesPerSecond::try_from(fps.frame_rate().unwrap()), Ok(fps));
+        }
+        assert_eq!(FramesPerSecond::Unknown.frame_rate(), None);
+        assert_eq!(FramesPerSecond::try_from(FrameRate::FPS_48), Err(FrameRate::FPS_48));
+    }
+
+    #[test]
+    fn test_frames_per_second_set() {
+        let set = FramesPerSecondSet::from([FramesPerSecond::TwentyFour, FramesPerSecond::TwentyFive]);
+        assert!(set.contains(&FramesPerSecond::TwentyFour));
+        assert!(set.contains(&FramesPerSecond::TwentyFive));
+        assert!(!set.contains(&FramesPerSecond::Thirty));
+        assert!(!FramesPerSecondSet::new().contains(&FramesPerSecond::Unknown));
+        assert!(set.with(FramesPerSecond::Unknown).contains(&FramesPerSecond::Unknown));
+    }
+}
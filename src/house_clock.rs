@@ -0,0 +1,200 @@
+//! Regenerates received LTC and keeps generating it when the input fails
+
+use core::time::Duration;
+use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig, Sample};
+use crate::ltc_encoder::LtcEncoder;
+use crate::{SyncState, TimecodeFrame};
+
+/// Where the timecode of a `HouseClock` comes from
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ClockSource {
+    /// The decoder is locked to the input
+    Input,
+    /// The input is lost, the generator continues on its own
+    Generator,
+    /// No input was locked yet or the generator ran longer than the max freewheel duration
+    Stopped,
+}
+
+/// Decodes LTC and outputs it through its own generator. While the input is locked, the generator
+/// is jammed to every decoded frame at the next frame edge of the input, so the output is in phase
+/// with the input. When the input is lost the generator keeps running, so the output continues without a
+/// gap until the max freewheel duration is exceeded
+pub struct HouseClock<T: Sample> {
+    decoder: LtcDecoder<T>,
+    generator: Option<LtcEncoder<T>>,
+    sampling_rate: u32,
+    high: T,
+    low: T,
+    max_freewheel_samples: u64,
+    /// Number of samples since the generator was jammed last
+    samples_since_jam: u64,
+    /// The frame the generator is jammed to at the next frame edge of the input and the number of
+    /// samples until then
+    pending_jam: Option<(TimecodeFrame, u64)>,
+}
+
+impl<T: Sample> HouseClock<T> {
+    pub const DEFAULT_MAX_FREEWHEEL: Duration = Duration::from_secs(10);
+
+    /// Constructor. `high` and `low` are the sample values of the generated signal
+    pub fn new(sampling_rate: u32, high: T, low: T) -> Self {
+        Self::new_with_config(sampling_rate, LtcDecoderConfig::default(), high, low)
+    }
+    pub fn new_with_config(sampling_rate: u32, config: LtcDecoderConfig, high: T, low: T) -> Self {
+        let mut clock = Self {
+            decoder: LtcDecoder::new_with_config(sampling_rate, config),
+            generator: None,
+            sampling_rate,
+            high,
+            low,
+            max_freewheel_samples: 0,
+            samples_since_jam: 0,
+            pending_jam: None,
+        };
+        clock.max_freewheel_samples = clock.duration_to_samples(Self::DEFAULT_MAX_FREEWHEEL);
+        clock
+    }
+    /// Sets how long the generator continues after the input is lost
+    pub fn with_max_freewheel(mut self, max_freewheel: Duration) -> Self {
+        self.max_freewheel_samples = self.duration_to_samples(max_freewheel);
+        self
+    }
+
+    /// Push a sample of the input and get the sample of the output at the same time. None while
+    /// the clock is stopped
+    pub fn push_sample(&mut self, input: T) -> Option<T> {
+        if let Some((_, samples)) = self.pending_jam.as_mut() {
+            *samples -= 1;
+            if *samples == 0 {
+                if let Some((frame, _)) = self.pending_jam.take() {
+                    self.jam(frame);
+                }
+            }
+        }
+        if let Some(frame) = self.decoder.get_timecode_frame(input) {
+            if self.decoder.sync_state() == SyncState::Locked {
+                self.schedule_jam(frame);
+            }
+        }
+        if self.active_source() == ClockSource::Stopped {
+            self.generator = None;
+        }
+        self.samples_since_jam += 1;
+        self.generator.as_mut().map(|generator| generator.next_sample())
+    }
+
+    /// Fills `output` with the samples that belong to the samples of `input`. Samples without
+    /// output are set to the middle of high and low
+    pub fn process(&mut self, input: &[T], output: &mut [T]) {
+        let silence = T::from_f64((self.high.to_f64().unwrap_or(0.0) + self.low.to_f64().unwrap_or(0.0)) / 2.0).unwrap_or(T::zero());
+        for (input, output) in input.iter().zip(output) {
+            *output = self.push_sample(*input).unwrap_or(silence);
+        }
+    }
+
+    pub fn active_source(&self) -> ClockSource {
+        if self.generator.is_none() {
+            ClockSource::Stopped
+        } else if self.decoder.sync_state() == SyncState::Locked {
+            ClockSource::Input
+        } else if self.samples_since_jam <= self.max_freewheel_samples {
+            ClockSource::Generator
+        } else {
+            ClockSource::Stopped
+        }
+    }
+
+    /// The frame that the next output sample belongs to. None while the clock is stopped
+    pub fn current_timecode(&self) -> Option<&TimecodeFrame> {
+        self.generator.as_ref().map(|generator| generator.current_timecode())
+    }
+
+    pub fn decoder(&self) -> &LtcDecoder<T> {
+        &self.decoder
+    }
+
+    /// A frame is returned after its data bits, while the input is still sending its sync word.
+    /// Schedules the jam to the frame that starts at the next frame edge of the input
+    fn schedule_jam(&mut self, mut frame: TimecodeFrame) {
+        let (Some(latency), Some(frame_rate)) = (self.decoder.frame_latency_samples(), frame.frames_per_second.frame_rate()) else {
+            return;
+        };
+        let frame_samples = ((self.sampling_rate as u64 * frame_rate.denominator() as u64 + frame_rate.numerator() as u64 / 2)
+            / frame_rate.numerator() as u64).max(1);
+        // With a fixed latency the frame may be returned after the input passed further frame edges
+        let frames_ahead = latency as u64 / frame_samples + 1;
+        for _ in 0..frames_ahead {
+            frame.add_frame();
+        }
+        self.pending_jam = Some((frame, frames_ahead * frame_samples - latency as u64));
+    }
+
+    /// Restarts the generator at `frame`, which starts with the current sample
+    fn jam(&mut self, frame: TimecodeFrame) {
+        if let Some(generator) = LtcEncoder::new(frame, self.sampling_rate, self.high, self.low) {
+            self.generator = Some(generator);
+            self.samples_since_jam = 0;
+        }
+    }
+
+    fn duration_to_samples(&self, duration: Duration) -> u64 {
+        (duration.as_secs_f64() * self.sampling_rate as f64) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::house_clock::{ClockSource, HouseClock};
+    use crate::ltc_decoder::LtcDecoder;
+    use crate::ltc_encoder::LtcEncoder;
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_failover() {
+        let mut input = LtcEncoder::new(TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 48_000, 10_000, -10_000).unwrap();
+        let mut clock = HouseClock::new(48_000, 10_000, -10_000).with_max_freewheel(Duration::from_secs(1));
+        let mut output = LtcDecoder::<i32>::new(48_000);
+        let mut frames = Vec::new();
+        let mut sources = Vec::new();
+        // One second of input, then the input is lost
+        for i in 0..48_000 * 3 {
+            let sample = if i < 48_000 { input.next_sample() } else { 0 };
+            let output_sample = clock.push_sample(sample);
+            if sources.last() != Some(&clock.active_source()) {
+                sources.push(clock.active_source());
+            }
+            if let Some(output_sample) = output_sample {
+                frames.extend(output.get_timecode_frame(output_sample));
+            }
+        }
+        assert_eq!(sources, [ClockSource::Stopped, ClockSource::Input, ClockSource::Generator, ClockSource::Stopped]);
+        assert!(frames.windows(2).all(|frames| frames[0].checked_add_frames(1).as_ref() == Some(&frames[1])));
+        // The output continues for about a second after the input was lost
+        let last = frames.last().unwrap().to_frame_count().unwrap();
+        assert!(last >= TimecodeFrame::new(10, 0, 1, 20, TwentyFive).to_frame_count().unwrap());
+        assert_eq!(clock.current_timecode(), None);
+    }
+
+    #[test]
+    fn test_output_in_phase() {
+        let mut input = LtcEncoder::new(TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 48_000, 10_000, -10_000).unwrap();
+        let mut clock = HouseClock::new(48_000, 10_000, -10_000);
+        let mut jammed_at = None;
+        for i in 0..48_000 {
+            let sample = input.next_sample();
+            let output_sample = clock.push_sample(sample);
+            if jammed_at.is_none() && output_sample.is_some() {
+                jammed_at = Some(i);
+            }
+            if jammed_at.is_some() {
+                // Same frames on the same samples as the input
+                assert_eq!(output_sample, Some(sample), "sample {i}");
+            }
+        }
+        // The generator starts at a frame edge of the input
+        assert_eq!(jammed_at.unwrap() % 1920, 0);
+    }
+}
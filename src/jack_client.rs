@@ -0,0 +1,201 @@
+//! JACK client that decodes timecode from an input port. Optionally the JACK transport chases the
+//! decoded timecode and the client publishes it as timebase master
+
+use std::ffi::{c_int, c_void};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use jack::jack_sys::{jack_nframes_t, jack_position_t, jack_set_timebase_callback, jack_transport_state_t, JackPositionTimecode};
+use jack::{AsyncClient, AudioIn, Client, ClientOptions, Control, Frames, Port, ProcessHandler, ProcessScope, TransportState};
+
+use crate::ltc_decoder::{float_to_int_sample, LtcDecoder};
+use crate::TimecodeFrame;
+
+/// Options of LtcJackClient
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct LtcJackConfig {
+    /// The JACK transport is started, relocated and stopped to follow the decoded timecode
+    pub chase_transport: bool,
+    /// The client registers as timebase master and publishes the decoded timecode as the timecode
+    /// position of the transport (`frame_time` and `next_time`). Takes over from another master
+    pub timebase_master: bool,
+}
+
+/// An active JACK client with one input port named `ltc_in`. The client is deactivated when this
+/// is dropped
+pub struct LtcJackClient<F: FnMut(TimecodeFrame) + Send + 'static> {
+    client: AsyncClient<(), LtcProcessHandler<F>>,
+    port_name: String,
+    /// Read by the timebase callback, dropped after the client
+    _timebase: Option<Arc<TimebaseState>>,
+}
+
+impl<F: FnMut(TimecodeFrame) + Send + 'static> LtcJackClient<F> {
+    /// Registers a JACK client and calls `on_frame` (on the process thread) for every decoded
+    /// frame. If `chase_transport` is set, the JACK transport is started, relocated and stopped
+    /// to follow the decoded timecode
+    pub fn new(client_name: &str, chase_transport: bool, on_frame: F) -> Result<Self, jack::Error> {
+        Self::new_with_config(client_name, LtcJackConfig { chase_transport, ..LtcJackConfig::default() }, on_frame)
+    }
+
+    /// Like `new`, with the transport options of `config`
+    pub fn new_with_config(client_name: &str, config: LtcJackConfig, on_frame: F) -> Result<Self, jack::Error> {
+        let (client, _status) = Client::new(client_name, ClientOptions::NO_START_SERVER)?;
+        let port = client.register_port("ltc_in", AudioIn)?;
+        let port_name = port.name()?;
+        let timebase = config.timebase_master.then(|| Arc::new(TimebaseState::new()));
+        let handler = LtcProcessHandler {
+            decoder: LtcDecoder::new(client.sample_rate() as u32),
+            sample_rate: client.sample_rate() as u64,
+            port,
+            on_frame,
+            chase_transport: config.chase_transport,
+            samples_since_frame: 0,
+            transport_started: false,
+            frame_time_s: None,
+            timebase: timebase.clone(),
+        };
+        let client = client.activate_async((), handler)?;
+        if let Some(timebase) = &timebase {
+            // The callback stops before the state is dropped, as the client is deactivated first
+            let arg = Arc::as_ptr(timebase) as *mut c_void;
+            if unsafe { jack_set_timebase_callback(client.as_client().raw(), 0, Some(timebase_callback), arg) } != 0 {
+                return Err(jack::Error::CallbackRegistrationError);
+            }
+        }
+        Ok(Self { client, port_name, _timebase: timebase })
+    }
+
+    /// The full name of the input port, e.g. to connect it with `jack_connect`
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    /// The JACK client, e.g. to connect ports
+    pub fn client(&self) -> &Client {
+        self.client.as_client()
+    }
+
+    /// Deactivates and closes the client
+    pub fn deactivate(self) -> Result<(), jack::Error> {
+        self.client.deactivate().map(|_| ())
+    }
+}
+
+/// The decoded timecode handed from the process callback to the timebase callback. Both run on
+/// the process thread, the timebase callback after the process callback of the same cycle
+struct TimebaseState {
+    /// The bits of the timecode in seconds at the start of the next cycle, or NO_TIME
+    next_cycle_time_s: AtomicU64,
+}
+
+impl TimebaseState {
+    /// No timecode is received
+    const NO_TIME: u64 = u64::MAX;
+
+    fn new() -> Self {
+        Self { next_cycle_time_s: AtomicU64::new(Self::NO_TIME) }
+    }
+}
+
+/// Publishes the decoded timecode as the timecode position of the next cycle
+unsafe extern "C" fn timebase_callback(_state: jack_transport_state_t, n_frames: jack_nframes_t, pos: *mut jack_position_t, _new_pos: c_int, arg: *mut c_void) {
+    let timebase = &*(arg as *const TimebaseState);
+    let bits = timebase.next_cycle_time_s.load(Ordering::Relaxed);
+    if bits == TimebaseState::NO_TIME {
+        (*pos).valid &= !JackPositionTimecode;
+        return;
+    }
+    let frame_time = f64::from_bits(bits);
+    let frame_rate = (*pos).frame_rate;
+    (*pos).valid |= JackPositionTimecode;
+    (*pos).frame_time = frame_time;
+    (*pos).next_time = if frame_rate == 0 { frame_time } else { frame_time + n_frames as f64 / frame_rate as f64 };
+}
+
+/// Decodes the samples of the input port on the process thread
+struct LtcProcessHandler<F: FnMut(TimecodeFrame) + Send + 'static> {
+    decoder: LtcDecoder<i32>,
+    sample_rate: u64,
+    port: Port<AudioIn>,
+    on_frame: F,
+    chase_transport: bool,
+    /// Used to stop the transport if no timecode is received anymore
+    samples_since_frame: u64,
+    /// Tells if the transport was started by this client
+    transport_started: bool,
+    /// The timecode in seconds at the sample the last frame was decoded
+    frame_time_s: Option<f64>,
+    /// Set if the client is timebase master
+    timebase: Option<Arc<TimebaseState>>,
+}
+
+impl<F: FnMut(TimecodeFrame) + Send + 'static> LtcProcessHandler<F> {
+    /// The transport is stopped if no frame was received for this time
+    const TIMEOUT_S: u64 = 1;
+
+    /// Relocates and starts the transport if it is off by more than one frame. Returns true if the
+    /// transport was started
+    fn chase(client: &Client, frame: &TimecodeFrame, sample_rate: u64, offset_in_cycle: Frames, cycle_length: Frames) -> bool {
+        let (Some(frame_count), Some(frame_rate)) = (frame.to_frame_count(), frame.frames_per_second.frame_rate()) else {
+            return false;
+        };
+        let transport = client.transport();
+        let Ok(current) = transport.query() else {
+            return false;
+        };
+        let (numerator, denominator) = (frame_rate.numerator() as u64, frame_rate.denominator() as u64);
+        let frame_start = frame_count as u64 * sample_rate * denominator / numerator;
+        let frame_length = sample_rate * denominator / numerator;
+        // The frame is complete after its 64 data bits, which is 64/80 of the frame duration
+        let now = frame_start + frame_length * 64 / 80;
+        let cycle_start = now.saturating_sub(offset_in_cycle as u64);
+        let tolerance = frame_length;
+        if current.pos.frame() as u64 + tolerance < cycle_start || current.pos.frame() as u64 > cycle_start + tolerance {
+            // The new position takes effect in two process cycles
+            // Positions beyond the range of Frames (about 24.8h at 48kHz) can't be located
+            if let Ok(position) = Frames::try_from(cycle_start + 2 * cycle_length as u64) {
+                let _ = transport.locate(position);
+            }
+        }
+        current.state == TransportState::Stopped && transport.start().is_ok()
+    }
+
+    /// The timecode in seconds at the end of the 64 data bits of the frame, which is when it is
+    /// decoded
+    fn decoded_time_s(frame: &TimecodeFrame) -> Option<f64> {
+        let frame_count = frame.to_frame_count()?;
+        let frame_rate = frame.frames_per_second.frame_rate()?;
+        let frame_length_s = frame_rate.denominator() as f64 / frame_rate.numerator() as f64;
+        Some((frame_count as f64 + 64.0 / 80.0) * frame_length_s)
+    }
+}
+
+impl<F: FnMut(TimecodeFrame) + Send + 'static> ProcessHandler for LtcProcessHandler<F> {
+    fn process(&mut self, client: &Client, process_scope: &ProcessScope) -> Control {
+        let cycle_length = process_scope.n_frames();
+        for (i, sample) in self.port.as_slice(process_scope).iter().enumerate() {
+            self.samples_since_frame += 1;
+            if let Some(frame) = self.decoder.get_timecode_frame(float_to_int_sample(*sample)) {
+                self.samples_since_frame = 0;
+                self.frame_time_s = Self::decoded_time_s(&frame);
+                if self.chase_transport && Self::chase(client, &frame, self.sample_rate, i as Frames, cycle_length) {
+                    self.transport_started = true;
+                }
+                (self.on_frame)(frame);
+            }
+        }
+        let timed_out = self.samples_since_frame > self.sample_rate * Self::TIMEOUT_S;
+        if self.transport_started && timed_out {
+            self.transport_started = false;
+            let _ = client.transport().stop();
+        }
+        if let Some(timebase) = &self.timebase {
+            let next_cycle_time_s = self.frame_time_s.filter(|_| !timed_out)
+                .map(|time_s| time_s + (self.samples_since_frame + 1) as f64 / self.sample_rate as f64);
+            timebase.next_cycle_time_s.store(next_cycle_time_s.map_or(TimebaseState::NO_TIME, f64::to_bits), Ordering::Relaxed);
+        }
+        Control::Continue
+    }
+}
+
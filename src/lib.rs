@@ -1,13 +1,67 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 extern crate core;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use core::fmt::{Debug, Display, Formatter};
+#[cfg(feature = "debug")]
+use core::fmt::Debug;
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+
+pub use frame_rate::{FrameRate, FramesPerSecondSet};
+pub use user_bits::UserBits;
 
+pub mod chase_controller;
+pub mod clock_mapping;
+pub mod frame_rate;
 pub mod ltc_frame;
+pub mod mtc;
+pub mod user_bits;
 #[cfg(feature = "decode_ltc")]
 pub mod ltc_decoder;
+#[cfg(feature = "encode_ltc")]
+pub mod ltc_encoder;
+#[cfg(feature = "decode_ltc")]
+pub mod redundant_decoder;
+#[cfg(feature = "decode_ltc")]
+pub mod channel_selector;
+#[cfg(feature = "decode_ltc")]
+pub mod decoder_pool;
+#[cfg(all(feature = "decode_ltc", feature = "encode_ltc"))]
+pub mod house_clock;
+#[cfg(feature = "decode_ltc")]
+pub mod shared_timecode;
+#[cfg(feature = "wav")]
+pub mod wav_file;
+#[cfg(feature = "wav")]
+pub mod bwf;
+#[cfg(feature = "wav")]
+pub mod sync_offset;
+#[cfg(feature = "wav")]
+pub mod frame_list;
+#[cfg(feature = "wav")]
+pub mod segments;
+#[cfg(feature = "symphonia")]
+pub mod media_file;
+#[cfg(feature = "cpal")]
+pub mod cpal_input;
+#[cfg(feature = "jack")]
+pub mod jack_client;
+#[cfg(feature = "embedded-hal")]
+pub mod embedded_adc;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "rayon")]
+pub mod parallel_decode;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+#[cfg(feature = "log")]
+pub mod log_monitor;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod time_of_day;
 
 #[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimecodeFrame {
     pub hours: u8,
     pub minutes: u8,
@@ -17,51 +71,162 @@ pub struct TimecodeFrame {
 }
 
 impl TimecodeFrame {
-    pub fn add_frame(&mut self) {
+    /// Advances to the next frame. After 23:59:59:last the timecode continues at 00:00:00:00 and
+    /// true is returned to tell that a new day started
+    pub fn add_frame(&mut self) -> bool {
         self.frames += 1;
-        match self.frames_per_second {
-            FramesPerSecond::Unknown => {}
-            FramesPerSecond::TwentyFour => {
-                if self.frames >= 24 {
-                    self.frames = 0;
-                    self.seconds += 1;
-                }
-            }
-            FramesPerSecond::TwentyFive => {
-                if self.frames >= 25 {
-                    self.frames = 0;
-                    self.seconds += 1;
-                }
-            }
-            FramesPerSecond::Thirty => {
-                if self.frames >= 30 {
-                    self.frames = 0;
-                    self.seconds += 1;
-                }
+        if let Some(frames_per_second) = self.frames_per_second.frames_per_second_u8() {
+            if self.frames >= frames_per_second {
+                self.frames = 0;
+                self.seconds += 1;
             }
         }
         if self.seconds > 59 {
             self.seconds = 0;
             self.minutes += 1;
+            if self.frames_per_second.is_drop_frame() && !self.minutes.is_multiple_of(10) {
+                self.frames = FramesPerSecond::DROPPED_FRAMES_PER_MINUTE;
+            }
         }
         if self.minutes > 59 {
             self.minutes = 0;
             self.hours += 1;
         }
+        if self.hours > 23 {
+            self.hours = 0;
+            return true;
+        }
+        false
+    }
+    /// Number of frames since 00:00:00:00. Dropped frame numbers are not counted for drop-frame
+    /// timecode. None if the frame-rate is unknown
+    pub fn to_frame_count(&self) -> Option<u32> {
+        let frames_per_second = self.frames_per_second.frames_per_second_u8()? as u32;
+        let total_minutes = self.hours as u32 * 60 + self.minutes as u32;
+        let total_seconds = total_minutes * 60 + self.seconds as u32;
+        let mut count = total_seconds * frames_per_second + self.frames as u32;
+        if self.frames_per_second.is_drop_frame() {
+            count -= FramesPerSecond::DROPPED_FRAMES_PER_MINUTE as u32 * (total_minutes - total_minutes / 10);
+        }
+        Some(count)
+    }
+    /// Inverse of to_frame_count. None if the frame-rate is unknown or if count is beyond 24 hours
+    pub fn from_frame_count(count: u32, frames_per_second: FramesPerSecond) -> Option<Self> {
+        let fps = frames_per_second.frames_per_second_u8()? as u32;
+        let mut count = count;
+        if frames_per_second.is_drop_frame() {
+            let dropped_per_minute = FramesPerSecond::DROPPED_FRAMES_PER_MINUTE as u32;
+            let frames_per_ten_minutes = fps * 600 - dropped_per_minute * 9;
+            let frames_per_minute = fps * 60 - dropped_per_minute;
+            let ten_minutes = count / frames_per_ten_minutes;
+            let rest = count % frames_per_ten_minutes;
+            // The first minute of every ten minutes keeps all frame numbers
+            let dropped_in_ten_minutes = rest.saturating_sub(dropped_per_minute) / frames_per_minute;
+            count += dropped_per_minute * (ten_minutes * 9 + dropped_in_ten_minutes);
+        }
+        let frames = count % fps;
+        let total_seconds = count / fps;
+        let hours = total_seconds / 3600;
+        if hours > 23 {
+            return None;
+        }
+        Some(Self::new(hours as u8, (total_seconds / 60 % 60) as u8, (total_seconds % 60) as u8, frames as u8, frames_per_second))
+    }
+    /// Adds n frames. None if the frame-rate is unknown or if the result is beyond 24 hours
+    pub fn checked_add_frames(&self, n: u32) -> Option<Self> {
+        let count = self.to_frame_count()?.checked_add(n)?;
+        Self::from_frame_count(count, self.frames_per_second.clone())
+    }
+    /// Subtracts n frames. None if the frame-rate is unknown or if the result is before 00:00:00:00
+    pub fn checked_sub_frames(&self, n: u32) -> Option<Self> {
+        let count = self.to_frame_count()?.checked_sub(n)?;
+        Self::from_frame_count(count, self.frames_per_second.clone())
+    }
+    /// Adds n frames and stops at the last frame of the day. Timecode with unknown frame-rate is
+    /// returned unchanged
+    pub fn saturating_add_frames(&self, n: u32) -> Self {
+        self.map_frame_count(|count, frames_per_day| count.saturating_add(n).min(frames_per_day - 1))
+    }
+    /// Subtracts n frames and stops at 00:00:00:00. Timecode with unknown frame-rate is returned
+    /// unchanged
+    pub fn saturating_sub_frames(&self, n: u32) -> Self {
+        self.map_frame_count(|count, _| count.saturating_sub(n))
+    }
+    /// Adds n frames and continues at 00:00:00:00 after the last frame of the day. Timecode with
+    /// unknown frame-rate is returned unchanged
+    pub fn wrapping_add_frames(&self, n: u32) -> Self {
+        self.map_frame_count(|count, frames_per_day| ((count as u64 + n as u64) % frames_per_day as u64) as u32)
+    }
+    /// Subtracts n frames and continues at the last frame of the day before 00:00:00:00. Timecode
+    /// with unknown frame-rate is returned unchanged
+    pub fn wrapping_sub_frames(&self, n: u32) -> Self {
+        self.map_frame_count(|count, frames_per_day| {
+            let n = n % frames_per_day;
+            (count + frames_per_day - n) % frames_per_day
+        })
+    }
+    /// Adds n frames and continues at 00:00:00:00 after the last frame of the day. Also returns the
+    /// number of times midnight was passed. Timecode with unknown frame-rate is returned unchanged
+    pub fn overflowing_add_frames(&self, n: u32) -> (Self, u32) {
+        let days = match (self.to_frame_count(), self.frames_per_second.frames_per_day()) {
+            (Some(count), Some(frames_per_day)) => ((count as u64 + n as u64) / frames_per_day as u64) as u32,
+            _ => 0,
+        };
+        (self.wrapping_add_frames(n), days)
+    }
+    /// Subtracts n frames and continues at the last frame of the day before 00:00:00:00. Also
+    /// returns the number of times midnight was passed. Timecode with unknown frame-rate is returned
+    /// unchanged
+    pub fn overflowing_sub_frames(&self, n: u32) -> (Self, u32) {
+        let days = match (self.to_frame_count(), self.frames_per_second.frames_per_day()) {
+            (Some(count), Some(frames_per_day)) if n > count => (n - count - 1) / frames_per_day + 1,
+            _ => 0,
+        };
+        (self.wrapping_sub_frames(n), days)
+    }
+    /// Packs the timecode as 0xHHMMSSFF with two BCD digits per field, as used by video hardware
+    /// and MXF metadata
+    pub fn to_bcd_u32(&self) -> u32 {
+        Self::pack_bcd_u32(self.hours, self.minutes, self.seconds, self.frames)
+    }
+    /// Packs the fields as 0xHHMMSSFF like to_bcd_u32
+    pub(crate) fn pack_bcd_u32(hours: u8, minutes: u8, seconds: u8, frames: u8) -> u32 {
+        let bcd = |value: u8| ((value / 10) << 4) | (value % 10);
+        u32::from_be_bytes([bcd(hours), bcd(minutes), bcd(seconds), bcd(frames)])
+    }
+    /// Inverse of to_bcd_u32. The frame-rate is not part of the packing and has to be given
+    pub fn from_bcd_u32(bcd: u32, frames_per_second: FramesPerSecond) -> Result<Self, TimecodeFrameError> {
+        let mut fields = [0; 4];
+        for (field, byte) in fields.iter_mut().zip(bcd.to_be_bytes()) {
+            let (tens, units) = (byte >> 4, byte & 0xF);
+            if tens > 9 || units > 9 {
+                return Err(TimecodeFrameError::InvalidBcd(bcd));
+            }
+            *field = tens * 10 + units;
+        }
+        Self::try_new(fields[0], fields[1], fields[2], fields[3], frames_per_second)
+    }
+    /// Helper function that changes the frame count within a day
+    fn map_frame_count<F: FnOnce(u32, u32) -> u32>(&self, f: F) -> Self {
+        self.to_frame_count()
+            .zip(self.frames_per_second.frames_per_day())
+            .and_then(|(count, frames_per_day)| Self::from_frame_count(f(count, frames_per_day), self.frames_per_second.clone()))
+            .unwrap_or_else(|| self.clone())
     }
 }
 
-#[cfg(feature = "debug")]
+/// Formats as HH:MM:SS:FF, or HH:MM:SS;FF for drop-frame timecode
 impl Display for TimecodeFrame {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{:02}:{:02}:{:02}:{:02} fps:{:#?}", self.hours, self.minutes, self.seconds, self.frames, self.frames_per_second)
+        let frames_separator = if self.frames_per_second.is_drop_frame() { ';' } else { ':' };
+        write!(f, "{:02}:{:02}:{:02}{}{:02}", self.hours, self.minutes, self.seconds, frames_separator, self.frames)
     }
 }
 
 #[cfg(feature = "debug")]
 impl Debug for TimecodeFrame {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        Display::fmt(self, f)
+        write!(f, "{} fps:{:#?}", self, self.frames_per_second)
     }
 }
 
@@ -75,7 +240,32 @@ impl TimecodeFrame {
             frames_per_second: FramesPerSecond::from_frame_duration_without_syncword_in_s(duration_for_frame_without_syncword_in_s),
         }
     }
-    pub fn new(hours: u8, minutes: u8, seconds: u8, frames: u8, frames_per_second: FramesPerSecond) -> Self {
+    /// Constructor that checks that all fields are in range. Frames can't be checked if the
+    /// frame-rate is unknown
+    pub fn try_new(hours: u8, minutes: u8, seconds: u8, frames: u8, frames_per_second: FramesPerSecond) -> Result<Self, TimecodeFrameError> {
+        if hours > 23 {
+            return Err(TimecodeFrameError::HoursOutOfRange(hours));
+        }
+        if minutes > 59 {
+            return Err(TimecodeFrameError::MinutesOutOfRange(minutes));
+        }
+        if seconds > 59 {
+            return Err(TimecodeFrameError::SecondsOutOfRange(seconds));
+        }
+        if let Some(nominal_frames_per_second) = frames_per_second.frames_per_second_u8() {
+            if frames >= nominal_frames_per_second {
+                return Err(TimecodeFrameError::FramesOutOfRange { frames, frames_per_second });
+            }
+        }
+        let is_dropped = seconds == 0 && frames < FramesPerSecond::DROPPED_FRAMES_PER_MINUTE && !minutes.is_multiple_of(10);
+        if frames_per_second.is_drop_frame() && is_dropped {
+            return Err(TimecodeFrameError::DroppedFrame);
+        }
+        Ok(Self::new(hours, minutes, seconds, frames, frames_per_second))
+    }
+    /// Makes a frame without checking the fields. Const, so frames can be used in statics and
+    /// configuration tables
+    pub const fn new(hours: u8, minutes: u8, seconds: u8, frames: u8, frames_per_second: FramesPerSecond) -> Self {
         Self {
             hours,
             minutes,
@@ -86,15 +276,79 @@ impl TimecodeFrame {
     }
 }
 
+/// 00:00:00:00 at an unknown frame-rate
+impl Default for TimecodeFrame {
+    fn default() -> Self {
+        Self::new(0, 0, 0, 0, FramesPerSecond::Unknown)
+    }
+}
+
+/// Tells if the decoder is locked to the received timecode
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyncState {
+    /// No timecode is received
+    Unlocked,
+    /// Timecode is received, but no complete or consecutive frames yet
+    Locking,
+    /// Consecutive frames are received
+    Locked,
+    /// The locked LTC signal got lost. The timecode is still extrapolated for
+    /// `LtcDecoderConfig::max_extrapolation_frames`
+    Freewheeling,
+}
+
+/// Tells why a TimecodeFrame is invalid
 #[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TimecodeFrameError {
+    HoursOutOfRange(u8),
+    MinutesOutOfRange(u8),
+    SecondsOutOfRange(u8),
+    /// The frame number is not lower than the number of frames per second
+    FramesOutOfRange { frames: u8, frames_per_second: FramesPerSecond },
+    /// The frame number is skipped in drop-frame timecode
+    DroppedFrame,
+    /// A digit of a BCD packed timecode is above 9
+    InvalidBcd(u32),
+}
+
+impl Display for TimecodeFrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TimecodeFrameError::HoursOutOfRange(hours) => write!(f, "Hours {} out of range 0..=23", hours),
+            TimecodeFrameError::MinutesOutOfRange(minutes) => write!(f, "Minutes {} out of range 0..=59", minutes),
+            TimecodeFrameError::SecondsOutOfRange(seconds) => write!(f, "Seconds {} out of range 0..=59", seconds),
+            TimecodeFrameError::FramesOutOfRange { frames, frames_per_second } => write!(f, "Frames {} out of range for {:?}", frames, frames_per_second),
+            TimecodeFrameError::DroppedFrame => write!(f, "Frame number is dropped in drop-frame timecode"),
+            TimecodeFrameError::InvalidBcd(bcd) => write!(f, "Invalid BCD timecode {:#010x}", bcd),
+        }
+    }
+}
+
+impl core::error::Error for TimecodeFrameError {}
+
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FramesPerSecond {
     Unknown,
     TwentyFour,
     TwentyFive,
     Thirty,
+    /// 29.97 fps where the frame numbers 0 and 1 are skipped at the start of every minute,
+    /// except for every tenth minute
+    ThirtyDropFrame,
+    /// LTC at twice the bit-rate of 25 fps. The frame-pair flag tells if the frame number is odd
+    Fifty,
+    /// LTC at twice the bit-rate of 30 fps. The frame-pair flag tells if the frame number is odd
+    Sixty,
 }
 
 impl FramesPerSecond {
+    /// Number of frame numbers skipped at the start of a minute in drop-frame timecode
+    const DROPPED_FRAMES_PER_MINUTE: u8 = 2;
+
+    const DURATION_SIXTY_FULL_FRAME_IN_S: f32 = 0.016_666_66;
+    const DURATION_FIFTY_FULL_FRAME_IN_S: f32 = 0.02;
     const DURATION_THIRTY_FULL_FRAME_IN_S: f32 = 0.033_333_33;
     const DURATION_TWENTY_FIVE_FULL_FRAME_IN_S: f32 = 0.04;
     const DURATION_TWENTY_FOUR_FULL_FRAME_IN_S: f32 = 0.041_666_66;
@@ -102,10 +356,28 @@ impl FramesPerSecond {
     const DURATION_TWENTY_FOUR_WITHOUT_SYNC_WORD_IN_S: f32 = Self::DURATION_TWENTY_FOUR_FULL_FRAME_IN_S * 64.0 / 80.0;
     const DURATION_TWENTY_FIVE_WITHOUT_SYNC_WORD_IN_S: f32 = Self::DURATION_TWENTY_FIVE_FULL_FRAME_IN_S * 64.0 / 80.0;
     const DURATION_THIRTY_WITHOUT_SYNC_WORD_IN_S: f32 = Self::DURATION_THIRTY_FULL_FRAME_IN_S * 64.0 / 80.0;
+    const DURATION_FIFTY_WITHOUT_SYNC_WORD_IN_S: f32 = Self::DURATION_FIFTY_FULL_FRAME_IN_S * 64.0 / 80.0;
+    const DURATION_SIXTY_WITHOUT_SYNC_WORD_IN_S: f32 = Self::DURATION_SIXTY_FULL_FRAME_IN_S * 64.0 / 80.0;
+
+    /// The frame-rates that can be told apart by the duration of a frame. 29.97 fps is within the
+    /// tolerance of 30 fps and told apart by the drop-frame flag
+    #[cfg(feature = "decode_ltc")]
+    const DETECTABLE_FRAME_RATES: [(FramesPerSecond, FrameRate); 5] = [
+        (FramesPerSecond::TwentyFour, FrameRate::FPS_24),
+        (FramesPerSecond::TwentyFive, FrameRate::FPS_25),
+        (FramesPerSecond::Thirty, FrameRate::FPS_30),
+        (FramesPerSecond::Fifty, FrameRate::FPS_50),
+        (FramesPerSecond::Sixty, FrameRate::FPS_60),
+    ];
+    /// How many percent the duration of a frame may differ from the nominal duration
+    #[cfg(feature = "decode_ltc")]
+    const DURATION_TOLERANCE_PERCENT: u64 = 2;
 
     const DURATION_BOUND_TWENTY_FOUR_WITHOUT_SYNC_WORD_IN_S: (f32, f32) = (Self::DURATION_TWENTY_FOUR_WITHOUT_SYNC_WORD_IN_S * 0.98, Self::DURATION_TWENTY_FOUR_WITHOUT_SYNC_WORD_IN_S * 1.02);
     const DURATION_BOUND_THWENTY_FIVE_WITHOUT_SYNC_WORD_IN_S: (f32, f32) = (Self::DURATION_TWENTY_FIVE_WITHOUT_SYNC_WORD_IN_S * 0.98, Self::DURATION_TWENTY_FIVE_WITHOUT_SYNC_WORD_IN_S * 1.02);
     const DURATION_BOUND_THIRTY_WITHOUT_SYNC_WORD_IN_S: (f32, f32) = (Self::DURATION_THIRTY_WITHOUT_SYNC_WORD_IN_S * 0.98, Self::DURATION_THIRTY_WITHOUT_SYNC_WORD_IN_S * 1.02);
+    const DURATION_BOUND_FIFTY_WITHOUT_SYNC_WORD_IN_S: (f32, f32) = (Self::DURATION_FIFTY_WITHOUT_SYNC_WORD_IN_S * 0.98, Self::DURATION_FIFTY_WITHOUT_SYNC_WORD_IN_S * 1.02);
+    const DURATION_BOUND_SIXTY_WITHOUT_SYNC_WORD_IN_S: (f32, f32) = (Self::DURATION_SIXTY_WITHOUT_SYNC_WORD_IN_S * 0.98, Self::DURATION_SIXTY_WITHOUT_SYNC_WORD_IN_S * 1.02);
 
     fn from_frame_duration_without_syncword_in_s(frames_duration_s: f32) -> FramesPerSecond {
         if Self::is_in_duration_bounds(frames_duration_s, Self::DURATION_BOUND_TWENTY_FOUR_WITHOUT_SYNC_WORD_IN_S) {
@@ -117,10 +389,299 @@ impl FramesPerSecond {
         if Self::is_in_duration_bounds(frames_duration_s, Self::DURATION_BOUND_THIRTY_WITHOUT_SYNC_WORD_IN_S) {
             return FramesPerSecond::Thirty;
         }
+        if Self::is_in_duration_bounds(frames_duration_s, Self::DURATION_BOUND_FIFTY_WITHOUT_SYNC_WORD_IN_S) {
+            return FramesPerSecond::Fifty;
+        }
+        if Self::is_in_duration_bounds(frames_duration_s, Self::DURATION_BOUND_SIXTY_WITHOUT_SYNC_WORD_IN_S) {
+            return FramesPerSecond::Sixty;
+        }
         FramesPerSecond::Unknown
     }
 
+    /// The nominal (integer) number of frames in a second, 30 for drop-frame. None if unknown
+    pub fn frames_per_second_u8(&self) -> Option<u8> {
+        match self {
+            FramesPerSecond::Unknown => None,
+            FramesPerSecond::TwentyFour => Some(24),
+            FramesPerSecond::TwentyFive => Some(25),
+            FramesPerSecond::Thirty | FramesPerSecond::ThirtyDropFrame => Some(30),
+            FramesPerSecond::Fifty => Some(50),
+            FramesPerSecond::Sixty => Some(60),
+        }
+    }
+
+    /// The exact frame-rate, e.g. 30000/1001 for drop-frame. None if unknown
+    pub fn frame_rate(&self) -> Option<FrameRate> {
+        match self {
+            FramesPerSecond::Unknown => None,
+            FramesPerSecond::TwentyFour => Some(FrameRate::FPS_24),
+            FramesPerSecond::TwentyFive => Some(FrameRate::FPS_25),
+            FramesPerSecond::Thirty => Some(FrameRate::FPS_30),
+            FramesPerSecond::ThirtyDropFrame => Some(FrameRate::FPS_29_97),
+            FramesPerSecond::Fifty => Some(FrameRate::FPS_50),
+            FramesPerSecond::Sixty => Some(FrameRate::FPS_60),
+        }
+    }
+
+    /// The exact frame-rate, e.g. 29.97 for drop-frame. None if unknown
+    pub fn fps(&self) -> Option<f32> {
+        self.frame_rate().map(|frame_rate| frame_rate.fps())
+    }
+
+    /// The exact duration of one frame, rounded to nanoseconds. None if unknown
+    pub fn frame_duration(&self) -> Option<Duration> {
+        self.frame_rate().map(|frame_rate| frame_rate.frame_duration())
+    }
+
+    /// Number of frames from 00:00:00:00 to 23:59:59:last. None if unknown
+    pub(crate) fn frames_per_day(&self) -> Option<u32> {
+        let frames_per_minute = self.frames_per_second_u8()? as u32 * 60;
+        if self.is_drop_frame() {
+            Some((frames_per_minute * 10 - Self::DROPPED_FRAMES_PER_MINUTE as u32 * 9) * 6 * 24)
+        } else {
+            Some(frames_per_minute * 60 * 24)
+        }
+    }
+
+    /// Tells if frame numbers are skipped to match the 29.97 fps of NTSC video
+    pub fn is_drop_frame(&self) -> bool {
+        *self == FramesPerSecond::ThirtyDropFrame
+    }
+
+    /// Tells if the LTC frame number only counts frame-pairs, with the frame-pair flag telling if
+    /// the frame number is odd
+    pub fn has_frame_pairs(&self) -> bool {
+        matches!(self, FramesPerSecond::Fifty | FramesPerSecond::Sixty)
+    }
+
     fn is_in_duration_bounds(frames_duration_s: f32, bounds: (f32, f32)) -> bool {
         frames_duration_s > bounds.0 && frames_duration_s < bounds.1
     }
+
+    /// Tells the frame-rate from the number of samples the 64 data bits of a frame took. The bounds
+    /// are exact integers, so the result does not depend on float rounding at unusual sampling rates.
+    /// Only the allowed rates are matched if a set is passed, where drop-frame allows 30 fps
+    #[cfg(feature = "decode_ltc")]
+    pub(crate) fn from_frame_data_samples(samples: usize, sampling_rate: u32, allowed: Option<&FramesPerSecondSet>) -> FramesPerSecond {
+        Self::match_frame_data_samples(samples, sampling_rate, allowed).map_or(FramesPerSecond::Unknown, |(frames_per_second, _)| frames_per_second)
+    }
+    /// Like from_frame_data_samples, but also returns how far the duration is from the nominal one
+    /// in parts per million. None if no frame-rate matches
+    #[cfg(feature = "decode_ltc")]
+    pub(crate) fn match_frame_data_samples(samples: usize, sampling_rate: u32, allowed: Option<&FramesPerSecondSet>) -> Option<(FramesPerSecond, u64)> {
+        let is_allowed = |frames_per_second: &FramesPerSecond| match allowed {
+            Some(allowed) => allowed.contains(frames_per_second) || (*frames_per_second == FramesPerSecond::Thirty && allowed.contains(&FramesPerSecond::ThirtyDropFrame)),
+            None => true,
+        };
+        Self::DETECTABLE_FRAME_RATES.iter().filter(|(frames_per_second, _)| is_allowed(frames_per_second)).find_map(|(frames_per_second, frame_rate)| {
+            // The data bits are 64/80 of a frame, which takes sampling_rate / frame_rate samples
+            let measured = samples as u128 * 5 * frame_rate.numerator() as u128;
+            let nominal = 4 * sampling_rate as u128 * frame_rate.denominator() as u128;
+            let deviation = measured.abs_diff(nominal);
+            (deviation * 100 < nominal * Self::DURATION_TOLERANCE_PERCENT as u128).then(|| (frames_per_second.clone(), (deviation * 1_000_000 / nominal) as u64))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{FrameRate, FramesPerSecond, FramesPerSecondSet, TimecodeFrame, TimecodeFrameError};
+    use crate::FramesPerSecond::{Fifty, Sixty, Thirty, ThirtyDropFrame, TwentyFive, TwentyFour, Unknown};
+
+    static START: TimecodeFrame = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+
+    #[test]
+    fn test_const_and_default() {
+        assert_eq!(START.hours, 10);
+        assert_eq!(TimecodeFrame::default(), TimecodeFrame::new(0, 0, 0, 0, Unknown));
+    }
+
+    #[test]
+    fn test_frame_count() {
+        assert_eq!(TimecodeFrame::new(0, 0, 0, 0, TwentyFive).to_frame_count(), Some(0));
+        assert_eq!(TimecodeFrame::new(1, 0, 1, 2, TwentyFive).to_frame_count(), Some(90_027));
+        assert_eq!(TimecodeFrame::new(0, 1, 0, 0, Thirty).to_frame_count(), Some(1800));
+        assert_eq!(TimecodeFrame::new(0, 1, 0, 2, ThirtyDropFrame).to_frame_count(), Some(1800));
+        assert_eq!(TimecodeFrame::new(0, 10, 0, 0, ThirtyDropFrame).to_frame_count(), Some(17_982));
+        assert_eq!(TimecodeFrame::new(0, 0, 0, 0, Unknown).to_frame_count(), None);
+        assert_eq!(TimecodeFrame::from_frame_count(1800, ThirtyDropFrame), Some(TimecodeFrame::new(0, 1, 0, 2, ThirtyDropFrame)));
+        assert_eq!(TimecodeFrame::from_frame_count(1799, ThirtyDropFrame), Some(TimecodeFrame::new(0, 0, 59, 29, ThirtyDropFrame)));
+        assert_eq!(TimecodeFrame::from_frame_count(24 * 3600 * 24, TwentyFour), None);
+        assert_eq!(TimecodeFrame::from_frame_count(0, Unknown), None);
+    }
+
+    #[test]
+    fn test_frame_count_follows_add_frame() {
+        for fps in [TwentyFour, TwentyFive, Thirty, ThirtyDropFrame, Fifty, Sixty] {
+            let mut frame = TimecodeFrame::new(0, 0, 0, 0, fps.clone());
+            for count in 0..40_000 {
+                assert_eq!(frame.to_frame_count(), Some(count));
+                assert_eq!(TimecodeFrame::from_frame_count(count, fps.clone()).as_ref(), Some(&frame));
+                frame.add_frame();
+            }
+        }
+    }
+
+    #[test]
+    fn test_checked_frame_arithmetic() {
+        let frame = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        assert_eq!(frame.checked_add_frames(26), Some(TimecodeFrame::new(10, 0, 1, 1, TwentyFive)));
+        assert_eq!(frame.checked_sub_frames(1), Some(TimecodeFrame::new(9, 59, 59, 24, TwentyFive)));
+        assert_eq!(frame.checked_add_frames(14 * 3600 * 25), None);
+        assert_eq!(frame.checked_sub_frames(10 * 3600 * 25 + 1), None);
+        assert_eq!(TimecodeFrame::new(0, 0, 0, 0, Unknown).checked_add_frames(1), None);
+        let frame = TimecodeFrame::new(0, 1, 0, 2, ThirtyDropFrame);
+        assert_eq!(frame.checked_sub_frames(1), Some(TimecodeFrame::new(0, 0, 59, 29, ThirtyDropFrame)));
+    }
+
+    #[test]
+    fn test_saturating_frame_arithmetic() {
+        let frame = TimecodeFrame::new(23, 59, 59, 20, Thirty);
+        assert_eq!(frame.saturating_add_frames(5), TimecodeFrame::new(23, 59, 59, 25, Thirty));
+        assert_eq!(frame.saturating_add_frames(u32::MAX), TimecodeFrame::new(23, 59, 59, 29, Thirty));
+        assert_eq!(frame.saturating_sub_frames(u32::MAX), TimecodeFrame::new(0, 0, 0, 0, Thirty));
+        let unknown = TimecodeFrame::new(1, 2, 3, 4, Unknown);
+        assert_eq!(unknown.saturating_add_frames(10), unknown);
+        let frame = TimecodeFrame::new(23, 59, 59, 0, ThirtyDropFrame);
+        assert_eq!(frame.saturating_add_frames(100), TimecodeFrame::new(23, 59, 59, 29, ThirtyDropFrame));
+    }
+
+    #[test]
+    fn test_wrapping_frame_arithmetic() {
+        let frame = TimecodeFrame::new(23, 59, 59, 23, TwentyFour);
+        assert_eq!(frame.wrapping_add_frames(1), TimecodeFrame::new(0, 0, 0, 0, TwentyFour));
+        assert_eq!(frame.wrapping_add_frames(24 * 3600 * 24), frame);
+        assert_eq!(TimecodeFrame::new(0, 0, 0, 0, TwentyFour).wrapping_sub_frames(1), frame);
+        assert_eq!(frame.wrapping_sub_frames(u32::MAX), frame.wrapping_add_frames(24 * 3600 * 24 - u32::MAX % (24 * 3600 * 24)));
+        let frame = TimecodeFrame::new(0, 0, 0, 1, ThirtyDropFrame);
+        assert_eq!(frame.wrapping_sub_frames(2), TimecodeFrame::new(23, 59, 59, 29, ThirtyDropFrame));
+    }
+
+    #[test]
+    fn test_add_frame_rollover() {
+        let mut frame = TimecodeFrame::new(23, 59, 59, 24, TwentyFive);
+        assert!(frame.add_frame());
+        assert_eq!(frame, TimecodeFrame::new(0, 0, 0, 0, TwentyFive));
+        assert!(!frame.add_frame());
+        assert_eq!(frame, TimecodeFrame::new(0, 0, 0, 1, TwentyFive));
+    }
+
+    #[test]
+    fn test_overflowing_frame_arithmetic() {
+        let frame = TimecodeFrame::new(23, 59, 59, 24, TwentyFive);
+        assert_eq!(frame.overflowing_add_frames(0), (frame.clone(), 0));
+        assert_eq!(frame.overflowing_add_frames(1), (TimecodeFrame::new(0, 0, 0, 0, TwentyFive), 1));
+        assert_eq!(frame.overflowing_add_frames(2 * 24 * 3600 * 25 + 1), (TimecodeFrame::new(0, 0, 0, 0, TwentyFive), 3));
+        let frame = TimecodeFrame::new(0, 0, 0, 1, TwentyFive);
+        assert_eq!(frame.overflowing_sub_frames(1), (TimecodeFrame::new(0, 0, 0, 0, TwentyFive), 0));
+        assert_eq!(frame.overflowing_sub_frames(2), (TimecodeFrame::new(23, 59, 59, 24, TwentyFive), 1));
+        assert_eq!(frame.overflowing_sub_frames(24 * 3600 * 25 + 2), (TimecodeFrame::new(23, 59, 59, 24, TwentyFive), 2));
+        let unknown = TimecodeFrame::new(0, 0, 0, 0, Unknown);
+        assert_eq!(unknown.overflowing_sub_frames(1), (unknown.clone(), 0));
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert_eq!(TimecodeFrame::try_new(23, 59, 59, 24, TwentyFive), Ok(TimecodeFrame::new(23, 59, 59, 24, TwentyFive)));
+        assert_eq!(TimecodeFrame::try_new(24, 0, 0, 0, TwentyFive), Err(TimecodeFrameError::HoursOutOfRange(24)));
+        assert_eq!(TimecodeFrame::try_new(0, 60, 0, 0, TwentyFive), Err(TimecodeFrameError::MinutesOutOfRange(60)));
+        assert_eq!(TimecodeFrame::try_new(0, 0, 60, 0, TwentyFive), Err(TimecodeFrameError::SecondsOutOfRange(60)));
+        assert_eq!(TimecodeFrame::try_new(0, 0, 0, 24, TwentyFour), Err(TimecodeFrameError::FramesOutOfRange { frames: 24, frames_per_second: TwentyFour }));
+        assert_eq!(TimecodeFrame::try_new(0, 1, 0, 1, ThirtyDropFrame), Err(TimecodeFrameError::DroppedFrame));
+        assert!(TimecodeFrame::try_new(0, 10, 0, 1, ThirtyDropFrame).is_ok());
+        assert!(TimecodeFrame::try_new(0, 1, 0, 1, Thirty).is_ok());
+        assert!(TimecodeFrame::try_new(0, 0, 0, 99, Unknown).is_ok());
+    }
+
+    #[test]
+    fn test_bcd_u32() {
+        let frame = TimecodeFrame::new(23, 59, 58, 24, TwentyFive);
+        assert_eq!(frame.to_bcd_u32(), 0x23_59_58_24);
+        assert_eq!(TimecodeFrame::from_bcd_u32(0x23_59_58_24, TwentyFive), Ok(frame));
+        assert_eq!(TimecodeFrame::new(1, 2, 3, 4, Thirty).to_bcd_u32(), 0x01_02_03_04);
+        assert_eq!(TimecodeFrame::from_bcd_u32(0x01_1A_00_00, TwentyFive), Err(TimecodeFrameError::InvalidBcd(0x01_1A_00_00)));
+        assert_eq!(TimecodeFrame::from_bcd_u32(0x24_00_00_00, TwentyFive), Err(TimecodeFrameError::HoursOutOfRange(24)));
+    }
+
+    #[test]
+    fn test_frames_per_second_accessors() {
+        assert_eq!(TwentyFive.frames_per_second_u8(), Some(25));
+        assert_eq!(ThirtyDropFrame.frames_per_second_u8(), Some(30));
+        assert_eq!(Sixty.frames_per_second_u8(), Some(60));
+        assert_eq!(Fifty.frame_rate(), Some(FrameRate::FPS_50));
+        assert!(Fifty.has_frame_pairs() && !TwentyFive.has_frame_pairs());
+        assert_eq!(Unknown.frames_per_second_u8(), None);
+        assert_eq!(TwentyFour.frame_rate(), Some(FrameRate::FPS_24));
+        assert_eq!(ThirtyDropFrame.frame_rate(), Some(FrameRate::FPS_29_97));
+        assert_eq!(Thirty.fps(), Some(30.0));
+        assert!((ThirtyDropFrame.fps().unwrap() - 29.97).abs() < 0.001);
+        assert_eq!(TwentyFive.frame_duration(), Some(Duration::from_millis(40)));
+        assert_eq!(ThirtyDropFrame.frame_duration(), Some(Duration::from_nanos(33_366_667)));
+        assert_eq!(Unknown.frame_duration(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "decode_ltc")]
+    fn test_from_frame_data_samples() {
+        // 64 bits of 25 fps at 48 kHz take 1536 samples, the bounds are 2% apart
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1536, 48_000, None), TwentyFive);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1506, 48_000, None), TwentyFive);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1505, 48_000, None), Unknown);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1566, 48_000, None), TwentyFive);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1567, 48_000, None), Unknown);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1470, 44_100, None), TwentyFour);
+        // 29.97 fps is detected as 30 fps
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1177, 44_100, None), Thirty);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(768, 48_000, None), Fifty);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(640, 48_000, None), Sixty);
+        assert_eq!(FramesPerSecond::match_frame_data_samples(1536, 48_000, None), Some((TwentyFive, 0)));
+        assert_eq!(FramesPerSecond::match_frame_data_samples(1537, 48_000, None), Some((TwentyFive, 651)));
+        let allowed = FramesPerSecondSet::from([TwentyFour, ThirtyDropFrame]);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1536, 48_000, Some(&allowed)), Unknown);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1470, 44_100, Some(&allowed)), TwentyFour);
+        assert_eq!(FramesPerSecond::from_frame_data_samples(1177, 44_100, Some(&allowed)), Thirty);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(format!("{}", TimecodeFrame::new(1, 2, 3, 4, TwentyFive)), "01:02:03:04");
+        assert_eq!(format!("{}", TimecodeFrame::new(10, 20, 30, 12, Thirty)), "10:20:30:12");
+        assert_eq!(format!("{}", TimecodeFrame::new(0, 1, 0, 2, ThirtyDropFrame)), "00:01:00;02");
+    }
+
+    #[test]
+    fn test_errors() {
+        fn as_error<E: core::error::Error + 'static>(error: E) -> Box<dyn core::error::Error> {
+            Box::new(error)
+        }
+        assert_eq!(as_error(TimecodeFrameError::HoursOutOfRange(24)).to_string(), "Hours 24 out of range 0..=23");
+        #[cfg(feature = "decode_ltc")]
+        {
+            use crate::ltc_decoder::{ComplianceViolation, DecodeErrorKind};
+            assert_eq!(as_error(DecodeErrorKind::Overdue).to_string(), "No threshold-cross within a bit");
+            assert_eq!(as_error(ComplianceViolation::InvalidBcd).to_string(), "Units digit above 9");
+        }
+    }
+
+    #[test]
+    fn test_add_frame_drop_frame() {
+        let mut frame = TimecodeFrame::new(0, 0, 59, 29, ThirtyDropFrame);
+        frame.add_frame();
+        assert_eq!(frame, TimecodeFrame::new(0, 1, 0, 2, ThirtyDropFrame));
+        let mut frame = TimecodeFrame::new(0, 9, 59, 29, ThirtyDropFrame);
+        frame.add_frame();
+        assert_eq!(frame, TimecodeFrame::new(0, 10, 0, 0, ThirtyDropFrame));
+        assert!(!FramesPerSecond::Thirty.is_drop_frame());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_timecode_frame() {
+        let frame = TimecodeFrame::new(10, 20, 30, 12, FramesPerSecond::TwentyFive);
+        let json = serde_json::to_string(&frame).unwrap();
+        assert_eq!(json, r#"{"hours":10,"minutes":20,"seconds":30,"frames":12,"frames_per_second":"TwentyFive"}"#);
+        assert_eq!(serde_json::from_str::<TimecodeFrame>(&json).unwrap(), frame);
+    }
 }
@@ -0,0 +1,91 @@
+//! Logs the internal events of the decoder with the `log` crate, so decode hiccups of production
+//! services can be correlated with other events in their logs
+
+use crate::ltc_decoder::{DecodeErrorKind, DecoderMonitor, Sample};
+use crate::{SyncState, TimecodeFrame};
+
+/// A `DecoderMonitor` that logs sync state changes at info level (warn if the lock was lost),
+/// the errors at debug level (warn for the mismatches that are reported once) and every frame at
+/// trace level. Every message tells the index of the sample it happened at
+#[derive(Clone, Debug, Default)]
+pub struct LogMonitor {
+    /// Number of samples passed to on_sample
+    samples: u64,
+}
+
+impl LogMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Index of the last sample
+    fn sample_index(&self) -> u64 {
+        self.samples.saturating_sub(1)
+    }
+}
+
+impl<T: Sample> DecoderMonitor<T> for LogMonitor {
+    fn on_sample(&mut self, _sample: T) {
+        self.samples += 1;
+    }
+    fn on_error(&mut self, kind: DecodeErrorKind) {
+        match kind {
+            DecodeErrorKind::SamplingRateMismatch | DecodeErrorKind::FrameRateMismatch | DecodeErrorKind::NonStandardDropFrame => {
+                log::warn!("{} at sample {}", kind, self.sample_index())
+            }
+            _ => log::debug!("{} at sample {}", kind, self.sample_index()),
+        }
+    }
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        log::trace!("Frame {} at sample {}", frame, self.sample_index());
+    }
+    fn on_sync_state(&mut self, sync_state: SyncState) {
+        match sync_state {
+            SyncState::Freewheeling | SyncState::Unlocked => log::warn!("Sync state {:?} at sample {}", sync_state, self.sample_index()),
+            SyncState::Locking | SyncState::Locked => log::info!("Sync state {:?} at sample {}", sync_state, self.sample_index()),
+        }
+    }
+    fn observes_sync_state(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::log_monitor::LogMonitor;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig};
+    use crate::TimecodeFrame;
+
+    struct TestLogger(Mutex<Vec<(log::Level, String)>>);
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+        fn log(&self, record: &log::Record) {
+            self.0.lock().unwrap().push((record.level(), record.args().to_string()));
+        }
+        fn flush(&self) {}
+    }
+
+    static LOGGER: TestLogger = TestLogger(Mutex::new(Vec::new()));
+
+    #[test]
+    fn test_log() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Info);
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), LogMonitor::new());
+        for sample in samples.iter().chain(&[0; 48_000]) {
+            decoder.get_timecode_frame(*sample);
+        }
+        let messages = LOGGER.0.lock().unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[1], (log::Level::Info, "Sync state Locked at sample 5376".to_string()));
+        assert_eq!(messages[2].0, log::Level::Warn);
+        assert!(messages[3].1.starts_with("Sync state Unlocked"));
+    }
+}
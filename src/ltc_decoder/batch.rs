@@ -0,0 +1,58 @@
+use alloc::vec::Vec;
+
+use crate::ltc_decoder::{DecoderMonitor, LtcDecoder, Sample, TimecodeFrameExt};
+use crate::TimecodeFrame;
+
+/// Decodes all timecode-frames of a buffer of samples, for targets with an allocator that decode
+/// recordings in one go. Returns the frames together with the index of the sample at which they
+/// were decoded, like `wav_file::decode_wav` does with std
+pub fn decode_samples<T: Sample>(samples: &[T], sampling_rate: u32) -> Vec<(u64, TimecodeFrame)> {
+    LtcDecoder::<T>::new(sampling_rate).decode_frames(samples)
+}
+
+impl<T: Sample, M: DecoderMonitor<T>> LtcDecoder<T, M> {
+    /// Decodes a buffer and collects the frames together with the index of the sample at which they
+    /// were decoded. The index counts all samples pushed to the decoder, so it continues over buffers
+    pub fn decode_frames(&mut self, samples: &[T]) -> Vec<(u64, TimecodeFrame)> {
+        self.decode_frames_strided(samples, 1, 0)
+    }
+    /// Like decode_frames, but decodes every stride-th sample starting at offset, e.g. a channel of
+    /// an interleaved buffer
+    pub fn decode_frames_strided(&mut self, buffer: &[T], stride: usize, offset: usize) -> Vec<(u64, TimecodeFrame)> {
+        buffer.iter().skip(offset).step_by(stride.max(1))
+            .filter_map(|sample| self.get_timecode_frame(*sample).map(|frame| (self.samples_received - 1, frame)))
+            .collect()
+    }
+    /// Like decode_frames, but collects the frames with their flags, user bits, parity and timing
+    pub fn decode_frames_ext(&mut self, samples: &[T]) -> Vec<TimecodeFrameExt> {
+        samples.iter().filter_map(|sample| self.get_timecode_frame_ext(*sample)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::{decode_samples, LtcDecoder};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_decode_samples() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let frames = decode_samples(&samples, 48_000);
+        assert_eq!(frames.len(), 9);
+        assert_eq!(frames[0], (1920 + 1536, TimecodeFrame::new(10, 0, 0, 1, TwentyFive)));
+
+        // The index continues over buffers
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let (first, second) = samples.split_at(5000);
+        let mut split = decoder.decode_frames(first);
+        split.extend(decoder.decode_frames(second));
+        assert_eq!(split, frames);
+
+        let interleaved: Vec<i32> = samples.iter().flat_map(|sample| [0, *sample]).collect();
+        assert_eq!(LtcDecoder::<i32>::new(48_000).decode_frames_strided(&interleaved, 2, 1), frames);
+        let ext = LtcDecoder::<i32>::new(48_000).decode_frames_ext(&samples);
+        assert_eq!(ext.iter().map(|frame| frame.frame.clone()).collect::<Vec<_>>(), frames.iter().map(|(_, frame)| frame.clone()).collect::<Vec<_>>());
+    }
+}
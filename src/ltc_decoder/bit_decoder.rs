@@ -1,6 +1,8 @@
-use crate::ltc_decoder::Sample;
+use crate::ltc_decoder::calibration::{CalibrationReport, PeriodAccumulator, SignalLevels};
+use crate::ltc_decoder::{DecodeErrorKind, DecoderMonitor, LtcDecoderConfig, Sample, SignalLevel};
 
 /// Contains the state of received half-bits and bits by ThresholdCrossDetector
+#[derive(Clone)]
 enum BitDecoderState {
     /// Waiting for a full-bit to receive to get in sync
     OutOfSync,
@@ -15,7 +17,7 @@ pub(crate) enum BitVal {
     /// No bit detected after pushing last audio sample
     None,
     /// Invalid state detected-> Invalidate decoder
-    Invalid,
+    Invalid(DecodeErrorKind),
     /// True (1)
     True,
     /// False (0)
@@ -23,6 +25,7 @@ pub(crate) enum BitVal {
 }
 
 /// Reads sample by sample, detects the heartbeat of bits in ltc stream and returns 0s and 1s
+#[derive(Clone)]
 pub(crate) struct BitDecoder<T: Sample> {
     /// ThresholdCrossDetector returns bits and half-bits.
     threshold_cross_detector: ThresholdCrossDetector<T>,
@@ -33,9 +36,9 @@ pub(crate) struct BitDecoder<T: Sample> {
 
 impl<T: Sample> BitDecoder<T> {
     /// Constructor
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(config: &LtcDecoderConfig) -> Self {
         Self {
-            threshold_cross_detector: ThresholdCrossDetector::new(),
+            threshold_cross_detector: ThresholdCrossDetector::new(config),
             state: BitDecoderState::OutOfSync,
         }
     }
@@ -45,13 +48,64 @@ impl<T: Sample> BitDecoder<T> {
         self.state = BitDecoderState::OutOfSync;
         self.threshold_cross_detector.invalidate();
     }
+    /// Tells if the heartbeat of the bits has been found
+    pub(crate) fn is_in_sync(&self) -> bool {
+        !matches!(self.state, BitDecoderState::OutOfSync)
+    }
+    /// Counts a sample that is not decoded towards the current period
+    pub(crate) fn skip_sample(&mut self) {
+        self.threshold_cross_detector.skip_sample();
+    }
+    /// The learned duration of a half-bit in samples. None if the decoder is not in sync
+    pub(crate) fn half_bit_samples(&self) -> Option<usize> {
+        let state = &self.threshold_cross_detector.state;
+        state.valid.then_some(state.half_size)
+    }
+    /// The learned durations of a half-bit and a bit with fractions of samples. None if the decoder
+    /// is not in sync
+    pub(crate) fn bit_timing(&self) -> Option<(f32, f32)> {
+        let state = &self.threshold_cross_detector.state;
+        state.valid.then_some((state.half_size_smoothed, state.full_size_smoothed))
+    }
+    /// Starts with learned durations of a half-bit and a bit, so the first periods are classified
+    /// without learning them first
+    pub(crate) fn restore_bit_timing(&mut self, half_bit: f32, bit: f32) {
+        self.invalidate();
+        self.threshold_cross_detector.state.restore(half_bit, bit);
+    }
+    /// Tells if the level of the received signal is high enough to be decoded
+    pub(crate) fn signal_level(&self) -> SignalLevel {
+        self.threshold_cross_detector.sample_bounds.signal_level
+    }
+    pub(crate) fn calibration(&self) -> CalibrationReport<T> {
+        let state = &self.threshold_cross_detector.state;
+        let bounds = &self.threshold_cross_detector.sample_bounds;
+        CalibrationReport {
+            half_bit: state.valid.then(|| state.half_periods.stats(state.half_size)),
+            bit: state.valid.then(|| state.full_periods.stats(state.full_size)),
+            levels: bounds.valid.then_some(SignalLevels {
+                min: bounds.min_value,
+                threshold: bounds.threshold,
+                max: bounds.max_value,
+            }),
+        }
+    }
     /// Every audio sample-point that is received is pushed in this function. It will return if a bit
     /// is detected by returning true (1) or false (0)
     /// The function feeds and handles detection of audio-level for high and low as well as bit-heartbeat detection
-    pub(crate) fn get_bit(&mut self, sample: T) -> BitVal {
-        match self.threshold_cross_detector.crosses(sample) {
+    pub(crate) fn get_bit<M: DecoderMonitor<T>>(&mut self, sample: T, monitor: &mut M) -> BitVal {
+        let bit = self.get_bit_unmonitored(sample, monitor);
+        match bit {
+            BitVal::True => monitor.on_bit(true),
+            BitVal::False => monitor.on_bit(false),
+            _ => {}
+        }
+        bit
+    }
+    fn get_bit_unmonitored<M: DecoderMonitor<T>>(&mut self, sample: T, monitor: &mut M) -> BitVal {
+        match self.threshold_cross_detector.crosses(sample, monitor) {
             ThresholdCross::None => BitVal::None,
-            ThresholdCross::Invalid => BitVal::Invalid,
+            ThresholdCross::Invalid(kind) => BitVal::Invalid(kind),
             ThresholdCross::Short => {
                 // half bit received
                 match self.state {
@@ -78,7 +132,7 @@ impl<T: Sample> BitDecoder<T> {
                     }
                     BitDecoderState::HalfBitReceived => {
                         // Expected a half-bit in the state of sync
-                        BitVal::Invalid
+                        BitVal::Invalid(DecodeErrorKind::MissingHalfBit)
                     }
                 }
             }
@@ -88,6 +142,7 @@ impl<T: Sample> BitDecoder<T> {
 
 
 /// When reading audio samples, the SampleBounds calculate what high and low means in the audio signal for detecting LTC
+#[derive(Clone)]
 struct SampleBounds<T: Sample> {
     /// Tells, if the last received audio-samples determine a valid high and low status
     valid: bool,
@@ -97,6 +152,24 @@ struct SampleBounds<T: Sample> {
     min_value: T,
     /// The treshold between high and low value for samples
     threshold: T,
+    /// Twice the value a sample needs to exceed to be high. Together with lower_threshold_x2 it
+    /// adds hysteresis, so ringing and noise around the threshold do not cause additional
+    /// threshold-crosses. Doubled values avoid rounding of the threshold on signals with an
+    /// amplitude of just a few steps
+    upper_threshold_x2: i128,
+    /// Twice the value a sample needs to be below to be low
+    lower_threshold_x2: i128,
+    /// Tells if the last recalculation found a signal that is strong enough to be decoded
+    signal_level: SignalLevel,
+    /// Signals with a lower peak-to-peak value are ignored
+    noise_gate_peak_to_peak: i128,
+    /// Fixed threshold between high and low. None takes the middle between the peaks
+    midpoint: Option<i128>,
+    /// Tells if a sample was received. The history is filled with the first sample, so an offset
+    /// of unsigned samples does not distort the first recalculation
+    started: bool,
+    /// The last state returned. Kept while a sample is between lower_threshold and upper_threshold
+    last_is_high: bool,
     /// Keeps the received samples
     sample_history: [T; 255],
     /// Received samples since the last recalculation
@@ -104,76 +177,108 @@ struct SampleBounds<T: Sample> {
 }
 
 impl<T: Sample> SampleBounds<T> {
+    /// The hysteresis on each side of the threshold is the peak-to-peak value divided by this
+    const HYSTERESIS_DIVISOR: i128 = 16;
+    /// Signals with a smaller peak-to-peak value can not be told apart from noise of the least
+    /// significant bit
+    const MIN_PEAK_TO_PEAK: i128 = 2;
+
     /// Creates a new starter instance of SampleBounds
-    fn new() -> SampleBounds<T> {
+    fn new(config: &LtcDecoderConfig) -> SampleBounds<T> {
+        let midpoint = config.sample_midpoint.map(i128::from);
         Self {
             valid: false,
             max_value: T::zero(),
             min_value: T::zero(),
             threshold: T::zero(),
-            sample_history: [T::zero(); 255],
+            upper_threshold_x2: 0,
+            lower_threshold_x2: 0,
+            signal_level: SignalLevel::Unknown,
+            noise_gate_peak_to_peak: config.noise_gate_peak_to_peak as i128,
+            midpoint,
+            started: false,
+            last_is_high: false,
+            sample_history: [midpoint.and_then(T::from_i128).unwrap_or_else(T::zero); 255],
             received_count: 0,
         }
     }
     /// Every received sample should be pushed here for history purposes.
     /// Every 255 samples it will recalculated
-    fn push_sample(&mut self, sample: T) {
+    fn push_sample<M: DecoderMonitor<T>>(&mut self, sample: T, monitor: &mut M) {
+        if !self.started && self.midpoint.is_none() {
+            self.sample_history = [sample; 255];
+        }
+        self.started = true;
         self.sample_history.rotate_left(1);
         self.sample_history[0] = sample;
         self.received_count += 1;
         if self.received_count == u8::MAX {
             self.received_count = 0;
             self.recalculate();
+            if self.valid {
+                monitor.on_threshold(self.threshold);
+            }
         }
     }
     /// Recalculates min_value, max_value and threshold
     pub fn recalculate(&mut self) {
-        let min_val = self.sample_history.iter().min();
-        let max_val = self.sample_history.iter().max();
-        if min_val.is_none() || max_val.is_none() {
+        let (Some(min_val), Some(max_val)) = (self.sample_history.iter().min(), self.sample_history.iter().max()) else {
             self.invalidate();
             return;
-        }
-        let min_val = *min_val.unwrap();
-        let max_val = *max_val.unwrap();
-
-        self.min_value = min_val;
-        self.max_value = max_val;
+        };
+        self.min_value = *min_val;
+        self.max_value = *max_val;
         self.recalculate_threshold();
     }
     /// Recalculates the threshold from max_value and min_value
     fn recalculate_threshold(&mut self) {
-        let max_half = self.max_value.to_i128();
-        let min_half = self.min_value.to_i128();
-        if min_half.is_none() || max_half.is_none() {
+        let (Some(max_value), Some(min_value)) = (self.max_value.to_i128(), self.min_value.to_i128()) else {
+            self.valid = false;
+            return;
+        };
+        if max_value - min_value < Self::MIN_PEAK_TO_PEAK {
+            self.signal_level = SignalLevel::TooLow;
             self.valid = false;
             return;
         }
-        let max_half = max_half.unwrap() / 2;
-        let min_half = min_half.unwrap() / 2;
-        let average_value = T::from_i128(max_half + min_half);
-
-        if average_value.is_none() {
+        if max_value - min_value < self.noise_gate_peak_to_peak {
+            self.signal_level = SignalLevel::BelowNoiseGate;
             self.valid = false;
             return;
         }
+        let sum = self.midpoint.map_or(max_value + min_value, |midpoint| 2 * midpoint);
+        let Some(threshold) = T::from_i128((sum + 1).div_euclid(2)) else {
+            self.valid = false;
+            return;
+        };
+        let hysteresis_x2 = 2 * (max_value - min_value) / Self::HYSTERESIS_DIVISOR;
         self.valid = true;
-        self.threshold = average_value.unwrap();
+        self.signal_level = SignalLevel::Valid;
+        self.threshold = threshold;
+        self.upper_threshold_x2 = sum + hysteresis_x2;
+        self.lower_threshold_x2 = sum - hysteresis_x2;
     }
     /// Tells if a sample is high or low. May return None if the state of sample_bounds is not valid
     /// The function stores the sample to calibrate (and recalibrate periodially) what high or low means
-    fn is_high(&mut self, sample: T) -> Option<bool> {
-        self.push_sample(sample);
+    fn is_high<M: DecoderMonitor<T>>(&mut self, sample: T, monitor: &mut M) -> Option<bool> {
+        self.push_sample(sample, monitor);
         if !self.valid {
-            None
-        } else {
-            Some(self.threshold < sample)
+            return None;
         }
+        let sample_x2 = 2 * sample.to_i128()?;
+        if sample_x2 > self.upper_threshold_x2 {
+            self.last_is_high = true;
+        } else if sample_x2 < self.lower_threshold_x2 {
+            self.last_is_high = false;
+        }
+        Some(self.last_is_high)
     }
     /// In case of any unexpected event in the audio stream, invalidate helps to reset the system
     /// and start from the beginning again
     fn invalidate(&mut self) {
         self.threshold = T::zero();
+        self.upper_threshold_x2 = 0;
+        self.lower_threshold_x2 = 0;
         self.max_value = T::zero();
         self.min_value = T::zero();
         self.valid = false;
@@ -191,26 +296,44 @@ enum ThresholdCross {
     ///No threshold cross detected on sample point
     None,
     /// Invalid threshold cross detected on sample point -> Invalidate parents
-    Invalid,
+    Invalid(DecodeErrorKind),
     /// Threshold cross detected for a short period (= half of a 1)
     Short,
     /// Threshold cross detected for a long period (=0)
     Long,
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 /// Calculates the lenght of a bit / a half-bit and keeps track of it
 struct ThresholdCrossState {
     valid: bool,
     unknown_size: usize,
     half_size: usize,
     full_size: usize,
+    /// How many percent a duration may be shorter than the learned duration to be the same
+    tolerance_percent: usize,
+    /// The periods classified as half-bits since the state got valid
+    half_periods: PeriodAccumulator,
+    /// The periods classified as bits since the state got valid
+    full_periods: PeriodAccumulator,
+    /// Number of periods the learned durations need to follow a drift. 0 disables following
+    drift_time_constant: f32,
+    /// The learned durations with fractions of samples, to follow drifts slower than a sample
+    half_size_smoothed: f32,
+    full_size_smoothed: f32,
 }
 
 impl ThresholdCrossState {
+    /// Higher tolerances would make half-bits and bits indistinguishable
+    const MAX_TOLERANCE_PERCENT: usize = 25;
+
     /// Constructor
-    fn new() -> Self {
-        let mut s = Self::default();
+    fn new(config: &LtcDecoderConfig) -> Self {
+        let mut s = Self {
+            tolerance_percent: (config.timing_tolerance_percent as usize).min(Self::MAX_TOLERANCE_PERCENT),
+            drift_time_constant: config.drift_time_constant as f32,
+            ..Self::default()
+        };
         s.invalidate();
         s
     }
@@ -218,13 +341,44 @@ impl ThresholdCrossState {
     /// many samples were in between two states. If not valid it needs at least one half-bit and
     /// one bit to detected to have a valid state to tell what a half-bit and a bit is
     fn cross_from_cross_size(&mut self, size: usize) -> ThresholdCross {
+        let was_valid = self.valid;
+        let cross = self.classify(size);
+        if !was_valid && self.valid {
+            self.half_size_smoothed = self.half_size as f32;
+            self.full_size_smoothed = self.full_size as f32;
+        }
+        match cross {
+            ThresholdCross::Short => {
+                self.half_periods.push(size);
+                if was_valid {
+                    self.half_size = Self::follow_drift(&mut self.half_size_smoothed, size, self.drift_time_constant);
+                }
+            }
+            ThresholdCross::Long => {
+                self.full_periods.push(size);
+                if was_valid {
+                    self.full_size = Self::follow_drift(&mut self.full_size_smoothed, size, self.drift_time_constant);
+                }
+            }
+            _ => {}
+        }
+        cross
+    }
+    /// Moves the smoothed duration towards a received period and returns it rounded
+    fn follow_drift(smoothed: &mut f32, size: usize, time_constant: f32) -> usize {
+        if time_constant > 0.0 {
+            *smoothed += (size as f32 - *smoothed) / time_constant;
+        }
+        (*smoothed + 0.5) as usize
+    }
+    fn classify(&mut self, size: usize) -> ThresholdCross {
         if !self.valid {
             // Didn't find a short and a long item yet
             if self.unknown_size == 0 {
                 self.unknown_size = size;
                 return ThresholdCross::None;
             }
-            if Self::is_approx_same(&self.unknown_size, &size) {
+            if Self::is_approx_same(&self.unknown_size, &size, self.tolerance_percent) {
                 return ThresholdCross::None;
             }
             if Self::is_approx_half(&size, &self.unknown_size) {
@@ -239,15 +393,32 @@ impl ThresholdCrossState {
                 self.valid = true;
                 return ThresholdCross::Long;
             }
-            return ThresholdCross::Invalid;
+            return ThresholdCross::Invalid(DecodeErrorKind::SteadyViolation);
         }
-        if Self::is_approx_same(&size, &self.full_size) {
+        if Self::is_approx_same(&size, &self.full_size, self.tolerance_percent) {
             return ThresholdCross::Long;
         }
-        if Self::is_approx_same(&size, &self.half_size) {
+        if Self::is_approx_same(&size, &self.half_size, self.tolerance_percent) {
             return ThresholdCross::Short;
         }
-        ThresholdCross::Invalid
+        if size > self.full_size {
+            ThresholdCross::Invalid(DecodeErrorKind::Overdue)
+        } else {
+            ThresholdCross::Invalid(DecodeErrorKind::SteadyViolation)
+        }
+    }
+    /// Takes the durations of half-bits and bits as learned. Ignored if they don't fit together
+    fn restore(&mut self, half_size_smoothed: f32, full_size_smoothed: f32) {
+        self.invalidate();
+        let (half_size, full_size) = ((half_size_smoothed + 0.5) as usize, (full_size_smoothed + 0.5) as usize);
+        if half_size == 0 || !Self::is_approx_half(&half_size, &full_size) {
+            return;
+        }
+        self.half_size = half_size;
+        self.full_size = full_size;
+        self.half_size_smoothed = half_size_smoothed;
+        self.full_size_smoothed = full_size_smoothed;
+        self.valid = true;
     }
     /// Invalidates the state -> the duration of half-bits and bits will be recalculated until the
     /// structs starts returning cross-types again
@@ -256,6 +427,8 @@ impl ThresholdCrossState {
         self.valid = false;
         self.half_size = 0;
         self.full_size = 0;
+        self.half_periods = PeriodAccumulator::default();
+        self.full_periods = PeriodAccumulator::default();
     }
     /// Tells if a value is approximately half to a compared value. Used to determine how long a
     /// half-bit and a bit is
@@ -271,15 +444,79 @@ impl ThresholdCrossState {
     }
     /// Tells if a value is approximately the same to a compared value. Used to determine how long a
     /// half-bit and a bit is
-    fn is_approx_same(check: &usize, comp: &usize) -> bool {
-        let low = (comp * 4) / 5;
-        let high = (comp * 5) / 4;
+    fn is_approx_same(check: &usize, comp: &usize, tolerance_percent: usize) -> bool {
+        let low = (comp * (100 - tolerance_percent)) / 100;
+        let high = (comp * 100) / (100 - tolerance_percent);
         check >= &low && check <= &high
     }
 }
 
+/// Signals with an asymmetric duty-cycle (e.g. after AC-coupling or with a DC-offset) stretch
+/// every high period and shorten every low period by the same amount. Since biphase-mark is
+/// DC-free, this amount can be estimated from the average length of high and low periods and
+/// removed before the periods are classified
+#[derive(Clone)]
+struct DutyCycleCompensation {
+    /// The lengths of the last high periods
+    high_sizes: [usize; Self::HISTORY_SIZE],
+    /// The lengths of the last low periods
+    low_sizes: [usize; Self::HISTORY_SIZE],
+    /// Number of high periods received (up to HISTORY_SIZE)
+    high_count: usize,
+    /// Number of low periods received (up to HISTORY_SIZE)
+    low_count: usize,
+}
+
+impl DutyCycleCompensation {
+    const HISTORY_SIZE: usize = 32;
+    /// Minimal periods per polarity that need to be received until the compensation is applied
+    const MIN_COUNT: usize = 4;
+
+    /// Constructor
+    fn new() -> Self {
+        Self {
+            high_sizes: [0; Self::HISTORY_SIZE],
+            low_sizes: [0; Self::HISTORY_SIZE],
+            high_count: 0,
+            low_count: 0,
+        }
+    }
+    /// Stores the length of a period between two threshold-crosses and returns the length
+    /// corrected by the measured asymmetry
+    fn compensate(&mut self, size: usize, was_high: bool) -> usize {
+        let (sizes, count) = if was_high {
+            (&mut self.high_sizes, &mut self.high_count)
+        } else {
+            (&mut self.low_sizes, &mut self.low_count)
+        };
+        sizes.rotate_right(1);
+        sizes[0] = size;
+        if *count < Self::HISTORY_SIZE {
+            *count += 1;
+        }
+        let offset = self.offset();
+        let corrected = if was_high { size as isize - offset } else { size as isize + offset };
+        if corrected < 1 {
+            1
+        } else {
+            corrected as usize
+        }
+    }
+    /// Returns how many samples a high period is longer than it should be (and a low period shorter)
+    fn offset(&self) -> isize {
+        let count = self.high_count.min(self.low_count);
+        if count < Self::MIN_COUNT {
+            return 0;
+        }
+        let high_sum: usize = self.high_sizes[..count].iter().sum();
+        let low_sum: usize = self.low_sizes[..count].iter().sum();
+        (high_sum as isize - low_sum as isize) / (2 * count as isize)
+    }
+}
+
 /// The detector takes audio smaples one after another and eventually will return if a half-bit
 /// or a bit was detected on a threshold cross.
+#[derive(Clone)]
 struct ThresholdCrossDetector<T: Sample> {
     /// Calculates and holds the threshold, when a signal is low or high
     sample_bounds: SampleBounds<T>,
@@ -292,30 +529,40 @@ struct ThresholdCrossDetector<T: Sample> {
     count: usize,
     /// Calculates and holds information about how long a half-bit and bit is.
     state: ThresholdCrossState,
+    /// Corrects the length of periods for signals with asymmetric duty-cycle
+    duty_cycle: DutyCycleCompensation,
+    /// Threshold-crosses within this number of samples after the last one are ignored
+    debounce_samples: usize,
 }
 
 
 impl<T: Sample> ThresholdCrossDetector<T> {
     /// Constructor
-    fn new() -> Self {
+    fn new(config: &LtcDecoderConfig) -> Self {
         Self {
-            sample_bounds: SampleBounds::new(),
+            sample_bounds: SampleBounds::new(config),
             counting: false,
             is_high: None,
             count: 0,
-            state: ThresholdCrossState::new(),
+            state: ThresholdCrossState::new(config),
+            duty_cycle: DutyCycleCompensation::new(),
+            debounce_samples: config.debounce_samples,
         }
     }
 
     /// Used to find threshold-crosses. Returns if a bit or a half-bit duration cross has been detected
-    fn crosses(&mut self, sample: T) -> ThresholdCross {
-        if let Some(is_high) = self.sample_bounds.is_high(sample) {
-            if self.is_high.is_none() {
+    fn crosses<M: DecoderMonitor<T>>(&mut self, sample: T, monitor: &mut M) -> ThresholdCross {
+        if let Some(is_high) = self.sample_bounds.is_high(sample, monitor) {
+            let Some(was_high) = self.is_high else {
                 // Initial setting of current is-high
                 self.is_high = Some(is_high);
                 return ThresholdCross::None;
+            };
+            let mut changed = was_high != is_high;
+            if changed && self.counting && self.count < self.debounce_samples {
+                // Ringing right after the last threshold-cross
+                changed = false;
             }
-            let changed = self.is_high.unwrap() != is_high;
             if changed {
                 self.is_high = Some(is_high);
             }
@@ -326,19 +573,33 @@ impl<T: Sample> ThresholdCrossDetector<T> {
                 }
                 return ThresholdCross::None;
             }
-            self.count += 1;
+            // Saturates on 32-bit targets if the signal stops crossing the threshold for a day
+            self.count = self.count.saturating_add(1);
             if changed {
-                let count = self.count;
+                let count = self.duty_cycle.compensate(self.count, !is_high);
                 self.count = 0;
+                monitor.on_threshold_cross(count);
                 return self.state.cross_from_cross_size(count);
             }
             ThresholdCross::None
+        } else if self.is_high.is_some() {
+            // The signal got lost (e.g. it dropped below the noise gate). Restart synchronizing
+            ThresholdCross::Invalid(DecodeErrorKind::ThresholdInvalid)
         } else {
             //Sample bounds does not know the treshold for low and high bits at the moment
             ThresholdCross::None
         }
     }
+    /// Lets the current period grow by a sample without looking at it. The threshold-cross is
+    /// detected at the next decoded sample
+    fn skip_sample(&mut self) {
+        if self.counting {
+            self.count = self.count.saturating_add(1);
+        }
+    }
     /// Used to invalidate the whole decoding system in case unexpected data is received.
+    /// The duty-cycle compensation is kept, as it is independent of the sync-state and needed to
+    /// get in sync with asymmetric signals again
     fn invalidate(&mut self) {
         self.counting = false;
         self.is_high = None;
@@ -351,26 +612,60 @@ impl<T: Sample> ThresholdCrossDetector<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::ltc_decoder::bit_decoder::{SampleBounds, ThresholdCrossState};
+    use crate::ltc_decoder::bit_decoder::{DutyCycleCompensation, SampleBounds, ThresholdCrossState};
+    use crate::ltc_decoder::{LtcDecoderConfig, NoMonitor};
+
+    #[test]
+    fn test_follow_drift() {
+        let config = LtcDecoderConfig { drift_time_constant: 10, ..LtcDecoderConfig::default() };
+        let mut state = ThresholdCrossState::new(&config);
+        state.cross_from_cross_size(24);
+        state.cross_from_cross_size(12);
+        assert_eq!((state.half_size, state.full_size), (12, 24));
+        // The bits get slower by one sample
+        for _ in 0..100 {
+            state.cross_from_cross_size(25);
+        }
+        assert_eq!((state.half_size, state.full_size), (12, 25));
+        let mut state = ThresholdCrossState::new(&LtcDecoderConfig { drift_time_constant: 0, ..config });
+        state.cross_from_cross_size(24);
+        state.cross_from_cross_size(12);
+        for _ in 0..100 {
+            state.cross_from_cross_size(25);
+        }
+        assert_eq!(state.full_size, 24);
+    }
 
     #[test]
     fn test_recalculate_threshold() {
-        let mut b = SampleBounds::<i32>::new();
+        let mut b = SampleBounds::<i32>::new(&LtcDecoderConfig::default());
         b.max_value = 12;
         b.min_value = -8;
         b.recalculate_threshold();
         assert_eq!(b.threshold, 2);
     }
 
+    #[test]
+    fn test_midpoint() {
+        let config = LtcDecoderConfig { sample_midpoint: Some(2048), ..LtcDecoderConfig::default() };
+        let mut b = SampleBounds::<u16>::new(&config);
+        // A spike doesn't move the threshold
+        b.max_value = 4000;
+        b.min_value = 1748;
+        b.recalculate_threshold();
+        assert_eq!(b.threshold, 2048);
+        assert!(b.valid);
+    }
+
     #[test]
     fn test_recalculate() {
-        let mut b = SampleBounds::<i32>::new();
+        let mut b = SampleBounds::<i32>::new(&LtcDecoderConfig::default());
         assert!(!b.valid);
         let mut samples = [0; 255];
         samples[102] = 234;
         samples[23] = -1;
         for sample in samples {
-            b.push_sample(sample);
+            b.push_sample(sample, &mut NoMonitor);
         }
         assert_eq!(b.max_value, 234);
         assert_eq!(b.min_value, -1);
@@ -378,6 +673,19 @@ mod tests {
         assert!(b.valid)
     }
 
+    #[test]
+    fn test_is_high_hysteresis() {
+        let mut b = SampleBounds::<i32>::new(&LtcDecoderConfig::default());
+        b.max_value = 160;
+        b.min_value = -160;
+        b.recalculate_threshold();
+        b.valid = true;
+        assert_eq!(b.is_high(100, &mut NoMonitor), Some(true));
+        assert_eq!(b.is_high(-5, &mut NoMonitor), Some(true));
+        assert_eq!(b.is_high(-100, &mut NoMonitor), Some(false));
+        assert_eq!(b.is_high(5, &mut NoMonitor), Some(false));
+        assert_eq!(b.is_high(30, &mut NoMonitor), Some(true));
+    }
 
     #[test]
     fn test_is_approx_half() {
@@ -391,6 +699,16 @@ mod tests {
         assert!(ThresholdCrossState::is_approx_half(&12, &23));
     }
 
+    #[test]
+    fn test_is_approx_same() {
+        assert!(ThresholdCrossState::is_approx_same(&80, &100, 20));
+        assert!(ThresholdCrossState::is_approx_same(&125, &100, 20));
+        assert!(!ThresholdCrossState::is_approx_same(&79, &100, 20));
+        assert!(!ThresholdCrossState::is_approx_same(&126, &100, 20));
+        assert!(ThresholdCrossState::is_approx_same(&75, &100, 25));
+        assert!(ThresholdCrossState::is_approx_same(&133, &100, 25));
+    }
+
     #[test]
     fn test_is_approx_double() {
         assert!(ThresholdCrossState::is_approx_double(&200, &100));
@@ -399,4 +717,16 @@ mod tests {
         assert!(!ThresholdCrossState::is_approx_double(&200, &150));
         assert!(!ThresholdCrossState::is_approx_double(&200, &50));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_duty_cycle_compensation() {
+        let mut d = DutyCycleCompensation::new();
+        for _ in 0..8 {
+            d.compensate(15, true);
+            d.compensate(9, false);
+        }
+        assert_eq!(d.offset(), 3);
+        assert_eq!(d.compensate(27, true), 24);
+        assert_eq!(d.compensate(21, false), 24);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::ltc_frame::LtcFrame;
+use crate::{FramesPerSecond, TimecodeFrame, UserBits};
+
+/// Decodes LTC from already demodulated bits. Useful if the biphase-mark decoding is done by
+/// hardware (e.g. FPGA) and only the framing layer is needed
+pub struct LtcBitstreamDecoder {
+    ltc_frame: LtcFrame,
+    /// Bits carry no timing information, so the frame-rate can not be detected and is set by the user
+    frames_per_second: FramesPerSecond,
+    /// User bits of the last decoded frame
+    user_bits: Option<UserBits>,
+}
+
+impl LtcBitstreamDecoder {
+    /// Constructor. Use `FramesPerSecond::Unknown` if the frame-rate of the bitstream is not known
+    pub fn new(frames_per_second: FramesPerSecond) -> Self {
+        Self {
+            ltc_frame: LtcFrame::new_empty(),
+            frames_per_second,
+            user_bits: None,
+        }
+    }
+
+    /// Push received bits one after another in this function. Once a complete frame including the
+    /// sync-word has been received, a Timecode-Frame will be returned
+    pub fn push_bit(&mut self, bit: bool) -> Option<TimecodeFrame> {
+        self.ltc_frame.shift_bit(bit);
+        let (data, _) = self.ltc_frame.get_data()?;
+        self.user_bits = Some(data.get_user_bits(&self.frames_per_second));
+        Some(data.make_ltc_frame_with_fps(self.frames_per_second.clone()))
+    }
+
+    /// The user bits of the last frame returned by push_bit
+    pub fn user_bits(&self) -> Option<UserBits> {
+        self.user_bits
+    }
+
+    /// Resets the decoder, e.g. if the bitstream was interrupted
+    pub fn invalidate(&mut self) {
+        self.ltc_frame.invalidate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::LtcBitstreamDecoder;
+    use crate::ltc_decoder::test_signal::frame_bits;
+    use crate::{TimecodeFrame, UserBits};
+    use crate::FramesPerSecond::Thirty;
+    use crate::user_bits::{TimecodeKind, UserBitsFormat};
+
+    #[test]
+    fn test_push_bits() {
+        let mut decoder = LtcBitstreamDecoder::new(TwentyFive);
+        let mut frames = [None, None, None];
+        for (i, tc) in [(10, 20, 30, 12), (10, 20, 30, 13), (10, 20, 30, 14)].iter().enumerate() {
+            for bit in frame_bits(&TimecodeFrame::new(tc.0, tc.1, tc.2, tc.3, TwentyFive)) {
+                if let Some(frame) = decoder.push_bit(bit) {
+                    frames[i] = Some(frame);
+                }
+            }
+        }
+        // The first frame is not preceded by a sync-word
+        assert_eq!(frames[0], None);
+        assert_eq!(frames[1], Some(TimecodeFrame::new(10, 20, 30, 13, TwentyFive)));
+        assert_eq!(frames[2], Some(TimecodeFrame::new(10, 20, 30, 14, TwentyFive)));
+    }
+
+    #[test]
+    fn test_user_bits_characters() {
+        for (frames_per_second, bgf0) in [(TwentyFive, 27), (Thirty, 43)] {
+            let mut decoder = LtcBitstreamDecoder::new(frames_per_second.clone());
+            let mut frame = None;
+            for _ in 0..2 {
+                let mut bits = frame_bits(&TimecodeFrame::new(10, 20, 30, 12, frames_per_second.clone()));
+                for (i, bit) in bits.iter_mut().take(64).enumerate() {
+                    let group = i / 8;
+                    if i % 8 >= 4 {
+                        *bit = (u32::from_le_bytes(*b"AB12") >> (group * 4 + i % 8 - 4)) & 1 == 1;
+                    }
+                }
+                bits[bgf0] = true;
+                for bit in bits {
+                    frame = decoder.push_bit(bit).or(frame);
+                }
+            }
+            assert_eq!(frame, Some(TimecodeFrame::new(10, 20, 30, 12, frames_per_second)));
+            let user_bits: UserBits = decoder.user_bits().unwrap();
+            assert_eq!(user_bits.format(), UserBitsFormat::EightBitCharacters);
+            assert_eq!(user_bits.characters(), Some(*b"AB12"));
+            assert_eq!(user_bits.timecode_kind(), TimecodeKind::Elapsed);
+        }
+    }
+
+    #[test]
+    fn test_time_of_day() {
+        let mut decoder = LtcBitstreamDecoder::new(TwentyFive);
+        for frames in 12..14 {
+            let mut bits = frame_bits(&TimecodeFrame::new(10, 20, 30, frames, TwentyFive));
+            // The clock flag (BGF1)
+            bits[58] = true;
+            bits.into_iter().for_each(|bit| { decoder.push_bit(bit); });
+        }
+        assert_eq!(decoder.user_bits().unwrap().timecode_kind(), TimecodeKind::TimeOfDay);
+    }
+}
@@ -0,0 +1,44 @@
+use core::ops::Deref;
+
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// The frames decoded from a block by `LtcDecoder::process_block`, each with the index of the sample
+/// in the block that completed it. Holds up to N frames without allocating. A frame takes at least
+/// 1/60 s, so N = 1 suffices for blocks shorter than 16 ms
+pub struct BlockFrames<const N: usize> {
+    frames: [(usize, TimecodeFrame); N],
+    len: usize,
+    /// Frames that did not fit
+    overflow: usize,
+}
+
+impl<const N: usize> BlockFrames<N> {
+    pub(crate) fn new() -> Self {
+        Self {
+            frames: core::array::from_fn(|_| (0, TimecodeFrame::new(0, 0, 0, 0, FramesPerSecond::Unknown))),
+            len: 0,
+            overflow: 0,
+        }
+    }
+    pub(crate) fn push(&mut self, offset: usize, frame: TimecodeFrame) {
+        match self.frames.get_mut(self.len) {
+            Some(slot) => {
+                *slot = (offset, frame);
+                self.len += 1;
+            }
+            None => self.overflow += 1,
+        }
+    }
+    /// Number of frames that were decoded but did not fit into N. Use a larger N if this is not 0
+    pub fn overflow(&self) -> usize {
+        self.overflow
+    }
+}
+
+impl<const N: usize> Deref for BlockFrames<N> {
+    type Target = [(usize, TimecodeFrame)];
+
+    fn deref(&self) -> &Self::Target {
+        &self.frames[..self.len]
+    }
+}
@@ -0,0 +1,101 @@
+/// Quantitative readout of the signal as the decoder sees it, e.g. for monitoring dashboards.
+/// Returned by `LtcDecoder::calibration`
+#[derive(PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalibrationReport<T> {
+    /// Periods between threshold-crosses that were classified as half-bits. None while the decoder
+    /// has not learned the duration of half-bits and bits
+    pub half_bit: Option<PeriodStats>,
+    /// Periods between threshold-crosses that were classified as bits
+    pub bit: Option<PeriodStats>,
+    /// None while the signal level is not valid
+    pub levels: Option<SignalLevels<T>>,
+}
+
+/// Duration of half-bits or bits in samples
+#[derive(PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeriodStats {
+    /// The learned duration that periods are compared with. It follows slow drift, see
+    /// `LtcDecoderConfig::drift_time_constant`
+    pub learned: usize,
+    /// Mean of the periods received since the decoder got in sync. 0.0 if none was received yet
+    pub mean: f64,
+    /// Standard deviation of the periods received since the decoder got in sync, the jitter
+    pub std_dev: f64,
+    pub count: u64,
+}
+
+/// The levels the decoder measured on the last samples
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalLevels<T> {
+    pub min: T,
+    /// Samples above are high, samples below are low
+    pub threshold: T,
+    pub max: T,
+}
+
+/// Calculates mean and standard deviation of periods with Welford's algorithm, so no history has
+/// to be kept
+#[derive(Default, Clone, Copy)]
+pub(crate) struct PeriodAccumulator {
+    count: u64,
+    mean: f64,
+    /// Sum of the squared differences from the mean
+    m2: f64,
+}
+
+impl PeriodAccumulator {
+    pub(crate) fn push(&mut self, period: usize) {
+        let period = period as f64;
+        self.count += 1;
+        let delta = period - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (period - self.mean);
+    }
+    pub(crate) fn stats(&self, learned: usize) -> PeriodStats {
+        let variance = if self.count > 1 { self.m2 / (self.count - 1) as f64 } else { 0.0 };
+        PeriodStats {
+            learned,
+            mean: self.mean,
+            std_dev: sqrt(variance),
+            count: self.count,
+        }
+    }
+}
+
+/// f64::sqrt needs std. Newton's method converges within a few iterations
+pub(crate) fn sqrt(value: f64) -> f64 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    let mut root = if value > 1.0 { value / 2.0 } else { 1.0 };
+    for _ in 0..64 {
+        let next = (root + value / root) / 2.0;
+        if (next - root).abs() <= f64::EPSILON * root {
+            return next;
+        }
+        root = next;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ltc_decoder::calibration::{sqrt, PeriodAccumulator};
+
+    #[test]
+    fn test_period_accumulator() {
+        let mut accumulator = PeriodAccumulator::default();
+        for period in [10, 12, 10, 12] {
+            accumulator.push(period);
+        }
+        let stats = accumulator.stats(11);
+        assert_eq!(stats.count, 4);
+        assert!((stats.mean - 11.0).abs() < 1e-9);
+        assert!((stats.std_dev - sqrt(4.0 / 3.0)).abs() < 1e-9);
+        assert!((sqrt(2.0) - core::f64::consts::SQRT_2).abs() < 1e-12);
+        assert_eq!(sqrt(0.0), 0.0);
+    }
+}
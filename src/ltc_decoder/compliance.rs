@@ -0,0 +1,100 @@
+use core::fmt::{Display, Formatter};
+
+use crate::ltc_frame::ltc_frame_data::LtcFrameData;
+use crate::{FramesPerSecond, TimecodeFrame, TimecodeFrameError};
+
+/// A constraint of SMPTE ST 12-1 that a decoded frame violates. Only checked if
+/// `LtcDecoderConfig::strict_validation` is set
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ComplianceViolation {
+    /// A units digit of the timecode is above 9
+    InvalidBcd,
+    /// Hours, minutes, seconds or frames are beyond their range
+    FieldOutOfRange,
+    /// Drop-frame timecode contains a frame number that has to be skipped
+    DroppedFrameNumber,
+    /// The polarity correction bit does not lead to an even number of zeros
+    ParityMismatch,
+    /// The drop-frame flag is set, but the frame-rate is neither 30 fps nor 60 fps. It has to be zero then
+    DropFrameFlagNotAllowed,
+    /// The color frame flag is set in 24 fps timecode, which has no color framing. It has to be zero then
+    ColorFrameFlagNotAllowed,
+}
+
+impl Display for ComplianceViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ComplianceViolation::InvalidBcd => write!(f, "Units digit above 9"),
+            ComplianceViolation::FieldOutOfRange => write!(f, "Timecode field out of range"),
+            ComplianceViolation::DroppedFrameNumber => write!(f, "Frame number is dropped in drop-frame timecode"),
+            ComplianceViolation::ParityMismatch => write!(f, "Polarity correction bit does not lead to an even number of zeros"),
+            ComplianceViolation::DropFrameFlagNotAllowed => write!(f, "Drop-frame flag set at a frame-rate without drop-frame"),
+            ComplianceViolation::ColorFrameFlagNotAllowed => write!(f, "Color frame flag set at a frame-rate without color framing"),
+        }
+    }
+}
+
+impl core::error::Error for ComplianceViolation {}
+
+impl ComplianceViolation {
+    const ALL: [Self; 6] = [
+        Self::InvalidBcd,
+        Self::FieldOutOfRange,
+        Self::DroppedFrameNumber,
+        Self::ParityMismatch,
+        Self::DropFrameFlagNotAllowed,
+        Self::ColorFrameFlagNotAllowed,
+    ];
+
+    fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// The violations of a frame as bit mask
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub(crate) struct ComplianceViolations(u8);
+
+impl ComplianceViolations {
+    /// Checks all constraints of the received data and the frame decoded from it
+    pub(crate) fn check(data: &LtcFrameData, frame: &TimecodeFrame) -> Self {
+        let mut violations = Self::default();
+        if !data.bcd_valid() {
+            violations.insert(ComplianceViolation::InvalidBcd);
+        }
+        match TimecodeFrame::try_new(frame.hours, frame.minutes, frame.seconds, frame.frames, frame.frames_per_second.clone()) {
+            Ok(_) => {}
+            Err(TimecodeFrameError::DroppedFrame) => violations.insert(ComplianceViolation::DroppedFrameNumber),
+            Err(_) => violations.insert(ComplianceViolation::FieldOutOfRange),
+        }
+        if !data.parity_valid() {
+            violations.insert(ComplianceViolation::ParityMismatch);
+        }
+        violations.0 |= Self::check_unassigned_flags(data, &frame.frames_per_second).0;
+        violations
+    }
+    /// Tells if a flag is set that ST 12-1 leaves unassigned at the frame-rate, e.g. because a
+    /// generator writes garbage into it. Checked for every frame, also without strict validation
+    pub(crate) fn unassigned_flags_set(data: &LtcFrameData, frames_per_second: &FramesPerSecond) -> bool {
+        Self::check_unassigned_flags(data, frames_per_second).0 != 0
+    }
+    fn check_unassigned_flags(data: &LtcFrameData, frames_per_second: &FramesPerSecond) -> Self {
+        let mut violations = Self::default();
+        // 59.94 fps may count drop-frame as well. The flag is not checked if the frame-rate is unknown
+        let drop_frame_allowed = matches!(frames_per_second, FramesPerSecond::ThirtyDropFrame | FramesPerSecond::Sixty | FramesPerSecond::Unknown);
+        if data.is_drop_frame() && !drop_frame_allowed {
+            violations.insert(ComplianceViolation::DropFrameFlagNotAllowed);
+        }
+        if data.is_color_frame() && *frames_per_second == FramesPerSecond::TwentyFour {
+            violations.insert(ComplianceViolation::ColorFrameFlagNotAllowed);
+        }
+        violations
+    }
+    fn insert(&mut self, violation: ComplianceViolation) {
+        self.0 |= violation.mask();
+    }
+    pub(crate) fn iter(self) -> impl Iterator<Item = ComplianceViolation> {
+        ComplianceViolation::ALL.into_iter().filter(move |violation| self.0 & violation.mask() != 0)
+    }
+}
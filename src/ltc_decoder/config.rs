@@ -0,0 +1,140 @@
+use crate::{FramesPerSecond, FramesPerSecondSet};
+
+/// Options to tune the decoder for the characteristics of the received audio signal
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LtcDecoderConfig {
+    /// Threshold-crosses that follow the previous one within this number of samples are ignored.
+    /// Helps with ringing of hard clipped signals. 0 disables debouncing
+    pub debounce_samples: usize,
+    /// Signals with a lower peak-to-peak value are not decoded, to avoid garbage frames decoded from
+    /// noise of unconnected inputs. The decoder reports `SignalLevel::BelowNoiseGate` in that case.
+    /// 0 disables the noise gate
+    pub noise_gate_peak_to_peak: u64,
+    /// How many percent a half-bit or a bit may be shorter than the learned duration. The longer
+    /// bound is derived from it (20% shorter allows 25% longer). Values above 25 make half-bits
+    /// and bits indistinguishable and are clamped
+    pub timing_tolerance_percent: u8,
+    /// Drops frames where the polarity correction bit does not lead to an even number of zeros.
+    /// Not all generators set this bit
+    pub require_parity: bool,
+    /// `LtcDecoder::extrapolated_now` returns None if no frame was decoded for this number of
+    /// frame durations
+    pub max_extrapolation_frames: u8,
+    /// Number of half-bits and bits over which the learned durations follow slow drift of the bit
+    /// period (temperature, varispeed), so long captures don't run out of the timing tolerance.
+    /// 0 keeps the durations learned when the decoder got in sync. The threshold between high
+    /// and low follows the signal anyway
+    pub drift_time_constant: u16,
+    /// Applied to all timecode returned by the decoder, e.g. to compensate the delay of downstream
+    /// processing
+    pub output_offset: TimecodeOffset,
+    /// Checks every decoded frame against SMPTE ST 12-1 and reports the violations to
+    /// `TimecodeSink::on_violation`, e.g. for quality control of generators and archives. Frames
+    /// are returned anyway
+    pub strict_validation: bool,
+    /// Returns every frame exactly this number of samples after its first sample instead of right
+    /// after its last data bit, whose timing jitters. It has to be longer than the 64 data bits
+    /// (e.g. a frame duration), later frames are returned as soon as they are decoded. Latencies
+    /// above seven frame durations at 60 fps (e.g. 5600 samples at 48 kHz) are clamped to it. None
+    /// returns frames as soon as possible
+    pub fixed_latency_samples: Option<u32>,
+    /// The frame-rate of the project. Frames decoded at another rate are returned anyway, but
+    /// reported as `DecodeErrorKind::FrameRateMismatch`. None accepts any rate
+    pub expected_frames_per_second: Option<FramesPerSecond>,
+    /// Pins the frame-rate of all decoded frames instead of detecting it from their duration, if
+    /// the rate is known in advance. Avoids misclassification of marginal signals. None detects
+    /// the rate
+    pub forced_frames_per_second: Option<FramesPerSecond>,
+    /// Restricts the detection to these frame-rates, so a measurement near the tolerance can't
+    /// end up at a rate that is never used. Frames that fit none of them are returned with
+    /// `FramesPerSecond::Unknown`. 30 fps is detected if `Thirty` or `ThirtyDropFrame` is allowed,
+    /// the drop-frame flag tells them apart. None allows all rates
+    pub allowed_frames_per_second: Option<FramesPerSecondSet>,
+    /// The sample value of silence, e.g. 2048 for raw counts of a 12-bit ADC biased at Vref/2. The
+    /// threshold between high and low stays at this value instead of following the middle between
+    /// the peaks, so spikes and asymmetric clipping don't move it. None estimates it from the peaks
+    pub sample_midpoint: Option<i64>,
+    /// Once locked, only every n-th sample is decoded to save CPU and energy on battery-powered
+    /// readers. The factor is limited so that at least 6 samples per half-bit are decoded. The
+    /// decoder returns to the full rate on any error until it is locked again. 0 and 1 decode
+    /// all samples
+    pub locked_decimation: u8,
+}
+
+/// Signed offset of the timecode. Positive values return later timecode
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimecodeOffset {
+    #[default]
+    None,
+    Frames(i32),
+    /// Decoded frames are offset by the nearest number of whole frames, the extrapolated timecode
+    /// by the exact number of samples
+    Samples(i64),
+}
+
+impl Default for LtcDecoderConfig {
+    fn default() -> Self {
+        Self {
+            debounce_samples: 0,
+            noise_gate_peak_to_peak: 0,
+            timing_tolerance_percent: 20,
+            require_parity: false,
+            max_extrapolation_frames: 4,
+            drift_time_constant: 1000,
+            output_offset: TimecodeOffset::None,
+            strict_validation: false,
+            fixed_latency_samples: None,
+            expected_frames_per_second: None,
+            forced_frames_per_second: None,
+            allowed_frames_per_second: None,
+            sample_midpoint: None,
+            locked_decimation: 1,
+        }
+    }
+}
+
+impl LtcDecoderConfig {
+    /// Config for LTC that has been recorded too hot and is hard clipped
+    pub fn clipped() -> Self {
+        Self {
+            debounce_samples: 2,
+            ..Self::default()
+        }
+    }
+    /// Config for LTC that went through lossy codecs (MP3, AAC, Bluetooth...) and has smeared
+    /// edges and pre-echo
+    pub fn lossy() -> Self {
+        Self {
+            debounce_samples: 3,
+            timing_tolerance_percent: 25,
+            require_parity: true,
+            ..Self::default()
+        }
+    }
+    /// Config for quality control: frames with invalid polarity correction bit are dropped, the
+    /// bit timing has to be close to nominal, every frame is checked against SMPTE ST 12-1 and the
+    /// timecode is not extrapolated (no freewheeling) beyond the last decoded frame
+    pub fn strict() -> Self {
+        Self {
+            timing_tolerance_percent: 15,
+            require_parity: true,
+            max_extrapolation_frames: 0,
+            strict_validation: true,
+            ..Self::default()
+        }
+    }
+    /// Config for chasing live timecode: tolerates irregular bit timing and follows varispeed
+    /// quickly. The timecode is extrapolated (freewheeling) over two seconds of dropouts at 25 fps
+    pub fn permissive() -> Self {
+        Self {
+            timing_tolerance_percent: 25,
+            require_parity: false,
+            max_extrapolation_frames: 50,
+            drift_time_constant: 250,
+            ..Self::default()
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::ltc_decoder::{LtcDecoder, Sample};
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// A frame queued by `DmaDecoder`, with the index of the sample that completed it, counted since
+/// the decoder was created
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct QueuedFrame {
+    pub sample_index: u64,
+    pub frame: TimecodeFrame,
+}
+
+/// Decodes the chunks of a double-buffered DMA transfer (I2S or ADC) on embedded targets, without
+/// per-sample calls from interrupts. Every call of process_chunk decodes at most
+/// `max_samples_per_call` samples and continues where the last call stopped, so the execution time
+/// of a call is bounded. Decoded frames are kept in a ring of Q frames, where the oldest frame is
+/// overwritten if the frames are not popped in time. Nothing is allocated
+pub struct DmaDecoder<T: Sample, const Q: usize> {
+    decoder: LtcDecoder<T>,
+    max_samples_per_call: usize,
+    /// Samples of the current chunk that were decoded by previous calls
+    chunk_position: usize,
+    queue: [QueuedFrame; Q],
+    /// Index of the oldest frame in the queue
+    head: usize,
+    len: usize,
+    /// Frames that were overwritten before they were popped
+    overflow: usize,
+}
+
+impl<T: Sample, const Q: usize> DmaDecoder<T, Q> {
+    /// Constructor. A call of process_chunk decodes at most max_samples_per_call samples (at least
+    /// 1), which should be chosen to fit the time budget of the context it is called from
+    pub fn new(decoder: LtcDecoder<T>, max_samples_per_call: usize) -> Self {
+        Self {
+            decoder,
+            max_samples_per_call: max_samples_per_call.max(1),
+            chunk_position: 0,
+            queue: core::array::from_fn(|_| QueuedFrame { sample_index: 0, frame: TimecodeFrame::new(0, 0, 0, 0, FramesPerSecond::Unknown) }),
+            head: 0,
+            len: 0,
+            overflow: 0,
+        }
+    }
+    /// Decodes the next samples of a chunk, e.g. the half of the DMA buffer that was completed last.
+    /// Returns true once the whole chunk is decoded. Until then, the same chunk has to be passed
+    /// again, which has to happen before the DMA overwrites it
+    pub fn process_chunk(&mut self, chunk: &[T]) -> bool {
+        let start = self.chunk_position.min(chunk.len());
+        let end = chunk.len().min(start + self.max_samples_per_call);
+        for sample in &chunk[start..end] {
+            if let Some(frame) = self.decoder.get_timecode_frame(*sample) {
+                self.enqueue(QueuedFrame { sample_index: self.decoder.samples_received - 1, frame });
+            }
+        }
+        if end == chunk.len() {
+            self.chunk_position = 0;
+            true
+        } else {
+            self.chunk_position = end;
+            false
+        }
+    }
+    /// Takes the oldest decoded frame from the queue
+    pub fn pop(&mut self) -> Option<QueuedFrame> {
+        if self.len == 0 {
+            return None;
+        }
+        let frame = self.queue[self.head].clone();
+        self.head = (self.head + 1) % Q;
+        self.len -= 1;
+        Some(frame)
+    }
+    /// Number of frames in the queue
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Number of frames that were overwritten before they were popped. Use a larger Q or pop more
+    /// often if this is not 0
+    pub fn overflow(&self) -> usize {
+        self.overflow
+    }
+    pub fn decoder(&self) -> &LtcDecoder<T> {
+        &self.decoder
+    }
+    pub fn decoder_mut(&mut self) -> &mut LtcDecoder<T> {
+        &mut self.decoder
+    }
+
+    fn enqueue(&mut self, frame: QueuedFrame) {
+        if Q == 0 {
+            self.overflow += 1;
+            return;
+        }
+        let tail = (self.head + self.len) % Q;
+        self.queue[tail] = frame;
+        if self.len == Q {
+            self.head = (self.head + 1) % Q;
+            self.overflow += 1;
+        } else {
+            self.len += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::{DmaDecoder, LtcDecoder, QueuedFrame};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_process_chunk() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = DmaDecoder::<i32, 2>::new(LtcDecoder::new(48_000), 100);
+        let mut frames = Vec::new();
+        // Halves of a DMA buffer of 10 ms
+        for chunk in samples.chunks(480) {
+            let mut calls = 1;
+            while !decoder.process_chunk(chunk) {
+                calls += 1;
+            }
+            assert_eq!(calls, 5);
+            frames.extend(core::iter::from_fn(|| decoder.pop()));
+        }
+        assert_eq!(decoder.overflow(), 0);
+        assert_eq!(frames.len(), 9);
+        assert_eq!(frames[0], QueuedFrame { sample_index: 1920 + 1536, frame: TimecodeFrame::new(10, 0, 0, 1, TwentyFive) });
+        assert_eq!(frames[8].frame, TimecodeFrame::new(10, 0, 0, 9, TwentyFive));
+    }
+
+    #[test]
+    fn test_queue_overflow() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = DmaDecoder::<i32, 4>::new(LtcDecoder::new(48_000), samples.len());
+        assert!(decoder.process_chunk(&samples));
+        // The oldest frames were overwritten
+        assert_eq!(decoder.len(), 4);
+        assert_eq!(decoder.overflow(), 5);
+        assert_eq!(decoder.pop().map(|queued| queued.frame), Some(TimecodeFrame::new(10, 0, 0, 6, TwentyFive)));
+        assert_eq!(decoder.len(), 3);
+    }
+}
@@ -0,0 +1,79 @@
+use num_traits::Bounded;
+
+use crate::ltc_decoder::{DecoderMonitor, LtcDecoder, Sample, TimecodeSink};
+
+/// How the channels of an interleaved buffer are combined before decoding
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Downmix {
+    /// Adds the channels, saturating at the limits of the sample type
+    Sum,
+    /// Divides the sum by the number of channels, so it never clips
+    Average,
+}
+
+impl Downmix {
+    fn mix<T: Sample + Bounded>(self, channels: &[T]) -> T {
+        let sum: f64 = channels.iter().map(|sample| sample.to_f64().unwrap_or(0.0)).sum();
+        let value = match self {
+            Downmix::Sum => sum,
+            Downmix::Average => sum / channels.len() as f64,
+        };
+        T::from_f64(value).unwrap_or(if value < 0.0 { T::min_value() } else { T::max_value() })
+    }
+}
+
+impl<T: Sample + Bounded, M: DecoderMonitor<T>> LtcDecoder<T, M> {
+    /// Combines the channels of every sample frame of an interleaved buffer before decoding, e.g.
+    /// for recordings where LTC was printed on L and R and either alone is marginal. Channels with
+    /// inverted polarity cancel each other out. Trailing samples of an incomplete sample frame are
+    /// ignored
+    pub fn push_samples_downmixed<S: TimecodeSink>(&mut self, buffer: &[T], channels: usize, downmix: Downmix, sink: &mut S) {
+        for (index, sample_frame) in buffer.chunks_exact(channels.max(1)).enumerate() {
+            self.push_sample_to_sink(downmix.mix(sample_frame), index, sink);
+        }
+        sink.on_stats(&self.stats());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::{CallbackSink, Downmix, LtcDecoder};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_downmix() {
+        let mut shape = SignalShape::new(24.0);
+        shape.high = 20_000;
+        shape.low = -20_000;
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &shape);
+        // Each channel drops out during a different frame
+        let stereo: Vec<i16> = samples.iter().enumerate().flat_map(|(index, sample)| {
+            let left = if (4 * 1920..5 * 1920).contains(&index) { 0 } else { *sample as i16 };
+            let right = if (7 * 1920..8 * 1920).contains(&index) { 0 } else { *sample as i16 };
+            [left, right]
+        }).collect();
+        let decode = |buffer: &[i16], channels: usize, downmix: Downmix| {
+            let mut frames = Vec::new();
+            LtcDecoder::<i16>::new(48_000).push_samples_downmixed(buffer, channels, downmix, &mut CallbackSink::new(|frame: &TimecodeFrame| frames.push(frame.clone())));
+            frames
+        };
+        let expected: Vec<_> = (1..10).map(|frames| TimecodeFrame::new(10, 0, 0, frames, TwentyFive)).collect();
+        // The sum clips, which keeps the shape of the signal
+        assert_eq!(decode(&stereo, 2, Downmix::Sum), expected);
+        assert_eq!(decode(&stereo, 2, Downmix::Average), expected);
+        // A single channel misses frames
+        let left: Vec<i16> = stereo.iter().step_by(2).copied().collect();
+        assert!(decode(&left, 1, Downmix::Sum).len() < expected.len());
+    }
+
+    #[test]
+    fn test_mix() {
+        assert_eq!(Downmix::Sum.mix(&[30_000_i16, 10_000]), i16::MAX);
+        assert_eq!(Downmix::Sum.mix(&[-30_000_i16, -10_000]), i16::MIN);
+        assert_eq!(Downmix::Average.mix(&[30_000_i16, 10_000]), 20_000);
+        assert_eq!(Downmix::Average.mix(&[200_u8, 100, 0]), 100);
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// Builds the output of `LtcDecoder::get_timecode_frame_as` from the fields of a decoded frame, so
+/// applications get their own compact timecode type. Without an output offset the fields are taken
+/// straight from the decoded bits, the decoder builds its own `TimecodeFrame` with this trait as well
+pub trait FrameBuilder: Sized {
+    fn build(hours: u8, minutes: u8, seconds: u8, frames: u8, frames_per_second: &FramesPerSecond) -> Self;
+}
+
+impl FrameBuilder for TimecodeFrame {
+    fn build(hours: u8, minutes: u8, seconds: u8, frames: u8, frames_per_second: &FramesPerSecond) -> Self {
+        Self::new(hours, minutes, seconds, frames, frames_per_second.clone())
+    }
+}
+
+/// BCD packed as 0xHHMMSSFF like `TimecodeFrame::to_bcd_u32`. The frame-rate is dropped
+impl FrameBuilder for u32 {
+    fn build(hours: u8, minutes: u8, seconds: u8, frames: u8, _frames_per_second: &FramesPerSecond) -> Self {
+        TimecodeFrame::pack_bcd_u32(hours, minutes, seconds, frames)
+    }
+}
@@ -0,0 +1,66 @@
+use crate::ltc_decoder::compliance::ComplianceViolations;
+use crate::ltc_frame::ltc_frame_data::LtcFrameData;
+use crate::{TimecodeFrame, UserBits};
+
+/// A decoded frame with the data it was decoded from, so the flags, user bits and violations stay
+/// with the frame while it is held back
+#[derive(Clone)]
+pub(crate) struct DecodedFrame {
+    pub(crate) frame: TimecodeFrame,
+    pub(crate) data: LtcFrameData,
+    pub(crate) user_bits: UserBits,
+    pub(crate) violations: ComplianceViolations,
+    pub(crate) consecutive_frames: u32,
+}
+
+/// Holds decoded frames back until a fixed number of samples after their start, so they are
+/// returned with constant latency
+#[derive(Clone)]
+pub(crate) struct FrameDelay {
+    latency: u64,
+    /// Frames with the index of their first sample, oldest at head
+    frames: [Option<(u64, DecodedFrame)>; Self::CAPACITY],
+    head: usize,
+    len: usize,
+}
+
+impl FrameDelay {
+    /// Enough for the longest latency, as frames are queued after their 64 data bits
+    const CAPACITY: usize = 8;
+    /// The longest latency in frame durations at 60 fps
+    const MAX_LATENCY_FRAMES: u64 = 7;
+
+    /// The longest latency that fits the queue at the sampling rate, about 116 ms
+    pub(crate) fn max_latency(sampling_rate: u32) -> u64 {
+        sampling_rate as u64 * Self::MAX_LATENCY_FRAMES / 60
+    }
+
+    pub(crate) fn new(latency: u64) -> Self {
+        Self {
+            latency,
+            frames: [const { None }; Self::CAPACITY],
+            head: 0,
+            len: 0,
+        }
+    }
+    /// Queues a decoded frame. The oldest frame is dropped if the queue is full
+    pub(crate) fn push(&mut self, start: u64, frame: DecodedFrame) {
+        if self.len == Self::CAPACITY {
+            self.head = (self.head + 1) % Self::CAPACITY;
+            self.len -= 1;
+        }
+        self.frames[(self.head + self.len) % Self::CAPACITY] = Some((start, frame));
+        self.len += 1;
+    }
+    /// Returns the oldest frame with its start, if it is due at the sample with index now
+    pub(crate) fn pop(&mut self, now: u64) -> Option<(u64, DecodedFrame)> {
+        let (start, _) = self.frames[self.head].as_ref()?;
+        if start.saturating_add(self.latency) > now {
+            return None;
+        }
+        let frame = self.frames[self.head].take();
+        self.head = (self.head + 1) % Self::CAPACITY;
+        self.len -= 1;
+        frame
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{SyncState, TimecodeFrame, UserBits};
+
+/// A decoded frame with everything the decoder knows about it, returned by
+/// `LtcDecoder::get_timecode_frame_ext` for consumers that need more than the timecode.
+///
+/// There is deliberately no single confidence value: any score would weigh the lock, the run of
+/// gapless frames and the parity arbitrarily. Derive it from `sync_state`, `consecutive_frames` and
+/// `parity_valid` as the application needs
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimecodeFrameExt {
+    pub frame: TimecodeFrame,
+    /// The drop-frame flag as received, also if the frame-rate is not 30 fps
+    pub drop_frame_flag: bool,
+    pub color_frame_flag: bool,
+    /// The binary groups and binary group flags
+    pub user_bits: UserBits,
+    /// Tells if the polarity correction bit leads to an even number of zeros
+    pub parity_valid: bool,
+    /// Tells if a flag is set that SMPTE ST 12-1 leaves unassigned at the frame-rate, like the
+    /// drop-frame flag at 25 fps or the color frame flag at 24 fps
+    pub unassigned_flags_set: bool,
+    /// The sync state after the frame, tells how far the frame can be trusted
+    pub sync_state: SyncState,
+    /// Number of frames up to this one that followed the previous one without gaps
+    pub consecutive_frames: u32,
+    /// Index of the sample the frame started at, counted from the first sample pushed into the
+    /// decoder
+    pub start_sample: u64,
+}
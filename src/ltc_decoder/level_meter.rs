@@ -0,0 +1,65 @@
+use crate::ltc_decoder::calibration::sqrt;
+use crate::ltc_decoder::Sample;
+
+/// Level of the input signal measured over `LevelMeter::WINDOW_MS`. Both values are in sample units
+/// and relative to the mean of the window, so DC-offsets and unsigned samples are measured the same
+#[derive(PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputLevel {
+    /// The largest distance of a sample from the mean
+    pub peak: f64,
+    pub rms: f64,
+}
+
+/// Measures peak and RMS of the samples pushed into the decoder window by window
+#[derive(Clone)]
+pub(crate) struct LevelMeter {
+    window_size: u32,
+    count: u32,
+    sum: f64,
+    sum_of_squares: f64,
+    min: f64,
+    max: f64,
+    /// The level of the last completed window
+    level: Option<InputLevel>,
+}
+
+impl LevelMeter {
+    pub(crate) const WINDOW_MS: u32 = 100;
+
+    pub(crate) fn new(sampling_rate: u32) -> Self {
+        Self {
+            window_size: ((sampling_rate as u64 * Self::WINDOW_MS as u64 / 1000) as u32).max(1),
+            count: 0,
+            sum: 0.0,
+            sum_of_squares: 0.0,
+            min: f64::MAX,
+            max: f64::MIN,
+            level: None,
+        }
+    }
+    pub(crate) fn push_sample<T: Sample>(&mut self, sample: T) {
+        let sample = sample.to_f64().unwrap_or(0.0);
+        self.count += 1;
+        self.sum += sample;
+        self.sum_of_squares += sample * sample;
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+        if self.count == self.window_size {
+            let count = self.count as f64;
+            let mean = self.sum / count;
+            self.level = Some(InputLevel {
+                peak: (self.max - mean).max(mean - self.min),
+                rms: sqrt(self.sum_of_squares / count - mean * mean),
+            });
+            self.count = 0;
+            self.sum = 0.0;
+            self.sum_of_squares = 0.0;
+            self.min = f64::MAX;
+            self.max = f64::MIN;
+        }
+    }
+    pub(crate) fn level(&self) -> Option<InputLevel> {
+        self.level
+    }
+}
@@ -1,12 +1,61 @@
-use core::fmt::Display;
+use core::fmt::{Display, Formatter};
 
 use num_traits::{FromPrimitive, ToPrimitive, Zero};
 
+use crate::clock_mapping::ClockMapping;
 use crate::ltc_decoder::bit_decoder::{BitDecoder, BitVal};
+use crate::ltc_decoder::compliance::ComplianceViolations;
+use crate::ltc_decoder::frame_delay::{DecodedFrame, FrameDelay};
+use crate::ltc_decoder::level_meter::LevelMeter;
 use crate::ltc_frame::LtcFrame;
-use crate::TimecodeFrame;
+use crate::ltc_frame::ltc_frame_data::LtcFrameData;
+use crate::user_bits::TimecodeKind;
+use crate::{FramesPerSecond, FramesPerSecondSet, TimecodeFrame, UserBits};
+pub use crate::SyncState;
 
+#[cfg(feature = "alloc")]
+mod batch;
 mod bit_decoder;
+mod block;
+mod bitstream_decoder;
+mod calibration;
+mod compliance;
+mod config;
+mod dma;
+mod downmix;
+mod frame_builder;
+mod frame_delay;
+mod frame_ext;
+mod level_meter;
+mod monitor;
+mod partial;
+mod pcm;
+mod phase;
+mod sink;
+mod state;
+#[cfg(test)]
+pub(crate) mod test_signal;
+
+#[cfg(feature = "alloc")]
+pub use batch::decode_samples;
+pub use bitstream_decoder::LtcBitstreamDecoder;
+pub use block::BlockFrames;
+pub use calibration::{CalibrationReport, PeriodStats, SignalLevels};
+pub use compliance::ComplianceViolation;
+pub use config::{LtcDecoderConfig, TimecodeOffset};
+pub use dma::{DmaDecoder, QueuedFrame};
+pub use downmix::Downmix;
+pub use frame_builder::FrameBuilder;
+pub use frame_ext::TimecodeFrameExt;
+pub use level_meter::InputLevel;
+pub use monitor::{DecoderMonitor, NoMonitor};
+pub use pcm::{Endianness, PcmEncoding, PcmFormat, PcmFormatError};
+pub use partial::PartialTimecode;
+pub use phase::PhaseMeter;
+pub use sink::{CallbackSink, DecoderStats, LockAcquisition, TimecodeSink};
+pub use state::DecoderState;
+#[cfg(feature = "std")]
+pub use sink::{ChannelSink, TimecodeEvent};
 
 //pub trait Sample: Copy + Zero + std::ops::Div<f64>+ FromPrimitive + Ord + Sync + Send + 'static {}
 //pub trait Sample: Zero + Ord + Clone + Copy + 'static {}
@@ -15,44 +64,669 @@ pub trait Sample: Zero + Ord + Clone + Copy + FromPrimitive + ToPrimitive + Disp
 
 impl<T> Sample for T where T: Zero + Ord + Clone + Copy + FromPrimitive + ToPrimitive + Display + 'static {}
 
-pub struct LtcDecoder<T: Sample> {
+/// Sample requires Ord, so float samples need to be converted before they are pushed into the
+/// decoder. Converts to an integer sample with 24 bits resolution. Values beyond full scale keep
+/// their headroom up to the limits of i32
+pub fn float_to_int_sample(sample: f32) -> i32 {
+    (sample * 8_388_608.0) as i32
+}
+
+/// Tells if the level of the received signal allows decoding
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SignalLevel {
+    /// Not enough samples received yet to measure the level
+    Unknown,
+    /// The peak-to-peak value of the signal is too low to be decoded
+    TooLow,
+    /// The peak-to-peak value of the signal is below the noise gate set in `LtcDecoderConfig`
+    BelowNoiseGate,
+    /// The signal is strong enough to be decoded
+    Valid,
+}
+
+/// Tells what went wrong while decoding
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DecodeErrorKind {
+    /// The time between two threshold-crosses matches neither a half-bit nor a bit
+    SteadyViolation,
+    /// The time between two threshold-crosses is longer than a bit
+    Overdue,
+    /// A bit was received after a single half-bit
+    MissingHalfBit,
+    /// The signal got too weak to tell high from low
+    ThresholdInvalid,
+    /// No sync-word followed 80 bits after the last one
+    SyncWordMismatch,
+    /// A frame was dropped because of its polarity correction bit
+    ParityMismatch,
+    /// The duration of the frames fits no frame-rate at the sampling rate passed to the decoder, but
+    /// at another common sampling rate. See `LtcDecoder::sampling_rate_mismatch`
+    SamplingRateMismatch,
+    /// The frames were decoded at another frame-rate than `LtcDecoderConfig::expected_frames_per_second`.
+    /// See `LtcDecoder::frame_rate_mismatch`
+    FrameRateMismatch,
+    /// The drop-frame flag is set, but the frames run at 30 fps instead of 29.97 fps. See
+    /// `LtcDecoder::non_standard_drop_frame`
+    NonStandardDropFrame,
+}
+
+impl Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeErrorKind::SteadyViolation => write!(f, "Threshold-cross fits neither a half-bit nor a bit"),
+            DecodeErrorKind::Overdue => write!(f, "No threshold-cross within a bit"),
+            DecodeErrorKind::MissingHalfBit => write!(f, "Bit received after a single half-bit"),
+            DecodeErrorKind::ThresholdInvalid => write!(f, "Signal too weak to tell high from low"),
+            DecodeErrorKind::SyncWordMismatch => write!(f, "No sync-word 80 bits after the last one"),
+            DecodeErrorKind::ParityMismatch => write!(f, "Frame dropped because of its polarity correction bit"),
+            DecodeErrorKind::SamplingRateMismatch => write!(f, "Frames fit another sampling rate than the passed one"),
+            DecodeErrorKind::FrameRateMismatch => write!(f, "Frames decoded at another frame-rate than expected"),
+            DecodeErrorKind::NonStandardDropFrame => write!(f, "Drop-frame flag set at 30 fps"),
+        }
+    }
+}
+
+impl core::error::Error for DecodeErrorKind {}
+
+/// Cloning copies the whole state, so a clone continues decoding exactly like the original, e.g.
+/// to checkpoint the decoder before speculative processing and roll back to the clone. Requires the
+/// monitor to be Clone
+#[derive(Clone)]
+pub struct LtcDecoder<T: Sample, M: DecoderMonitor<T> = NoMonitor> {
     ltc_frame: LtcFrame,
     bit_decoder: BitDecoder<T>,
-    sampling_rate: f32,
+    /// In Hz. Fractional sampling rates are rounded, which is below 12 ppm above 44 kHz
+    sampling_rate: u32,
+    /// Drops frames with invalid polarity correction bit
+    require_parity: bool,
+    /// Maps the decoded frames to the number of received samples
+    clock: ClockMapping,
+    samples_received: u64,
+    max_extrapolation_frames: u8,
+    output_offset: TimecodeOffset,
+    strict_validation: bool,
+    /// Violations of the last returned frame
+    violations: ComplianceViolations,
+    /// Holds frames back in fixed latency mode
+    frame_delay: Option<FrameDelay>,
+    /// Number of frames that followed the previous one without gaps
+    consecutive_frames: u32,
+    /// The sync state that was returned by poll_sync_state_change last
+    reported_sync_state: SyncState,
+    /// Tells if the decoder was locked after the last sample pushed by push_samples
+    sink_locked: bool,
+    /// The sync state that was passed to the monitor last
+    monitored_sync_state: SyncState,
+    frames_decoded: u64,
+    frames_dropped: u64,
+    invalid_bits: u64,
+    /// User bits of the last returned frame
+    user_bits: Option<UserBits>,
+    /// Samples from the start of the last decoded frame until it was returned
+    frame_latency: Option<usize>,
+    bits_decoded: u64,
+    level_meter: LevelMeter,
+    /// The sampling rate the frames were received with, if it differs from the passed one
+    sampling_rate_mismatch: Option<u32>,
+    expected_frames_per_second: Option<FramesPerSecond>,
+    forced_frames_per_second: Option<FramesPerSecond>,
+    allowed_frames_per_second: Option<FramesPerSecondSet>,
+    /// The frame-rate the frames were decoded at, if it differs from the expected one
+    frame_rate_mismatch: Option<FramesPerSecond>,
+    /// Tells if drop-frame timecode runs at 30 fps
+    non_standard_drop_frame: bool,
+    /// The configured decimation while locked
+    locked_decimation: u8,
+    /// The current decimation, 1 decodes all samples
+    decimation: u8,
+    /// Samples since the last decoded sample while decimating
+    decimation_phase: u8,
+    /// Samples received and bits decoded when the signal appeared, while waiting for the first frame
+    acquisition_start: Option<(u64, u64)>,
+    /// A frame was decoded since the decoder was unlocked last
+    acquired: bool,
+    lock_acquisition: Option<LockAcquisition>,
+    /// Number of bits since the last sync-word. None if no sync-word was received since the decoder
+    /// got in sync
+    bits_since_sync_word: Option<u8>,
+    /// The error of the last pushed sample
+    last_error: Option<DecodeErrorKind>,
+    /// The quarter of the frame that started with the last pushed sample, 0 if it completed a
+    /// sync-word
+    quarter_frame: Option<u8>,
+    /// The frame-rate of a restored state that was locked. The first frame at this rate locks the
+    /// decoder
+    resume_frames_per_second: Option<FramesPerSecond>,
+    monitor: M,
 }
 
 impl<T: Sample> LtcDecoder<T> {
     pub fn new<S: ToPrimitive>(sampling_rate: S) -> Self {
+        Self::new_with_config(sampling_rate, LtcDecoderConfig::default())
+    }
+    /// Constructor with options to tune the decoder to the received signal
+    pub fn new_with_config<S: ToPrimitive>(sampling_rate: S, config: LtcDecoderConfig) -> Self {
+        Self::new_with_monitor(sampling_rate, config, NoMonitor)
+    }
+}
+
+impl<T: Sample, M: DecoderMonitor<T>> LtcDecoder<T, M> {
+    /// Constructor with a monitor that observes the internals of the decoder
+    pub fn new_with_monitor<S: ToPrimitive>(sampling_rate: S, config: LtcDecoderConfig, monitor: M) -> Self {
+        let sampling_rate = sampling_rate.to_f64().filter(|sampling_rate| *sampling_rate >= 1.0).expect("Invalid sampling rate");
+        let sampling_rate = (sampling_rate + 0.5) as u32;
         Self {
             ltc_frame: LtcFrame::new_empty(),
-            bit_decoder: BitDecoder::new(),
-            sampling_rate: sampling_rate.to_f32().expect("Invalid sampling rate"),
+            bit_decoder: BitDecoder::new(&config),
+            sampling_rate,
+            require_parity: config.require_parity,
+            clock: ClockMapping::new(sampling_rate as u64),
+            samples_received: 0,
+            max_extrapolation_frames: config.max_extrapolation_frames,
+            output_offset: config.output_offset,
+            strict_validation: config.strict_validation,
+            violations: ComplianceViolations::default(),
+            frame_delay: config.fixed_latency_samples.map(|latency| FrameDelay::new((latency as u64).min(FrameDelay::max_latency(sampling_rate)))),
+            consecutive_frames: 0,
+            reported_sync_state: SyncState::Unlocked,
+            sink_locked: false,
+            monitored_sync_state: SyncState::Unlocked,
+            frames_decoded: 0,
+            frames_dropped: 0,
+            invalid_bits: 0,
+            user_bits: None,
+            frame_latency: None,
+            bits_decoded: 0,
+            level_meter: LevelMeter::new(sampling_rate),
+            sampling_rate_mismatch: None,
+            expected_frames_per_second: config.expected_frames_per_second,
+            forced_frames_per_second: config.forced_frames_per_second,
+            allowed_frames_per_second: config.allowed_frames_per_second,
+            frame_rate_mismatch: None,
+            non_standard_drop_frame: false,
+            locked_decimation: config.locked_decimation.max(1),
+            decimation: 1,
+            decimation_phase: 0,
+            acquisition_start: None,
+            acquired: false,
+            lock_acquisition: None,
+            bits_since_sync_word: None,
+            last_error: None,
+            quarter_frame: None,
+            resume_frames_per_second: None,
+            monitor,
         }
     }
+    pub fn monitor(&self) -> &M {
+        &self.monitor
+    }
+    pub fn monitor_mut(&mut self) -> &mut M {
+        &mut self.monitor
+    }
 }
 
-impl<T: Sample> LtcDecoder<T> {
+impl<T: Sample, M: DecoderMonitor<T>> LtcDecoder<T, M> {
+    /// The decoder is locked as long as the next frame follows within this number of frame durations
+    const LOCK_TIMEOUT_FRAMES: f64 = 1.5;
+    /// Number of consecutive frames needed to lock
+    const LOCK_FRAMES: u32 = 2;
+    /// Decimation never decodes less samples per half-bit, so the timing stays within the tolerance
+    const MIN_DECIMATED_SAMPLES_PER_HALF_BIT: usize = 6;
+    const QUARTER_FRAME_BITS: u8 = LtcFrame::BIT_COUNT / 4;
+    /// Sampling rates of audio devices, checked if the frames fit no frame-rate
+    const COMMON_SAMPLING_RATES: [u32; 7] = [32_000, 44_100, 48_000, 88_200, 96_000, 176_400, 192_000];
+
     /// Push received audio-sample-point one after another in this function. From time to time
-    /// a Timecode-Frame will be returned to tell the current received timecode
+    /// a Timecode-Frame will be returned to tell the current received timecode. Never allocates
+    /// or panics, whatever the samples are, so it is safe to call from real-time threads
     pub fn get_timecode_frame(&mut self, sample: T) -> Option<TimecodeFrame> {
+        self.next_frame(sample).map(|decoded| decoded.frame)
+    }
+    /// Like get_timecode_frame, but returns the frame as the type of the application, e.g. `u32` for
+    /// BCD packed timecode
+    pub fn get_timecode_frame_as<B: FrameBuilder>(&mut self, sample: T) -> Option<B> {
+        let DecodedFrame { frame, data, .. } = self.next_frame(sample)?;
+        Some(match self.output_offset {
+            TimecodeOffset::None => data.build_frame(&frame.frames_per_second),
+            // The offset is only applied to the frame
+            _ => B::build(frame.hours, frame.minutes, frame.seconds, frame.frames, &frame.frames_per_second),
+        })
+    }
+    /// Like get_timecode_frame, but returns the flags, user bits, parity and timing of the frame as
+    /// well
+    pub fn get_timecode_frame_ext(&mut self, sample: T) -> Option<TimecodeFrameExt> {
+        let DecodedFrame { frame, data, user_bits, consecutive_frames, .. } = self.next_frame(sample)?;
+        let start_sample = self.samples_received.saturating_sub(1 + self.frame_latency? as u64);
+        let unassigned_flags_set = ComplianceViolations::unassigned_flags_set(&data, &frame.frames_per_second);
+        Some(TimecodeFrameExt {
+            frame,
+            drop_frame_flag: data.is_drop_frame(),
+            color_frame_flag: data.is_color_frame(),
+            user_bits,
+            parity_valid: data.parity_valid(),
+            unassigned_flags_set,
+            sync_state: self.sync_state(),
+            consecutive_frames,
+            start_sample,
+        })
+    }
+    /// Decodes a sample and returns the frame that is due with the data it was decoded from. In
+    /// fixed latency mode this is a frame that was decoded earlier
+    fn next_frame(&mut self, sample: T) -> Option<DecodedFrame> {
+        let frame = self.decode_sample(sample);
+        self.track_acquisition(frame.is_some());
+        if self.monitor.observes_sync_state() {
+            let sync_state = self.sync_state();
+            if sync_state != self.monitored_sync_state {
+                self.monitored_sync_state = sync_state;
+                self.monitor.on_sync_state(sync_state);
+            }
+        }
+        let frame = frame.map(|decoded| DecodedFrame { frame: self.offset_frame(decoded.frame), ..decoded });
+        let frame = if self.frame_delay.is_some() { self.delay_frame(frame) } else { frame }?;
+        self.user_bits = Some(frame.user_bits);
+        self.violations = frame.violations;
+        Some(frame)
+    }
+    fn decode_sample(&mut self, sample: T) -> Option<DecodedFrame> {
         self.ltc_frame.sample_received();
-        match self.bit_decoder.get_bit(sample) {
+        self.samples_received += 1;
+        self.last_error = None;
+        self.quarter_frame = None;
+        self.monitor.on_sample(sample);
+        self.level_meter.push_sample(sample);
+        if self.decimation > 1 {
+            self.decimation_phase = (self.decimation_phase + 1) % self.decimation;
+            if self.decimation_phase != 0 {
+                self.bit_decoder.skip_sample();
+                return None;
+            }
+        }
+        match self.bit_decoder.get_bit(sample, &mut self.monitor) {
             BitVal::None => { return None; }
-            BitVal::Invalid => {
+            BitVal::Invalid(kind) => {
+                self.invalid_bits += 1;
+                self.error(kind);
                 self.invalidate();
                 return None;
             }
-            BitVal::True => { self.ltc_frame.shift_bit(true); }
-            BitVal::False => { self.ltc_frame.shift_bit(false); }
+            BitVal::True => self.shift_bit(true),
+            BitVal::False => self.shift_bit(false),
         }
         if let Some((data, samples_for_frame)) = self.ltc_frame.get_data() {
-            Some(data.make_ltc_frame(self.sample_count_to_duration_s(samples_for_frame)))
+            if self.require_parity && !data.parity_valid() {
+                self.frames_dropped += 1;
+                self.error(DecodeErrorKind::ParityMismatch);
+                return None;
+            }
+            self.frames_decoded += 1;
+            let frame = match &self.forced_frames_per_second {
+                Some(forced) => TimecodeFrame { frames_per_second: forced.clone(), ..data.make_ltc_frame_with_fps(forced.clone()) },
+                None => self.detect_frame_rate(&data, samples_for_frame),
+            };
+            self.check_frame_rate(&frame.frames_per_second);
+            let violations = if self.strict_validation { ComplianceViolations::check(&data, &frame) } else { ComplianceViolations::default() };
+            self.frame_latency = Some(samples_for_frame);
+            self.count_consecutive_frames(&frame);
+            self.update_decimation();
+            self.clock.frame_received(&frame, self.samples_received);
+            self.check_drop_frame_timing(&frame.frames_per_second);
+            self.monitor.on_frame(&frame);
+            Some(DecodedFrame {
+                user_bits: data.get_user_bits(&frame.frames_per_second),
+                frame,
+                data,
+                violations,
+                consecutive_frames: self.consecutive_frames,
+            })
         } else {
             None
         }
     }
-    fn sample_count_to_duration_s(&self, sample_count: usize) -> f32 {
-        (sample_count as f32) / self.sampling_rate
+    /// Pushes a buffer of samples and passes the decoded frames, sync losses and the stats after the
+    /// buffer to the sink
+    pub fn push_samples<S: TimecodeSink>(&mut self, samples: &[T], sink: &mut S) {
+        self.push_samples_strided(samples, 1, 0, sink);
+    }
+    /// Like push_samples, but decodes every stride-th sample starting at offset, e.g. a channel of an
+    /// interleaved buffer with stride = number of channels and offset = channel. Nothing is copied
+    pub fn push_samples_strided<S: TimecodeSink>(&mut self, buffer: &[T], stride: usize, offset: usize, sink: &mut S) {
+        for (index, sample) in buffer.iter().skip(offset).step_by(stride.max(1)).enumerate() {
+            self.push_sample_to_sink(*sample, index, sink);
+        }
+        sink.on_stats(&self.stats());
+    }
+    /// Decodes a sample and passes everything but the stats to the sink
+    pub(crate) fn push_sample_to_sink<S: TimecodeSink>(&mut self, sample: T, offset: usize, sink: &mut S) {
+        let frame = self.get_timecode_frame(sample);
+        if let Some(quarter) = self.quarter_frame {
+            if quarter == 0 {
+                sink.on_frame_edge(offset);
+            }
+            sink.on_quarter_frame(quarter, offset);
+        }
+        if let Some(frame) = frame {
+            sink.on_frame(&frame);
+            for violation in self.violations.iter() {
+                sink.on_violation(&frame, violation);
+            }
+        }
+        if let Some(kind) = self.last_error {
+            sink.on_error(kind);
+        }
+        let locked = self.sync_state() == SyncState::Locked;
+        if self.sink_locked && !locked {
+            sink.on_sync_lost();
+        }
+        self.sink_locked = locked;
+    }
+    /// Decodes a block of an audio callback and returns the frames with their offset in the block.
+    /// Neither allocates nor blocks, so it can be called from real-time threads like the process
+    /// callback of a VST or CLAP plugin
+    pub fn process_block<const N: usize>(&mut self, samples: &[T]) -> BlockFrames<N> {
+        self.process_block_strided(samples, 1, 0)
+    }
+    /// Like process_block for a channel of an interleaved block, see push_samples_strided. The
+    /// offsets of the frames count the samples of the channel
+    pub fn process_block_strided<const N: usize>(&mut self, block: &[T], stride: usize, offset: usize) -> BlockFrames<N> {
+        let mut frames = BlockFrames::new();
+        for (index, sample) in block.iter().skip(offset).step_by(stride.max(1)).enumerate() {
+            if let Some(frame) = self.get_timecode_frame(*sample) {
+                frames.push(index, frame);
+            }
+        }
+        frames
+    }
+    /// The user bits of the last frame returned by get_timecode_frame
+    pub fn user_bits(&self) -> Option<UserBits> {
+        self.user_bits
+    }
+    /// Number of samples between the start of the last frame returned by get_timecode_frame and the
+    /// sample that returned it. A frame is returned after its 64 data bits, so this is about 80% of
+    /// a frame. Subtract it from the sample position to get the sample where the frame started
+    pub fn frame_latency_samples(&self) -> Option<usize> {
+        self.frame_latency
+    }
+    /// The sampling rate passed to the constructor in Hz
+    pub fn sampling_rate(&self) -> u32 {
+        self.sampling_rate
+    }
+    /// The sampling rate the samples actually have, if the frames fit no frame-rate at the sampling
+    /// rate passed to the decoder (e.g. a device that claims 44.1 kHz but delivers 48 kHz). The
+    /// frame-rate of the frames is then derived from this sampling rate instead of being unknown
+    pub fn sampling_rate_mismatch(&self) -> Option<u32> {
+        self.sampling_rate_mismatch
+    }
+    /// The frame-rate of the last decoded frame if it differs from
+    /// `LtcDecoderConfig::expected_frames_per_second`
+    pub fn frame_rate_mismatch(&self) -> Option<FramesPerSecond> {
+        self.frame_rate_mismatch.clone()
+    }
+    /// Tells if the last frames carry the drop-frame flag, but run at 30 fps instead of 29.97 fps.
+    /// This combination is not allowed by SMPTE ST 12-1, but emitted by misconfigured generators.
+    /// The frames are still counted as drop-frame. A sampling rate that is 0.1 % off (e.g.
+    /// 48.048 kHz passed as 48 kHz) looks the same
+    pub fn non_standard_drop_frame(&self) -> bool {
+        self.non_standard_drop_frame
+    }
+    /// The factor by which the samples are currently decimated, 1 if all samples are decoded. See
+    /// `LtcDecoderConfig::locked_decimation`
+    pub fn decimation(&self) -> u8 {
+        self.decimation
+    }
+    /// Peak and RMS of the input over the last 100 ms, e.g. for an input meter that warns about too
+    /// hot or too quiet LTC. None until 100 ms were pushed
+    pub fn input_level(&self) -> Option<InputLevel> {
+        self.level_meter.level()
+    }
+    /// The learned durations of half-bits and bits, their jitter and the levels of the signal
+    pub fn calibration(&self) -> CalibrationReport<T> {
+        self.bit_decoder.calibration()
+    }
+    /// The violations of SMPTE ST 12-1 of the last frame returned by get_timecode_frame. Always
+    /// empty unless `LtcDecoderConfig::strict_validation` is set
+    pub fn compliance_violations(&self) -> impl Iterator<Item = ComplianceViolation> {
+        self.violations.iter()
+    }
+    /// Tells if the last frame returned by get_timecode_frame carries time of day or elapsed time
+    pub fn timecode_kind(&self) -> Option<TimecodeKind> {
+        self.user_bits.map(|user_bits| user_bits.timecode_kind())
+    }
+    /// Counters since the decoder was created
+    pub fn stats(&self) -> DecoderStats {
+        DecoderStats {
+            frames_decoded: self.frames_decoded,
+            frames_dropped: self.frames_dropped,
+            invalid_bits: self.invalid_bits,
+            sync_state: self.sync_state(),
+            signal_level: self.signal_level(),
+            lock_acquisition: self.lock_acquisition,
+        }
+    }
+    /// The timecode at the last pushed sample and how far (0.0..1.0) it is into the frame. It is
+    /// extrapolated from the last decoded frames with the measured frame-rate, so it advances with
+    /// every sample. None if no frame was decoded within `max_extrapolation_frames`
+    pub fn extrapolated_now(&self) -> Option<(TimecodeFrame, f32)> {
+        let frame_duration = self.clock.last_frame_duration_ticks()?;
+        let stale_after = (frame_duration * self.max_extrapolation_frames as f64) as u64;
+        if self.samples_received - self.clock.last_frame_tick()? > stale_after {
+            return None;
+        }
+        match self.output_offset {
+            TimecodeOffset::Samples(samples) => self.clock.timecode_at(self.samples_received.checked_add_signed(samples)?),
+            _ => self.clock.timecode_at(self.samples_received).map(|(frame, sub_frame)| (self.offset_frame(frame), sub_frame)),
+        }
+    }
+    /// Long-term deviation of the received timecode from its nominal speed relative to the sampling
+    /// clock in ppm, e.g. to spot sync generators or sound cards that are off-spec. Positive if the
+    /// timecode runs faster. None if frames of less than a second were received since the last
+    /// jump of the timecode
+    pub fn drift_ppm(&self) -> Option<f64> {
+        self.clock.drift_ppm()
+    }
+    /// Number of bits received towards the next frame, from 0 right after a frame was returned up to
+    /// 79. Counted from the end of the data of the last frame, which is followed by its 16 bits
+    /// sync-word, so the count jumps to 16 when the first sync-word completes and is 0 before
+    pub fn bits_received(&self) -> u8 {
+        self.bits_since_sync_word.map_or(0, |bits| (bits + LtcFrame::SYNC_WORD_BIT_COUNT) % LtcFrame::BIT_COUNT)
+    }
+    /// How far the next frame is received from 0.0 to 1.0, e.g. to show that timecode is being
+    /// received until the first frame is decoded. None if the decoder is not in sync with the bits
+    pub fn progress(&self) -> Option<f32> {
+        (self.bit_decoder.is_in_sync() || self.bits_since_sync_word.is_some())
+            .then(|| self.bits_received() as f32 / LtcFrame::BIT_COUNT as f32)
+    }
+    /// Tells if the decoder is locked to the received timecode
+    pub fn sync_state(&self) -> SyncState {
+        let frames_since_last_frame = match (self.clock.last_frame_tick(), self.clock.last_frame_duration_ticks()) {
+            (Some(tick), Some(frame_duration)) => Some((self.samples_received - tick) as f64 / frame_duration),
+            _ => None,
+        };
+        let was_locked = self.consecutive_frames >= Self::LOCK_FRAMES;
+        match frames_since_last_frame {
+            Some(frames) if frames <= Self::LOCK_TIMEOUT_FRAMES && was_locked => SyncState::Locked,
+            Some(frames) if frames <= Self::LOCK_TIMEOUT_FRAMES => SyncState::Locking,
+            Some(frames) if frames <= self.max_extrapolation_frames as f64 && was_locked => SyncState::Freewheeling,
+            _ if self.bit_decoder.is_in_sync() => SyncState::Locking,
+            _ => SyncState::Unlocked,
+        }
+    }
+    /// Returns the new sync state if it changed since the last call. Call it after pushing samples
+    /// to get notified about changes
+    pub fn poll_sync_state_change(&mut self) -> Option<SyncState> {
+        let sync_state = self.sync_state();
+        if sync_state == self.reported_sync_state {
+            return None;
+        }
+        self.reported_sync_state = sync_state;
+        Some(sync_state)
+    }
+    /// Tells if the level of the received signal is high enough to be decoded. Useful to tell
+    /// the user why no timecode is received
+    pub fn signal_level(&self) -> SignalLevel {
+        self.bit_decoder.signal_level()
+    }
+    /// Applies the output offset to a decoded frame. Wraps at midnight
+    fn offset_frame(&self, frame: TimecodeFrame) -> TimecodeFrame {
+        let frames = match self.output_offset {
+            TimecodeOffset::None => return frame,
+            TimecodeOffset::Frames(frames) => frames as i64,
+            TimecodeOffset::Samples(samples) => match frame.frames_per_second.frame_rate() {
+                Some(frame_rate) => {
+                    // Rounded to the nearest number of frames
+                    let samples_per_frame_x2 = 2 * frame_rate.denominator() as i128 * self.sampling_rate as i128;
+                    let frames = (2 * samples as i128 * frame_rate.numerator() as i128 + samples_per_frame_x2 / 2).div_euclid(samples_per_frame_x2);
+                    frames.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+                }
+                None => return frame,
+            },
+        };
+        let n = frames.unsigned_abs().min(u32::MAX as u64) as u32;
+        if frames < 0 {
+            frame.wrapping_sub_frames(n)
+        } else {
+            frame.wrapping_add_frames(n)
+        }
+    }
+    /// Measures the time from the appearance of the signal until the first frame. A new measurement
+    /// starts when the decoder gets unlocked
+    fn track_acquisition(&mut self, frame_decoded: bool) {
+        if self.acquired {
+            self.acquired = frame_decoded || self.sync_state() != SyncState::Unlocked;
+            return;
+        }
+        if self.signal_level() != SignalLevel::Valid {
+            self.acquisition_start = None;
+            return;
+        }
+        let (start_sample, start_bits) = *self.acquisition_start.get_or_insert((self.samples_received, self.bits_decoded));
+        if frame_decoded {
+            self.lock_acquisition = Some(LockAcquisition {
+                samples: self.samples_received - start_sample,
+                bits: self.bits_decoded - start_bits,
+            });
+            self.acquisition_start = None;
+            self.acquired = true;
+        }
+    }
+    /// Queues a decoded frame in fixed latency mode and returns the frame that is due
+    fn delay_frame(&mut self, frame: Option<DecodedFrame>) -> Option<DecodedFrame> {
+        let now = self.samples_received - 1;
+        let frame_delay = self.frame_delay.as_mut()?;
+        if let (Some(frame), Some(latency)) = (frame, self.frame_latency) {
+            frame_delay.push(now.saturating_sub(latency as u64), frame);
+        }
+        let (start, frame) = frame_delay.pop(now)?;
+        self.frame_latency = Some((now - start) as usize);
+        Some(frame)
+    }
+    fn shift_bit(&mut self, bit: bool) {
+        self.bits_decoded += 1;
+        let bits = self.bits_since_sync_word.map(|bits| bits + 1);
+        if self.ltc_frame.shift_bit(bit) {
+            if bits.is_some_and(|bits| bits != LtcFrame::BIT_COUNT) {
+                self.error(DecodeErrorKind::SyncWordMismatch);
+            }
+            self.bits_since_sync_word = Some(0);
+            self.quarter_frame = Some(0);
+            self.monitor.on_sync_word();
+        } else if bits == Some(LtcFrame::BIT_COUNT) {
+            self.bits_since_sync_word = None;
+            self.error(DecodeErrorKind::SyncWordMismatch);
+        } else {
+            self.bits_since_sync_word = bits;
+            if let Some(bits) = bits.filter(|bits| bits % Self::QUARTER_FRAME_BITS == 0) {
+                self.quarter_frame = Some(bits / Self::QUARTER_FRAME_BITS);
+            }
+        }
+    }
+    /// Makes the frame with the frame-rate that fits the duration of its data
+    fn detect_frame_rate(&mut self, data: &LtcFrameData, samples_for_frame: usize) -> TimecodeFrame {
+        let frames_per_second = FramesPerSecond::from_frame_data_samples(samples_for_frame, self.sampling_rate, self.allowed_frames_per_second.as_ref());
+        let mut frame = data.make_ltc_frame_with_fps(frames_per_second);
+        if frame.frames_per_second == FramesPerSecond::Unknown {
+            if let Some((sampling_rate, frames_per_second)) = self.detect_sampling_rate(samples_for_frame) {
+                if self.sampling_rate_mismatch != Some(sampling_rate) {
+                    self.sampling_rate_mismatch = Some(sampling_rate);
+                    self.error(DecodeErrorKind::SamplingRateMismatch);
+                }
+                frame = data.make_ltc_frame_with_fps(frames_per_second);
+            }
+        } else {
+            self.sampling_rate_mismatch = None;
+        }
+        frame
+    }
+    /// Reports the first frame of every frame-rate that differs from the expected one
+    fn check_frame_rate(&mut self, frames_per_second: &FramesPerSecond) {
+        let Some(expected) = &self.expected_frames_per_second else {
+            return;
+        };
+        if frames_per_second == expected {
+            self.frame_rate_mismatch = None;
+        } else if self.frame_rate_mismatch.as_ref() != Some(frames_per_second) {
+            self.frame_rate_mismatch = Some(frames_per_second.clone());
+            self.error(DecodeErrorKind::FrameRateMismatch);
+        }
+    }
+    /// Reports drop-frame timecode that runs at 30 fps once. 29.97 fps and 30 fps can only be told
+    /// apart by the long-term drift, so this is checked after a second of frames
+    fn check_drop_frame_timing(&mut self, frames_per_second: &FramesPerSecond) {
+        if !frames_per_second.is_drop_frame() {
+            self.non_standard_drop_frame = false;
+            return;
+        }
+        let Some(drift_ppm) = self.clock.drift_ppm() else {
+            return;
+        };
+        // 30 fps run 1000 ppm faster than 29.97 fps
+        let runs_at_thirty = drift_ppm > 500.0;
+        if runs_at_thirty && !self.non_standard_drop_frame {
+            self.error(DecodeErrorKind::NonStandardDropFrame);
+        }
+        self.non_standard_drop_frame = runs_at_thirty;
+    }
+    /// Starts decimating once locked, with a factor that keeps enough samples per half-bit
+    fn update_decimation(&mut self) {
+        if self.consecutive_frames < Self::LOCK_FRAMES {
+            return;
+        }
+        let Some(half_bit_samples) = self.bit_decoder.half_bit_samples() else {
+            return;
+        };
+        let max_decimation = (half_bit_samples / Self::MIN_DECIMATED_SAMPLES_PER_HALF_BIT).clamp(1, u8::MAX as usize) as u8;
+        self.decimation = self.locked_decimation.min(max_decimation);
+    }
+    fn error(&mut self, kind: DecodeErrorKind) {
+        // Back to the full rate until locked again
+        self.decimation = 1;
+        self.decimation_phase = 0;
+        self.last_error = Some(kind);
+        self.monitor.on_error(kind);
+    }
+    fn count_consecutive_frames(&mut self, frame: &TimecodeFrame) {
+        let follows_last_frame = self.clock.last_frame().map(|last_frame| {
+            let mut expected = last_frame.clone();
+            expected.add_frame();
+            expected == *frame
+        });
+        self.consecutive_frames = if follows_last_frame == Some(true) { self.consecutive_frames.saturating_add(1) } else { 1 };
+        if self.resume_frames_per_second.take().as_ref() == Some(&frame.frames_per_second) {
+            self.consecutive_frames = self.consecutive_frames.max(Self::LOCK_FRAMES);
+        }
+    }
+    /// Finds the common sampling rate at which the data of a frame is closest to a known frame-rate
+    fn detect_sampling_rate(&self, samples_for_frame: usize) -> Option<(u32, FramesPerSecond)> {
+        Self::COMMON_SAMPLING_RATES.into_iter()
+            .filter(|sampling_rate| *sampling_rate != self.sampling_rate)
+            .filter_map(|sampling_rate| {
+                let (frames_per_second, deviation) = FramesPerSecond::match_frame_data_samples(samples_for_frame, sampling_rate, self.allowed_frames_per_second.as_ref())?;
+                Some((deviation, sampling_rate, frames_per_second))
+            })
+            .min_by_key(|(deviation, _, _)| *deviation)
+            .map(|(_, sampling_rate, frames_per_second)| (sampling_rate, frames_per_second))
     }
 
     /// In case some unexpected data is received, this function invalidates the decoder to restart
@@ -60,22 +734,32 @@ impl<T: Sample> LtcDecoder<T> {
     fn invalidate(&mut self) {
         self.ltc_frame.invalidate();
         self.bit_decoder.invalidate();
+        self.bits_since_sync_word = None;
     }
 }
 
 #[cfg(test)]
+// The test files are read with the wav crate, which deprecated all its items
+#[allow(deprecated)]
+// The trait bound tests only need to compile
+#[allow(clippy::assertions_on_constants)]
 mod tests {
+    use core::cell::Cell;
     use core::ops::Shl;
+    use std::alloc::{GlobalAlloc, Layout, System};
     use std::fs::File;
     use std::io;
     use std::io::Read;
 
     use num_traits::Zero;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
     use wav::BitDepth;
 
-    use crate::ltc_decoder::{LtcDecoder, Sample};
-    use crate::{TimecodeFrame};
-    use crate::FramesPerSecond::{Thirty, TwentyFive, TwentyFour};
+    use crate::ltc_decoder::{float_to_int_sample, ComplianceViolation, CallbackSink, DecodeErrorKind, DecoderMonitor, FrameBuilder, LtcDecoder, LtcDecoderConfig, Sample, SignalLevel, SyncState, TimecodeOffset, TimecodeSink};
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::{FramesPerSecond, TimecodeFrame, UserBits};
+    use crate::FramesPerSecond::{Fifty, Sixty, Thirty, ThirtyDropFrame, TwentyFive, TwentyFour, Unknown};
 
     #[test]
     fn test_sample_trait() {
@@ -158,6 +842,759 @@ mod tests {
         assert!(true);
     }
 
+    /// Decoders are created in one thread and moved to the audio thread
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<S: Send + Sync>() {}
+        assert_send_sync::<LtcDecoder<i32>>();
+        assert_send_sync::<LtcDecoder<u8>>();
+        assert_send_sync::<crate::ltc_decoder::LtcBitstreamDecoder>();
+        assert_send_sync::<crate::redundant_decoder::RedundantLtcDecoder<i16>>();
+        #[cfg(feature = "encode_ltc")]
+        assert_send_sync::<crate::ltc_encoder::LtcEncoder<i16>>();
+        assert_send_sync::<crate::shared_timecode::SharedTimecode>();
+    }
+
+    #[test]
+    fn test_extrapolated_now() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 50, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        assert!(decoder.extrapolated_now().is_none());
+        // 48000 samples are 25 frames, a frame has 1920 samples
+        for sample in &samples[..48_960] {
+            decoder.get_timecode_frame(*sample);
+        }
+        let (frame, sub_frame) = decoder.extrapolated_now().unwrap();
+        assert_eq!(frame, TimecodeFrame::new(10, 0, 1, 0, TwentyFive));
+        assert!((sub_frame - 0.5).abs() < 0.05);
+        for sample in &samples[48_960..50_400] {
+            decoder.get_timecode_frame(*sample);
+        }
+        let (frame, sub_frame) = decoder.extrapolated_now().unwrap();
+        assert_eq!(frame, TimecodeFrame::new(10, 0, 1, 1, TwentyFive));
+        assert!((sub_frame - 0.25).abs() < 0.05);
+        // Silence for more than four frames
+        for _ in 0..10_000 {
+            decoder.get_timecode_frame(0);
+        }
+        assert!(decoder.extrapolated_now().is_none());
+    }
+
+    #[test]
+    fn test_frame_latency() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        assert_eq!(decoder.frame_latency_samples(), None);
+        let mut frames = 0;
+        for (i, sample) in samples.iter().enumerate() {
+            if decoder.get_timecode_frame(*sample).is_some() {
+                frames += 1;
+                // The frames start every 1920 samples, 64 bits take 1536 samples
+                let latency = decoder.frame_latency_samples().unwrap();
+                assert!(latency.abs_diff(1536) <= 1, "{}", latency);
+                assert_eq!((i - latency) % 1920, 0);
+            }
+        }
+        assert_eq!(frames, 9);
+    }
+
+    #[test]
+    fn test_process_block() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let mut frames = Vec::new();
+        for (i, block) in samples.chunks(512).enumerate() {
+            let block_frames = decoder.process_block::<1>(block);
+            assert_eq!(block_frames.overflow(), 0);
+            frames.extend(block_frames.iter().map(|(offset, frame)| (i * 512 + offset, frame.clone())));
+        }
+        assert_eq!(frames.len(), 9);
+        assert_eq!(frames[0], (1920 + 1536, TimecodeFrame::new(10, 0, 0, 1, TwentyFive)));
+        // Blocks with more frames than fit
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let block_frames = decoder.process_block::<4>(&samples);
+        assert_eq!(block_frames.len(), 4);
+        assert_eq!(block_frames.overflow(), 5);
+    }
+
+    #[test]
+    fn test_fixed_latency() {
+        let mut shape = SignalShape::new(24.0);
+        shape.jitter = 1.0;
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 20, &shape);
+        let config = LtcDecoderConfig { fixed_latency_samples: Some(1700), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let mut frames = Vec::new();
+        for (i, sample) in samples.iter().enumerate() {
+            if let Some(frame) = decoder.get_timecode_frame(*sample) {
+                assert_eq!(decoder.frame_latency_samples(), Some(1700));
+                frames.push((i, frame));
+            }
+        }
+        assert_eq!(frames.len(), 19);
+        for (n, (i, frame)) in frames.into_iter().enumerate() {
+            assert_eq!(frame, TimecodeFrame::new(10, 0, 0, n as u8 + 1, TwentyFive));
+            // The jitter of the edges moves the start of the frames by up to two samples
+            assert!((i as i64 - (1920 * (n as i64 + 1) + 1700)).abs() <= 2, "{}", i);
+        }
+    }
+
+    #[test]
+    fn test_fixed_latency_sixty_fps() {
+        // 4000 samples are five frame durations at 60 fps, so five frames are queued at times
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, Sixty), 20, &SignalShape::new(10.0));
+        let config = LtcDecoderConfig { fixed_latency_samples: Some(4000), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames, (1..15).map(|frames| TimecodeFrame::new(10, 0, 0, frames, Sixty)).collect::<Vec<_>>());
+        // Longer latencies are clamped
+        let config = LtcDecoderConfig { fixed_latency_samples: Some(10_000), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let mut frames = Vec::new();
+        for sample in &samples {
+            if let Some(frame) = decoder.get_timecode_frame(*sample) {
+                assert_eq!(decoder.frame_latency_samples(), Some(5600));
+                frames.push(frame);
+            }
+        }
+        assert_eq!(frames, (1..13).map(|frames| TimecodeFrame::new(10, 0, 0, frames, Sixty)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[cfg(feature = "encode_ltc")]
+    fn test_fixed_latency_frame_ext() {
+        // Every frame carries its number in the user bits and the color frame flag on odd frames
+        let samples: Vec<i32> = (0..10_u8).flat_map(|n| {
+            let user_bits = UserBits { groups: n as u32, binary_group_flags: 0 };
+            let encoder = crate::ltc_encoder::LtcEncoder::new(TimecodeFrame::new(10, 0, 0, n, TwentyFive), 48_000, 1000, -1000).unwrap();
+            let mut encoder = encoder.with_user_bits(user_bits).with_color_frame(n % 2 == 1);
+            (0..1920).map(move |_| encoder.next_sample())
+        }).collect();
+        let config = LtcDecoderConfig { fixed_latency_samples: Some(4000), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let mut frames = Vec::new();
+        for sample in &samples {
+            if let Some(frame) = decoder.get_timecode_frame_ext(*sample) {
+                assert_eq!(decoder.user_bits(), Some(frame.user_bits));
+                frames.push(frame);
+            }
+        }
+        // The frames are held back past the end of the next frame
+        assert_eq!(frames.len(), 7);
+        for frame in frames {
+            let n = frame.frame.frames;
+            assert_eq!(frame.user_bits, UserBits { groups: n as u32, binary_group_flags: 0 });
+            assert_eq!(frame.color_frame_flag, n % 2 == 1);
+            assert_eq!(frame.consecutive_frames, n as u32);
+            assert_eq!(frame.start_sample, 1920 * n as u64);
+        }
+    }
+
+    #[test]
+    fn test_strided_input() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        // Channel 2 of 16 carries LTC
+        let mut interleaved = vec![0; samples.len() * 16];
+        for (i, sample) in samples.iter().enumerate() {
+            interleaved[i * 16 + 2] = *sample;
+        }
+        let mut expected = Vec::new();
+        LtcDecoder::<i32>::new(48_000).push_samples(&samples, &mut CallbackSink::new(|frame: &TimecodeFrame| expected.push(frame.clone())));
+        let mut frames = Vec::new();
+        LtcDecoder::<i32>::new(48_000).push_samples_strided(&interleaved, 16, 2, &mut CallbackSink::new(|frame: &TimecodeFrame| frames.push(frame.clone())));
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames, expected);
+        let block_frames = LtcDecoder::<i32>::new(48_000).process_block_strided::<4>(&interleaved, 16, 2);
+        assert_eq!(block_frames[0], (1920 + 1536, expected[0].clone()));
+    }
+
+    #[test]
+    fn test_frame_builder() {
+        /// Packs the timecode into a u32 as frame count of the day
+        #[derive(PartialEq, Debug)]
+        struct FrameCount(u32);
+        impl FrameBuilder for FrameCount {
+            fn build(hours: u8, minutes: u8, seconds: u8, frames: u8, _frames_per_second: &FramesPerSecond) -> Self {
+                Self(((hours as u32 * 60 + minutes as u32) * 60 + seconds as u32) * 25 + frames as u32)
+            }
+        }
+        let start = TimecodeFrame::new(10, 20, 30, 0, TwentyFive);
+        let samples = ltc_samples(&start, 3, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let frames: Vec<u32> = samples.iter().filter_map(|sample| decoder.get_timecode_frame_as(*sample)).collect();
+        assert_eq!(frames, [0x10_20_30_01, 0x10_20_30_02]);
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let frames: Vec<FrameCount> = samples.iter().filter_map(|sample| decoder.get_timecode_frame_as(*sample)).collect();
+        assert_eq!(frames[0], FrameCount(start.to_frame_count().unwrap() + 1));
+        let config = LtcDecoderConfig { output_offset: TimecodeOffset::Frames(-2), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let frames: Vec<u32> = samples.iter().filter_map(|sample| decoder.get_timecode_frame_as(*sample)).collect();
+        assert_eq!(frames, [0x10_20_29_24, 0x10_20_30_00]);
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let (first, second) = samples.split_at(5 * 1920 + 700);
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let frames: Vec<_> = first.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames.len(), 4);
+        let checkpoint = decoder.clone();
+        // Speculative processing of silence unlocks the decoder
+        for _ in 0..48_000 {
+            decoder.get_timecode_frame(0);
+        }
+        assert_eq!(decoder.sync_state(), SyncState::Unlocked);
+        // Rolled back, the decoder continues in the middle of the frame
+        let mut decoder = checkpoint;
+        assert_eq!(decoder.sync_state(), SyncState::Locked);
+        let frames: Vec<_> = second.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames.first(), Some(&TimecodeFrame::new(10, 0, 0, 5, TwentyFive)));
+        assert_eq!(frames.len(), 5);
+        assert_eq!(decoder.stats().frames_decoded, 9);
+    }
+
+    #[test]
+    fn test_progress() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        assert_eq!(decoder.progress(), None);
+        let mut progress = Vec::new();
+        for sample in &samples {
+            let bits_received = decoder.bits_received();
+            if decoder.get_timecode_frame(*sample).is_some() {
+                assert_eq!(decoder.bits_received(), 0);
+                progress.push(bits_received);
+            }
+        }
+        assert_eq!(progress, [79; 4]);
+        // The first sync-word completes at the end of the first frame
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        samples[..1920 + 12].iter().for_each(|sample| { decoder.get_timecode_frame(*sample); });
+        assert_eq!(decoder.bits_received(), 16);
+        assert_eq!(decoder.progress(), Some(0.2));
+    }
+
+    #[test]
+    fn test_frame_ext() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame_ext(*sample)).collect();
+        assert_eq!(frames.len(), 9);
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!(frame.frame, TimecodeFrame::new(10, 0, 0, i as u8 + 1, TwentyFive));
+            assert_eq!(frame.start_sample, 1920 * (i as u64 + 1));
+            assert!(frame.parity_valid);
+            assert!(!frame.drop_frame_flag);
+            assert!(!frame.color_frame_flag);
+            assert!(!frame.unassigned_flags_set);
+            assert_eq!(frame.user_bits, UserBits::default());
+            assert_eq!(frame.consecutive_frames, i as u32 + 1);
+        }
+        assert_eq!(frames[0].sync_state, SyncState::Locking);
+        assert_eq!(frames[1].sync_state, SyncState::Locked);
+    }
+
+    #[test]
+    fn test_lock_acquisition() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let signal = ltc_samples(&start, 5, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let mut acquisitions = Vec::new();
+        // Silence before the signal does not count. The silence between the signals unlocks the
+        // decoder, so the second signal is acquired again
+        for samples in [&[0; 10_000][..], &signal, &[0; 30_000], &signal[500..]] {
+            for sample in samples {
+                decoder.get_timecode_frame(*sample);
+            }
+            acquisitions.push(decoder.stats().lock_acquisition);
+        }
+        assert_eq!(decoder.sync_state(), SyncState::Locked);
+        assert!(acquisitions[0].is_none());
+        assert_eq!(acquisitions[1], acquisitions[2]);
+        assert_ne!(acquisitions[1], acquisitions[3]);
+        let acquisitions = [acquisitions[1].unwrap(), acquisitions[3].unwrap()];
+        for acquisition in acquisitions {
+            // The first frame is decoded after the first sync-word and the data of the second frame
+            assert!(acquisition.samples > 1920 && acquisition.samples < 2 * 1920, "{:?}", acquisition);
+            assert!(acquisition.bits > 80 && acquisition.bits <= 160, "{:?}", acquisition);
+        }
+    }
+
+    #[test]
+    fn test_calibration() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let report = decoder.calibration();
+        assert!(report.half_bit.is_none() && report.bit.is_none() && report.levels.is_none());
+        for sample in samples {
+            decoder.get_timecode_frame(sample);
+        }
+        let report = decoder.calibration();
+        // A bit has 24 samples
+        let (half_bit, bit) = (report.half_bit.unwrap(), report.bit.unwrap());
+        assert!(half_bit.learned.abs_diff(12) <= 1 && bit.learned.abs_diff(24) <= 1);
+        assert!((half_bit.mean - 12.0).abs() < 0.5 && (bit.mean - 24.0).abs() < 0.5);
+        assert!(half_bit.std_dev < 1.0 && bit.std_dev < 1.0);
+        assert!(half_bit.count > 100 && bit.count > 100);
+        let levels = report.levels.unwrap();
+        assert!(levels.min < levels.threshold && levels.threshold < levels.max);
+    }
+
+    #[test]
+    fn test_input_level() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let peak = samples.iter().map(|sample| sample.abs()).max().unwrap() as f64;
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        for sample in &samples[..4799] {
+            decoder.get_timecode_frame(*sample);
+        }
+        assert_eq!(decoder.input_level(), None);
+        for sample in &samples[4799..] {
+            decoder.get_timecode_frame(*sample);
+        }
+        let level = decoder.input_level().unwrap();
+        assert!((level.peak - peak).abs() < peak * 0.05, "{:?}", level);
+        assert!(level.rms > peak * 0.7 && level.rms <= level.peak, "{:?}", level);
+        // A DC-offset does not change the level
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        for sample in &samples {
+            decoder.get_timecode_frame(*sample + 1_000_000);
+        }
+        let offset_level = decoder.input_level().unwrap();
+        assert!((offset_level.peak - level.peak).abs() < 1.0 && (offset_level.rms - level.rms).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_sampling_rate_mismatch() {
+        #[derive(Default)]
+        struct ErrorMonitor(Vec<DecodeErrorKind>);
+        impl DecoderMonitor<i32> for ErrorMonitor {
+            fn on_error(&mut self, kind: DecodeErrorKind) {
+                self.0.push(kind);
+            }
+        }
+        // 48 kHz samples passed to a decoder that expects 44.1 kHz
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 5, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::new_with_monitor(44_100, LtcDecoderConfig::default(), ErrorMonitor::default());
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames[0], TimecodeFrame::new(10, 0, 0, 1, TwentyFive));
+        assert_eq!(decoder.sampling_rate_mismatch(), Some(48_000));
+        assert_eq!(decoder.monitor().0, [DecodeErrorKind::SamplingRateMismatch]);
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        samples.iter().for_each(|sample| { decoder.get_timecode_frame(*sample); });
+        assert_eq!(decoder.sampling_rate_mismatch(), None);
+    }
+
+    #[test]
+    fn test_unsigned_adc_samples() {
+        // Raw counts of a 12-bit ADC biased at Vref/2
+        let mut shape = SignalShape::new(24.0);
+        shape.high = 2348;
+        shape.low = 1748;
+        let samples: Vec<u16> = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &shape).iter().map(|sample| *sample as u16).collect();
+        let midpoint = LtcDecoderConfig { sample_midpoint: Some(2048), ..LtcDecoderConfig::default() };
+        for config in [LtcDecoderConfig::default(), midpoint] {
+            let mut decoder = LtcDecoder::<u16>::new_with_config(48_000, config);
+            let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+            assert_eq!(frames.len(), 9);
+            assert_eq!(frames[0], TimecodeFrame::new(10, 0, 0, 1, TwentyFive));
+        }
+    }
+
+    #[test]
+    fn test_frame_edge() {
+        #[derive(Default)]
+        struct EdgeSink {
+            edges: Vec<usize>,
+            quarters: Vec<(u8, usize)>,
+        }
+        impl TimecodeSink for EdgeSink {
+            fn on_frame(&mut self, _frame: &TimecodeFrame) {}
+            fn on_frame_edge(&mut self, offset: usize) {
+                self.edges.push(offset);
+            }
+            fn on_quarter_frame(&mut self, quarter: u8, offset: usize) {
+                self.quarters.push((quarter, offset));
+            }
+        }
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let mut edges = Vec::new();
+        let mut quarters = Vec::new();
+        for (i, buffer) in samples.chunks(512).enumerate() {
+            let mut sink = EdgeSink::default();
+            decoder.push_samples(buffer, &mut sink);
+            edges.extend(sink.edges.iter().map(|offset| i * 512 + offset));
+            quarters.extend(sink.quarters.iter().map(|(quarter, offset)| (*quarter, i * 512 + offset)));
+        }
+        // Exactly at the start of every following frame
+        assert_eq!(edges, (1..10).map(|frame| frame * 1920).collect::<Vec<_>>());
+        // Every 20 bits of 24 samples since the first edge
+        assert_eq!(quarters.len(), 4 * 9);
+        assert!(quarters.iter().enumerate().all(|(i, quarter)| *quarter == ((i % 4) as u8, 1920 + i * 480)));
+    }
+
+    #[test]
+    fn test_locked_decimation() {
+        // 96 kHz
+        let mut samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(48.0));
+        // Dropout and new signal
+        samples.extend([0; 3840]);
+        samples.extend(ltc_samples(&TimecodeFrame::new(11, 0, 0, 0, TwentyFive), 10, &SignalShape::new(48.0)));
+        let config = LtcDecoderConfig { locked_decimation: 8, ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(96_000, config);
+        let mut reference = LtcDecoder::<i32>::new(96_000);
+        let mut decimations = Vec::new();
+        for sample in &samples {
+            let frame = decoder.get_timecode_frame(*sample);
+            assert_eq!(frame, reference.get_timecode_frame(*sample));
+            if decimations.last() != Some(&decoder.decimation()) {
+                decimations.push(decoder.decimation());
+            }
+        }
+        // Limited to 6 of 24 samples per half-bit, back to the full rate during the dropout
+        assert_eq!(decimations, [1, 4, 1, 4]);
+        assert_eq!(reference.frames_decoded, 18);
+    }
+
+    #[test]
+    fn test_non_standard_drop_frame() {
+        #[derive(Default)]
+        struct ErrorMonitor(Vec<DecodeErrorKind>);
+        impl DecoderMonitor<i32> for ErrorMonitor {
+            fn on_error(&mut self, kind: DecodeErrorKind) {
+                self.0.push(kind);
+            }
+        }
+        // 29.97 fps are 1601.6 samples per frame at 48 kHz, 30 fps are 1600 samples
+        let standard = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, ThirtyDropFrame), 60, &SignalShape::new(20.015625));
+        let non_standard = ltc_samples(&TimecodeFrame::new(11, 0, 0, 0, ThirtyDropFrame), 60, &SignalShape::new(20.0));
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), ErrorMonitor::default());
+        for sample in &standard {
+            decoder.get_timecode_frame(*sample);
+        }
+        assert!(!decoder.non_standard_drop_frame());
+        let mut last_frame = None;
+        for sample in &non_standard {
+            last_frame = decoder.get_timecode_frame(*sample).or(last_frame);
+        }
+        assert!(decoder.non_standard_drop_frame());
+        assert_eq!(decoder.monitor().0, [DecodeErrorKind::NonStandardDropFrame]);
+        assert_eq!(last_frame, Some(TimecodeFrame::new(11, 0, 1, 29, ThirtyDropFrame)));
+    }
+
+    #[test]
+    fn test_frame_rate_mismatch() {
+        #[derive(Default)]
+        struct ErrorMonitor(Vec<DecodeErrorKind>);
+        impl DecoderMonitor<i32> for ErrorMonitor {
+            fn on_error(&mut self, kind: DecodeErrorKind) {
+                self.0.push(kind);
+            }
+        }
+        let mut samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        samples.extend(ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, Thirty), 5, &SignalShape::new(20.0)));
+        let config = LtcDecoderConfig { expected_frames_per_second: Some(Thirty), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, config, ErrorMonitor::default());
+        let mut frames = Vec::new();
+        for sample in &samples[..5 * 1920] {
+            frames.extend(decoder.get_timecode_frame(*sample));
+        }
+        // Frames at the wrong rate are returned, but reported once
+        assert_eq!(frames.len(), 4);
+        assert_eq!(decoder.frame_rate_mismatch(), Some(TwentyFive));
+        assert_eq!(decoder.monitor().0, [DecodeErrorKind::FrameRateMismatch]);
+        for sample in &samples[5 * 1920..] {
+            frames.extend(decoder.get_timecode_frame(*sample));
+        }
+        assert_eq!(frames.last(), Some(&TimecodeFrame::new(10, 0, 0, 4, Thirty)));
+        assert_eq!(decoder.frame_rate_mismatch(), None);
+    }
+
+    #[test]
+    fn test_forced_frame_rate() {
+        // 25 fps LTC that runs 3% fast is beyond the tolerance of the detection and taken for 24 fps
+        // at 44.1 kHz
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(23.3));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames[0], TimecodeFrame::new(10, 0, 0, 1, TwentyFour));
+        let config = LtcDecoderConfig { forced_frames_per_second: Some(TwentyFive), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames, (1..5).map(|frames| TimecodeFrame::new(10, 0, 0, frames, TwentyFive)).collect::<Vec<_>>());
+        // Frame-pairs are counted at the forced rate
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, Fifty), 5, &SignalShape::new(12.0));
+        let config = LtcDecoderConfig { forced_frames_per_second: Some(Fifty), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames.last(), Some(&TimecodeFrame::new(10, 0, 0, 4, Fifty)));
+    }
+
+    #[test]
+    fn test_allowed_frame_rates() {
+        // Taken for 24 fps at 44.1 kHz, see test_forced_frame_rate
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(23.3));
+        let config = LtcDecoderConfig { allowed_frames_per_second: Some([TwentyFive, Thirty].into()), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config.clone());
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames[0], TimecodeFrame::new(10, 0, 0, 1, Unknown));
+        assert_eq!(decoder.sampling_rate_mismatch(), None);
+        // Allowed rates are detected as usual
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames[0], TimecodeFrame::new(10, 0, 0, 1, TwentyFive));
+        // Drop-frame allows 30 fps with the drop-frame flag
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, ThirtyDropFrame), 5, &SignalShape::new(20.0));
+        let config = LtcDecoderConfig { allowed_frames_per_second: Some([ThirtyDropFrame].into()), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames[0], TimecodeFrame::new(10, 0, 0, 1, ThirtyDropFrame));
+    }
+
+    #[test]
+    fn test_fractional_sampling_rate() {
+        // NTSC-pulled audio at 44100 * 1000 / 1001 Hz, carrying 29.97 fps without drop-frame flag
+        let sampling_rate = 44_100.0 * 1000.0 / 1001.0;
+        let mut decoder = LtcDecoder::<i32>::new(sampling_rate);
+        assert_eq!(decoder.sampling_rate(), 44_056);
+        let start = TimecodeFrame::new(10, 0, 0, 0, Thirty);
+        let samples = ltc_samples(&start, 5, &SignalShape::new((sampling_rate / (80.0 * 30_000.0 / 1001.0)) as f32));
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+        assert_eq!(frames[0], TimecodeFrame::new(10, 0, 0, 1, Thirty));
+        assert_eq!(decoder.sampling_rate_mismatch(), None);
+    }
+
+    #[test]
+    fn test_drift() {
+        // The bit period drifts by a third within 30 frames
+        let mut start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let mut samples = Vec::new();
+        for samples_per_bit in [24.0, 25.5, 27.0, 28.5, 30.0, 31.5] {
+            samples.extend(ltc_samples(&start, 5, &SignalShape::new(samples_per_bit)));
+            start = start.checked_add_frames(5).unwrap();
+        }
+        let decode = |drift_time_constant| {
+            let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, LtcDecoderConfig { drift_time_constant, ..LtcDecoderConfig::default() });
+            samples.iter().for_each(|sample| { decoder.get_timecode_frame(*sample); });
+            decoder.stats().invalid_bits
+        };
+        assert_eq!(decode(LtcDecoderConfig::default().drift_time_constant), 0);
+        assert!(decode(0) > 0);
+    }
+
+    #[test]
+    fn test_drift_ppm() {
+        // The sound card runs 500 ppm faster than nominal, the timecode seems to run slower
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 50, &SignalShape::new(24.0 * 1.0005));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        for sample in samples {
+            decoder.get_timecode_frame(sample);
+        }
+        let drift_ppm = decoder.drift_ppm().unwrap();
+        assert!((drift_ppm + 500.0).abs() < 50.0, "{}", drift_ppm);
+    }
+
+    #[test]
+    fn test_output_offset() {
+        let start = TimecodeFrame::new(0, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let decode = |output_offset| {
+            let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, LtcDecoderConfig { output_offset, ..LtcDecoderConfig::default() });
+            let frames: Vec<_> = samples[..9_000].iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).collect();
+            (frames, decoder.extrapolated_now().unwrap())
+        };
+        let (frames, now) = decode(TimecodeOffset::None);
+        assert_eq!(frames[0], TimecodeFrame::new(0, 0, 0, 1, TwentyFive));
+        // Wraps at midnight
+        let (offset_frames, offset_now) = decode(TimecodeOffset::Frames(-2));
+        assert_eq!(offset_frames[0], TimecodeFrame::new(23, 59, 59, 24, TwentyFive));
+        assert_eq!(offset_now.0, now.0.wrapping_sub_frames(2));
+        assert_eq!(offset_now.1, now.1);
+        // 480 samples are a quarter of a frame. Decoded frames are offset by whole frames
+        let (offset_frames, offset_now) = decode(TimecodeOffset::Samples(2 * 1920 + 480));
+        assert_eq!(offset_frames[0], TimecodeFrame::new(0, 0, 0, 3, TwentyFive));
+        let position = |(frame, sub_frame): (TimecodeFrame, f32)| frame.to_frame_count().unwrap() as f32 + sub_frame;
+        assert!((position(offset_now) - position(now) - 2.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_sync_state() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        assert_eq!(decoder.sync_state(), SyncState::Unlocked);
+        assert_eq!(decoder.poll_sync_state_change(), None);
+        let mut changes = Vec::new();
+        for sample in samples {
+            decoder.get_timecode_frame(sample);
+            changes.extend(decoder.poll_sync_state_change());
+        }
+        assert_eq!(changes, [SyncState::Locking, SyncState::Locked]);
+        for _ in 0..30_000 {
+            decoder.get_timecode_frame(0);
+            changes.extend(decoder.poll_sync_state_change());
+        }
+        assert_eq!(changes, [SyncState::Locking, SyncState::Locked, SyncState::Freewheeling, SyncState::Unlocked]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_push_samples_to_sink() {
+        use crate::ltc_decoder::{CallbackSink, ChannelSink, DecoderStats, TimecodeEvent};
+
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut sink = ChannelSink::new(sender, true);
+        decoder.push_samples(&samples, &mut sink);
+        let decoder_stats_lock_acquisition = decoder.stats().lock_acquisition;
+        assert!(decoder_stats_lock_acquisition.is_some());
+        decoder.push_samples(&[0; 30_000], &mut sink);
+        let events: Vec<TimecodeEvent> = receiver.try_iter().collect();
+        let frames = events.iter().filter(|event| matches!(event, TimecodeEvent::Frame(_))).count();
+        assert_eq!(events[0], TimecodeEvent::Frame(start.checked_add_frames(1).unwrap()));
+        assert_eq!(events[frames], TimecodeEvent::Stats(DecoderStats {
+            frames_decoded: frames as u64,
+            frames_dropped: 0,
+            invalid_bits: 0,
+            sync_state: SyncState::Locked,
+            signal_level: SignalLevel::Valid,
+            lock_acquisition: decoder_stats_lock_acquisition,
+        }));
+        // The silence is detected before the sync gets lost
+        assert_eq!(events[frames + 1], TimecodeEvent::Error(DecodeErrorKind::ThresholdInvalid));
+        assert_eq!(events[frames + 2], TimecodeEvent::SyncLost);
+        assert!(matches!(events[frames + 3], TimecodeEvent::Stats(DecoderStats { sync_state: SyncState::Unlocked, .. })));
+
+        let mut count = 0;
+        LtcDecoder::<i32>::new(48_000).push_samples(&samples, &mut CallbackSink::new(|_| count += 1));
+        assert_eq!(count, frames);
+    }
+
+    #[derive(Default)]
+    struct CountingMonitor {
+        samples: usize,
+        thresholds: Vec<i32>,
+        threshold_crosses: usize,
+        bits: usize,
+        errors: usize,
+        sync_words: usize,
+        frames: usize,
+    }
+
+    impl DecoderMonitor<i32> for CountingMonitor {
+        fn on_sample(&mut self, _sample: i32) {
+            self.samples += 1;
+        }
+        fn on_threshold(&mut self, threshold: i32) {
+            self.thresholds.push(threshold);
+        }
+        fn on_threshold_cross(&mut self, _samples: usize) {
+            self.threshold_crosses += 1;
+        }
+        fn on_bit(&mut self, _bit: bool) {
+            self.bits += 1;
+        }
+        fn on_error(&mut self, _kind: DecodeErrorKind) {
+            self.errors += 1;
+        }
+        fn on_sync_word(&mut self) {
+            self.sync_words += 1;
+        }
+        fn on_frame(&mut self, _frame: &TimecodeFrame) {
+            self.frames += 1;
+        }
+    }
+
+    #[test]
+    fn test_monitor() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), CountingMonitor::default());
+        let frames = samples.iter().filter_map(|sample| decoder.get_timecode_frame(*sample)).count();
+        let monitor = decoder.monitor();
+        assert_eq!(monitor.samples, samples.len());
+        assert_eq!(monitor.thresholds.len(), samples.len() / 255);
+        assert!(monitor.thresholds.iter().all(|threshold| threshold.abs() < 1000));
+        // Every bit has one or two threshold-crosses
+        assert!(monitor.bits >= frames * 80 && monitor.bits <= 10 * 80);
+        assert!(monitor.threshold_crosses > monitor.bits);
+        assert_eq!(monitor.errors, 0);
+        assert_eq!(monitor.frames, frames);
+        // Every decoded frame follows a sync-word. The last sync-word of the signal is not completed,
+        // as its last bit ends without threshold-cross
+        assert_eq!(monitor.sync_words, frames);
+    }
+
+    #[test]
+    fn test_monitor_sync_state() {
+        #[derive(Default)]
+        struct SyncStateMonitor(Vec<SyncState>);
+        impl DecoderMonitor<i32> for SyncStateMonitor {
+            fn on_sync_state(&mut self, sync_state: SyncState) {
+                self.0.push(sync_state);
+            }
+            fn observes_sync_state(&self) -> bool {
+                true
+            }
+        }
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), SyncStateMonitor::default());
+        for sample in samples.iter().chain(&[0; 48_000]) {
+            decoder.get_timecode_frame(*sample);
+        }
+        assert_eq!(decoder.monitor().0, [SyncState::Locking, SyncState::Locked, SyncState::Freewheeling, SyncState::Unlocked]);
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        #[derive(Default)]
+        struct ErrorMonitor(Vec<DecodeErrorKind>);
+        impl DecoderMonitor<i32> for ErrorMonitor {
+            fn on_error(&mut self, kind: DecodeErrorKind) {
+                self.0.push(kind);
+            }
+        }
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let mut samples = ltc_samples(&start, 6, &SignalShape::new(24.0));
+        // Bits 4 and 5 of the fourth frame are zeros. Without them the sync-word comes too early.
+        // Silence follows the signal
+        samples.drain(3 * 1920 + 4 * 24..3 * 1920 + 6 * 24);
+        samples.extend([0; 2000]);
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, LtcDecoderConfig::default(), ErrorMonitor::default());
+        for sample in samples {
+            decoder.get_timecode_frame(sample);
+        }
+        assert_eq!(decoder.monitor().0, [DecodeErrorKind::SyncWordMismatch, DecodeErrorKind::ThresholdInvalid]);
+
+        let shape = SignalShape { valid_parity: false, ..SignalShape::new(24.0) };
+        let config = LtcDecoderConfig { require_parity: true, ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::new_with_monitor(48_000, config, ErrorMonitor::default());
+        for sample in ltc_samples(&start, 3, &shape) {
+            decoder.get_timecode_frame(sample);
+        }
+        assert_eq!(decoder.monitor().0, [DecodeErrorKind::ParityMismatch, DecodeErrorKind::ParityMismatch]);
+    }
+
+    #[test]
+    fn test_float_to_int_sample() {
+        assert_eq!(float_to_int_sample(0.5), 4_194_304);
+        assert_eq!(float_to_int_sample(-1.0), -8_388_608);
+        assert_eq!(float_to_int_sample(1000.0), i32::MAX);
+    }
+
     #[test]
     fn test_ltc_00100000_2mins_25fps_44100x8() {
         test_timecode_file("testfiles/LTC_00100000_2mins_25fps_44100x8.wav",
@@ -236,6 +1673,260 @@ mod tests {
                            TimecodeFrame::new(12, 22, 1, 0, Thirty))
     }
 
+    #[test]
+    fn test_symmetric_signal() {
+        test_synthesized_signal(&SignalShape::new(24.0));
+    }
+
+    #[test]
+    fn test_asymmetric_signal() {
+        let mut shape = SignalShape::new(24.0);
+        // 20% / 80% duty-cycle on half-bits
+        shape.asymmetry = 24.0 * 0.3;
+        test_synthesized_signal(&shape);
+        shape.asymmetry = -24.0 * 0.3;
+        test_synthesized_signal(&shape);
+    }
+
+    #[test]
+    fn test_clipped_signal() {
+        let mut shape = SignalShape::new(24.0);
+        shape.high = i32::MAX;
+        shape.low = i32::MIN;
+        test_synthesized_signal(&shape);
+        shape.ringing = true;
+        test_synthesized_signal_with_config(&shape, LtcDecoderConfig::clipped());
+    }
+
+    #[test]
+    fn test_low_amplitude_signal() {
+        let mut shape = SignalShape::new(24.0);
+        shape.high = -1;
+        shape.low = -3;
+        test_synthesized_signal(&shape);
+    }
+
+    #[test]
+    fn test_signal_level_too_low() {
+        let mut shape = SignalShape::new(24.0);
+        shape.high = 0;
+        shape.low = -1;
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &shape);
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        assert_eq!(decoder.signal_level(), SignalLevel::Unknown);
+        for sample in samples {
+            assert_eq!(decoder.get_timecode_frame(sample), None);
+        }
+        assert_eq!(decoder.signal_level(), SignalLevel::TooLow);
+    }
+
+    #[test]
+    fn test_noise_gate() {
+        let mut rng = StdRng::seed_from_u64(3080);
+        let config = LtcDecoderConfig {
+            noise_gate_peak_to_peak: 1_000,
+            ..LtcDecoderConfig::default()
+        };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config.clone());
+        for _ in 0..48_000 {
+            assert_eq!(decoder.get_timecode_frame(rng.gen_range(-100..100)), None);
+        }
+        assert_eq!(decoder.signal_level(), SignalLevel::BelowNoiseGate);
+        test_synthesized_signal_with_config(&SignalShape::new(24.0), config);
+    }
+
+    #[test]
+    fn test_lossy_signal() {
+        let mut shape = SignalShape::new(24.0);
+        shape.jitter = 1.5;
+        shape.pre_echo = true;
+        test_synthesized_signal_with_config(&shape, LtcDecoderConfig::lossy());
+    }
+
+    /// Counts the allocations of each thread, to check that decoding doesn't allocate
+    struct CountingAllocator;
+
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+            System.alloc(layout)
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// Pushes valid LTC, noise, full scale values and silence with all options that run on the hot
+    /// path. Overflow checks are on in tests, so this fails on arithmetic overflows as well
+    #[test]
+    fn test_real_time_safe() {
+        let mut samples = ltc_samples(&TimecodeFrame::new(23, 59, 59, 0, TwentyFive), 60, &SignalShape::new(24.0));
+        let mut rng = StdRng::seed_from_u64(1);
+        samples.extend((0..48_000).map(|_| rng.gen_range(-20_000..20_000)));
+        samples.extend((0..48_000).map(|i| if i % 20 < 10 { i32::MAX } else { i32::MIN }));
+        samples.extend((0..48_000).map(|_| rng.gen::<i32>()));
+        samples.extend([0; 48_000]);
+        let configs = [
+            LtcDecoderConfig::default(),
+            LtcDecoderConfig { output_offset: TimecodeOffset::Frames(i32::MIN), strict_validation: true, ..LtcDecoderConfig::clipped() },
+            LtcDecoderConfig { output_offset: TimecodeOffset::Samples(i64::MAX), drift_time_constant: 0, ..LtcDecoderConfig::lossy() },
+        ];
+        for config in configs {
+            let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+            let allocations = ALLOCATIONS.with(|allocations| allocations.get());
+            let mut frames = 0;
+            for block in samples.chunks(256) {
+                frames += decoder.process_block::<1>(block).len();
+                decoder.extrapolated_now();
+                decoder.sync_state();
+            }
+            assert_eq!(ALLOCATIONS.with(|allocations| allocations.get()), allocations);
+            assert!(frames > 50);
+        }
+    }
+
+    #[test]
+    fn test_strict_and_permissive() {
+        let mut samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 50, &SignalShape::new(24.0));
+        // Dropout of 20 frames
+        samples.extend([0; 20 * 1920]);
+        let mut shape = SignalShape::new(24.0);
+        shape.valid_parity = false;
+        let invalid_parity = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &shape);
+        test_synthesized_signal_with_config(&SignalShape::new(24.0), LtcDecoderConfig::strict());
+        test_synthesized_signal_with_config(&SignalShape::new(24.0), LtcDecoderConfig::permissive());
+
+        let mut strict = LtcDecoder::<i32>::new_with_config(48_000, LtcDecoderConfig::strict());
+        let mut permissive = LtcDecoder::<i32>::new_with_config(48_000, LtcDecoderConfig::permissive());
+        for sample in &samples {
+            strict.get_timecode_frame(*sample);
+            permissive.get_timecode_frame(*sample);
+        }
+        // Only the permissive decoder freewheels over the dropout
+        assert_eq!(strict.sync_state(), SyncState::Unlocked);
+        assert_eq!(strict.extrapolated_now(), None);
+        assert_eq!(permissive.sync_state(), SyncState::Freewheeling);
+        assert!(permissive.extrapolated_now().is_some());
+        // Only the strict decoder drops frames with invalid parity
+        assert!(invalid_parity.iter().all(|sample| strict.get_timecode_frame(*sample).is_none()));
+        assert!(invalid_parity.iter().filter_map(|sample| permissive.get_timecode_frame(*sample)).count() > 0);
+    }
+
+    #[test]
+    fn test_require_parity() {
+        let mut shape = SignalShape::new(24.0);
+        shape.valid_parity = false;
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 50, &shape);
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, LtcDecoderConfig::lossy());
+        for sample in samples {
+            assert_eq!(decoder.get_timecode_frame(sample), None);
+        }
+        // At 50 fps only the polarity correction bit is flipped, not the frame-pair flag
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, Fifty), 6, &SignalShape { samples_per_bit: 12.0, ..shape });
+        let config = LtcDecoderConfig { forced_frames_per_second: Some(Fifty), ..LtcDecoderConfig::default() };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let frames: Vec<_> = samples.iter().filter_map(|sample| decoder.get_timecode_frame_ext(*sample)).collect();
+        assert_eq!(frames.iter().map(|frame| frame.frame.clone()).collect::<Vec<_>>(), (1..6).map(|frames| TimecodeFrame::new(10, 0, 0, frames, Fifty)).collect::<Vec<_>>());
+        assert!(frames.iter().all(|frame| !frame.parity_valid));
+    }
+
+    #[test]
+    fn test_strict_validation() {
+        struct ViolationSink(Vec<(TimecodeFrame, ComplianceViolation)>);
+        impl TimecodeSink for ViolationSink {
+            fn on_frame(&mut self, _frame: &TimecodeFrame) {}
+            fn on_violation(&mut self, frame: &TimecodeFrame, violation: ComplianceViolation) {
+                self.0.push((frame.clone(), violation));
+            }
+        }
+        let config = LtcDecoderConfig { strict_validation: true, ..LtcDecoderConfig::default() };
+        let compliant = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config.clone());
+        let mut sink = ViolationSink(Vec::new());
+        decoder.push_samples(&compliant, &mut sink);
+        assert!(sink.0.is_empty());
+
+        let mut shape = SignalShape::new(24.0);
+        shape.valid_parity = false;
+        let samples = ltc_samples(&TimecodeFrame::new(25, 0, 0, 0, TwentyFive), 10, &shape);
+        decoder.push_samples(&samples, &mut sink);
+        // The samples continue the compliant signal, so their first frame is decoded as well. The
+        // hours wrap after it
+        assert_eq!(sink.0.len(), 11);
+        assert_eq!(sink.0[0], (TimecodeFrame::new(25, 0, 0, 0, TwentyFive), ComplianceViolation::FieldOutOfRange));
+        assert_eq!(sink.0[1], (TimecodeFrame::new(25, 0, 0, 0, TwentyFive), ComplianceViolation::ParityMismatch));
+        assert!(sink.0[2..].iter().all(|(_, violation)| *violation == ComplianceViolation::ParityMismatch));
+        assert_eq!(decoder.compliance_violations().collect::<Vec<_>>(), [ComplianceViolation::ParityMismatch]);
+
+        let mut shape = SignalShape::new(25.0);
+        shape.color_frame = true;
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFour), 5, &shape);
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config.clone());
+        let mut sink = ViolationSink(Vec::new());
+        decoder.push_samples(&samples, &mut sink);
+        assert_eq!(sink.0.len(), 4);
+        assert!(sink.0.iter().all(|(_, violation)| *violation == ComplianceViolation::ColorFrameFlagNotAllowed));
+
+        // With fixed latency the violations stay with the frame that is held back
+        let shape = SignalShape::new(24.0);
+        let samples: Vec<_> = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &shape).into_iter()
+            .chain(ltc_samples(&TimecodeFrame::new(25, 0, 0, 5, TwentyFive), 1, &shape))
+            .chain(ltc_samples(&TimecodeFrame::new(10, 0, 0, 6, TwentyFive), 5, &shape))
+            .collect();
+        let config = LtcDecoderConfig { fixed_latency_samples: Some(4000), ..config };
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config.clone());
+        let mut sink = ViolationSink(Vec::new());
+        decoder.push_samples(&samples, &mut sink);
+        assert_eq!(sink.0, [(TimecodeFrame::new(25, 0, 0, 5, TwentyFive), ComplianceViolation::FieldOutOfRange)]);
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let mut frames = 0;
+        for sample in &samples {
+            if let Some(frame) = decoder.get_timecode_frame(*sample) {
+                let violations: Vec<_> = decoder.compliance_violations().collect();
+                assert_eq!(violations.is_empty(), frame.hours == 10, "{}", frame);
+                frames += 1;
+            }
+        }
+        assert_eq!(frames, 8);
+
+        // Not checked by default
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let mut sink = ViolationSink(Vec::new());
+        decoder.push_samples(&samples, &mut sink);
+        assert!(sink.0.is_empty());
+    }
+
+    /// Decodes 25fps LTC at 48kHz synthesized with the given shape and checks that the decoder
+    /// locks within the first second and decodes every frame after that
+    fn test_synthesized_signal(shape: &SignalShape) {
+        test_synthesized_signal_with_config(shape, LtcDecoderConfig::default())
+    }
+
+    fn test_synthesized_signal_with_config(shape: &SignalShape, config: LtcDecoderConfig) {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 100, shape);
+        let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, config);
+        let mut decoded = Vec::new();
+        for sample in samples {
+            if let Some(tc) = decoder.get_timecode_frame(sample) {
+                decoded.push(tc);
+            }
+        }
+        assert!(decoded.len() > 75, "Only {} frames decoded", decoded.len());
+        let mut expected = decoded[0].clone();
+        assert!(expected.seconds == 0);
+        for tc in decoded {
+            assert_eq!(tc, expected);
+            expected.add_frame();
+        }
+    }
 
     /// runs a test on decoding timecode sample by sample with specifing the first expected decoded
     /// Frame (usually 1 frame above the start of the audio, because the lib needs some tim to sync)
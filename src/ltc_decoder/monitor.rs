@@ -0,0 +1,70 @@
+use crate::ltc_decoder::{DecodeErrorKind, Sample};
+use crate::{SyncState, TimecodeFrame};
+
+/// Observes the internals of the decoder, e.g. to visualize what the decoder saw on a problematic
+/// recording. All callbacks default to no-op. Every callback belongs to the sample last passed to
+/// on_sample, so a monitor can count the samples to know where things happened
+pub trait DecoderMonitor<T: Sample> {
+    /// Called for every pushed sample before it is decoded
+    fn on_sample(&mut self, _sample: T) {}
+    /// The threshold between high and low was recalculated from the peak values of the signal
+    fn on_threshold(&mut self, _threshold: T) {}
+    /// The signal crossed the threshold. `samples` is the length of the period since the last
+    /// threshold-cross, corrected by the duty-cycle compensation
+    fn on_threshold_cross(&mut self, _samples: usize) {}
+    /// A bit was decoded
+    fn on_bit(&mut self, _bit: bool) {}
+    /// Something unexpected was received. The decoder restarts synchronizing, except for
+    /// `DecodeErrorKind::ParityMismatch`, `DecodeErrorKind::SyncWordMismatch`,
+    /// `DecodeErrorKind::SamplingRateMismatch`, `DecodeErrorKind::FrameRateMismatch` and
+    /// `DecodeErrorKind::NonStandardDropFrame`
+    fn on_error(&mut self, _kind: DecodeErrorKind) {}
+    /// The last bit completed a sync-word, the data of the next frame starts
+    fn on_sync_word(&mut self) {}
+    /// A frame was decoded
+    fn on_frame(&mut self, _frame: &TimecodeFrame) {}
+    /// The sync state changed, e.g. the decoder lost the lock. Only called if observes_sync_state
+    /// returns true
+    fn on_sync_state(&mut self, _sync_state: SyncState) {}
+    /// The sync state is checked after every sample if this returns true. False by default to
+    /// keep the decoding cheap
+    fn observes_sync_state(&self) -> bool {
+        false
+    }
+}
+
+/// The default monitor of `LtcDecoder` that observes nothing
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoMonitor;
+
+impl<T: Sample> DecoderMonitor<T> for NoMonitor {}
+
+impl<T: Sample, M: DecoderMonitor<T> + ?Sized> DecoderMonitor<T> for &mut M {
+    fn on_sample(&mut self, sample: T) {
+        (**self).on_sample(sample);
+    }
+    fn on_threshold(&mut self, threshold: T) {
+        (**self).on_threshold(threshold);
+    }
+    fn on_threshold_cross(&mut self, samples: usize) {
+        (**self).on_threshold_cross(samples);
+    }
+    fn on_bit(&mut self, bit: bool) {
+        (**self).on_bit(bit);
+    }
+    fn on_error(&mut self, kind: DecodeErrorKind) {
+        (**self).on_error(kind);
+    }
+    fn on_sync_word(&mut self) {
+        (**self).on_sync_word();
+    }
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        (**self).on_frame(frame);
+    }
+    fn on_sync_state(&mut self, sync_state: SyncState) {
+        (**self).on_sync_state(sync_state);
+    }
+    fn observes_sync_state(&self) -> bool {
+        (**self).observes_sync_state()
+    }
+}
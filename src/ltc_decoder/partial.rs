@@ -0,0 +1,92 @@
+use crate::ltc_decoder::{DecoderMonitor, LtcDecoder, Sample};
+use crate::ltc_frame::LtcFrame;
+
+/// The fields of a frame that is still being received, returned by `LtcDecoder::partial_frame`.
+/// The bits of a frame arrive LSB-first with the frames first and the hours last, so a field is
+/// known before the frame is complete. Fields that are not completely received yet are None. The
+/// values are provisional, as the parity and the sync-word are only checked once the frame is
+/// complete
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub struct PartialTimecode {
+    pub hours: Option<u8>,
+    pub minutes: Option<u8>,
+    pub seconds: Option<u8>,
+    pub frames: Option<u8>,
+    pub drop_frame: Option<bool>,
+}
+
+impl PartialTimecode {
+    /// Number of bits after the sync-word needed for each field, as its last bit is 9 for frames,
+    /// 10 for the drop-frame flag, 26 for seconds, 42 for minutes and 57 for hours
+    const FRAMES_BITS: u8 = 10;
+    const DROP_FRAME_BITS: u8 = 11;
+    const SECONDS_BITS: u8 = 27;
+    const MINUTES_BITS: u8 = 43;
+    const HOURS_BITS: u8 = 58;
+
+    /// Tells if all fields are received
+    pub fn is_complete(&self) -> bool {
+        self.hours.is_some()
+    }
+}
+
+impl<T: Sample, M: DecoderMonitor<T>> LtcDecoder<T, M> {
+    /// The fields of the frame that is being received, for displays that show timecode with the
+    /// lowest latency and can tolerate provisional values. The frames are known about 1/8 of a frame
+    /// after the sync-word, the hours about 3/4. None if no sync-word was received since the decoder
+    /// got in sync, and while the sync-word of the next frame is received
+    pub fn partial_frame(&self) -> Option<PartialTimecode> {
+        let bits = self.bits_since_sync_word.filter(|bits| *bits <= LtcFrame::BIT_COUNT - LtcFrame::SYNC_WORD_BIT_COUNT)?;
+        let data = self.ltc_frame.get_partial_data(bits);
+        Some(PartialTimecode {
+            hours: (bits >= PartialTimecode::HOURS_BITS).then(|| data.get_hours()),
+            minutes: (bits >= PartialTimecode::MINUTES_BITS).then(|| data.get_minutes()),
+            seconds: (bits >= PartialTimecode::SECONDS_BITS).then(|| data.get_seconds()),
+            frames: (bits >= PartialTimecode::FRAMES_BITS).then(|| data.get_frames()),
+            drop_frame: (bits >= PartialTimecode::DROP_FRAME_BITS).then(|| data.is_drop_frame()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::{ThirtyDropFrame, TwentyFive};
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::{LtcDecoder, PartialTimecode};
+    use crate::TimecodeFrame;
+
+    /// The partial frame after the samples
+    fn partial_frame(samples: &[i32]) -> Option<PartialTimecode> {
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        samples.iter().for_each(|sample| { decoder.get_timecode_frame(*sample); });
+        decoder.partial_frame()
+    }
+
+    #[test]
+    fn test_partial_frame() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 20, 30, 5, TwentyFive), 3, &SignalShape::new(24.0));
+        // The first sync-word completes at the end of the first frame, 24 samples per bit
+        let frame_start = 1920 + 12;
+        assert_eq!(partial_frame(&samples[..1000]), None);
+        assert_eq!(partial_frame(&samples[..frame_start]), Some(PartialTimecode::default()));
+        let frames = partial_frame(&samples[..frame_start + 10 * 24]).unwrap();
+        assert_eq!(frames.frames, Some(6));
+        assert_eq!(frames.seconds, None);
+        let seconds = partial_frame(&samples[..frame_start + 27 * 24]).unwrap();
+        assert_eq!((seconds.seconds, seconds.minutes, seconds.drop_frame), (Some(30), None, Some(false)));
+        assert_eq!(partial_frame(&samples[..frame_start + 43 * 24]).unwrap().minutes, Some(20));
+        let hours = partial_frame(&samples[..frame_start + 58 * 24]).unwrap();
+        assert_eq!(hours.hours, Some(10));
+        assert!(hours.is_complete());
+        // The next sync-word
+        assert_eq!(partial_frame(&samples[..frame_start + 70 * 24]), None);
+    }
+
+    #[test]
+    fn test_drop_frame() {
+        let samples = ltc_samples(&TimecodeFrame::new(1, 0, 0, 2, ThirtyDropFrame), 3, &SignalShape::new(20.0));
+        let partial = partial_frame(&samples[..1600 + 10 + 11 * 20]).unwrap();
+        assert_eq!((partial.frames, partial.drop_frame), (Some(3), Some(true)));
+    }
+}
@@ -0,0 +1,218 @@
+use core::fmt::{Debug, Display, Formatter};
+
+use crate::ltc_decoder::{DecoderMonitor, LtcDecoder, TimecodeSink};
+
+/// How the samples of raw PCM bytes are coded
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PcmEncoding {
+    /// Two's complement
+    Signed,
+    /// Offset binary, silence is at half of the range
+    Unsigned,
+    /// G.711 A-law, as delivered by intercom and telephony bridges. Requires 8 bits
+    ALaw,
+    /// G.711 µ-law, as delivered by intercom and telephony bridges. Requires 8 bits
+    MuLaw,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// Describes raw PCM bytes passed to `LtcDecoder::push_pcm`. Channels are interleaved
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PcmFormat {
+    /// 8, 16, 24 or 32
+    pub bits_per_sample: u8,
+    pub encoding: PcmEncoding,
+    /// Ignored for 8 bits
+    pub endianness: Endianness,
+    pub channels: u16,
+    /// The channel that carries LTC, 0 for the first channel
+    pub channel: u16,
+}
+
+/// Tells why a `PcmFormat` can't be decoded
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum PcmFormatError {
+    UnsupportedBitDepth(u8),
+    ChannelOutOfRange { channel: u16, channels: u16 },
+}
+
+impl Display for PcmFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PcmFormatError::UnsupportedBitDepth(bits) => write!(f, "Unsupported bit depth {}", bits),
+            PcmFormatError::ChannelOutOfRange { channel, channels } => write!(f, "Channel {} selected but the format only has {} channels", channel, channels),
+        }
+    }
+}
+
+/// Error requires Debug, which prints the message, so it doesn't depend on the debug feature
+impl Debug for PcmFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl core::error::Error for PcmFormatError {}
+
+impl PcmFormat {
+    /// Tells if the format can be decoded
+    pub fn validate(&self) -> Result<(), PcmFormatError> {
+        let companded = matches!(self.encoding, PcmEncoding::ALaw | PcmEncoding::MuLaw);
+        if !matches!(self.bits_per_sample, 8 | 16 | 24 | 32) || (companded && self.bits_per_sample != 8) {
+            return Err(PcmFormatError::UnsupportedBitDepth(self.bits_per_sample));
+        }
+        if self.channel >= self.channels {
+            return Err(PcmFormatError::ChannelOutOfRange { channel: self.channel, channels: self.channels });
+        }
+        Ok(())
+    }
+    fn bytes_per_sample(&self) -> usize {
+        self.bits_per_sample as usize / 8
+    }
+    /// Converts the bytes of a sample to a signed value of the same bit depth. Companded samples
+    /// are expanded to 16 bits
+    fn sample(&self, bytes: &[u8]) -> i32 {
+        let mut value = 0_u32;
+        let mut push_byte = |byte: &u8| value = (value << 8) | *byte as u32;
+        match self.endianness {
+            Endianness::Little => bytes.iter().rev().for_each(&mut push_byte),
+            Endianness::Big => bytes.iter().for_each(&mut push_byte),
+        }
+        let unused_bits = 32 - self.bits_per_sample as u32;
+        match self.encoding {
+            // Sign extension by shifting the sign bit to the top and back
+            PcmEncoding::Signed => ((value << unused_bits) as i32) >> unused_bits,
+            PcmEncoding::Unsigned => (value as i64 - (1_i64 << (self.bits_per_sample - 1))) as i32,
+            PcmEncoding::ALaw => expand_a_law(value as u8),
+            PcmEncoding::MuLaw => expand_mu_law(value as u8),
+        }
+    }
+}
+
+/// Expands an A-law byte to 16 bits linear according to G.711
+fn expand_a_law(byte: u8) -> i32 {
+    // Even bits are inverted for transmission
+    let byte = byte ^ 0x55;
+    let exponent = (byte >> 4) & 0b111;
+    let mantissa = (byte & 0b1111) as i32;
+    let magnitude = match exponent {
+        0 => (mantissa << 4) + 8,
+        _ => ((mantissa << 4) + 0x108) << (exponent - 1),
+    };
+    // The sign bit is set for positive values
+    if byte & 0x80 != 0 { magnitude } else { -magnitude }
+}
+
+/// Expands a µ-law byte to 16 bits linear according to G.711
+fn expand_mu_law(byte: u8) -> i32 {
+    // All bits are inverted for transmission
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0b111;
+    let mantissa = (byte & 0b1111) as i32;
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if byte & 0x80 != 0 { -magnitude } else { magnitude }
+}
+
+impl<M: DecoderMonitor<i32>> LtcDecoder<i32, M> {
+    /// Decodes the selected channel of raw PCM bytes, e.g. from network audio or a capture API,
+    /// without converting the buffer first. Like push_samples, the stats are passed to the sink
+    /// after the buffer. Trailing bytes of an incomplete sample frame are ignored, so buffers have
+    /// to be split at sample frames
+    pub fn push_pcm<S: TimecodeSink>(&mut self, bytes: &[u8], format: &PcmFormat, sink: &mut S) -> Result<(), PcmFormatError> {
+        format.validate()?;
+        let bytes_per_sample = format.bytes_per_sample();
+        let start = format.channel as usize * bytes_per_sample;
+        for (index, sample_frame) in bytes.chunks_exact(format.channels as usize * bytes_per_sample).enumerate() {
+            let sample = format.sample(&sample_frame[start..start + bytes_per_sample]);
+            self.push_sample_to_sink(sample, index, sink);
+        }
+        sink.on_stats(&self.stats());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::{CallbackSink, Endianness, LtcDecoder, PcmEncoding, PcmFormat, PcmFormatError};
+    use crate::TimecodeFrame;
+
+    fn decode_pcm(bytes: &[u8], format: &PcmFormat) -> Result<Vec<TimecodeFrame>, PcmFormatError> {
+        let mut frames = Vec::new();
+        LtcDecoder::<i32>::new(48_000).push_pcm(bytes, format, &mut CallbackSink::new(|frame: &TimecodeFrame| frames.push(frame.clone())))?;
+        Ok(frames)
+    }
+
+    #[test]
+    fn test_pcm() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let expected: Vec<_> = (1..5).map(|frames| TimecodeFrame::new(10, 0, 0, frames, TwentyFive)).collect();
+        // 16 bit signed little endian, LTC on the second of two channels
+        let bytes: Vec<u8> = samples.iter().flat_map(|sample| [[0x55, 0x55], (*sample as i16).to_le_bytes()]).flatten().collect();
+        let format = PcmFormat { bits_per_sample: 16, encoding: PcmEncoding::Signed, endianness: Endianness::Little, channels: 2, channel: 1 };
+        assert_eq!(decode_pcm(&bytes, &format), Ok(expected.clone()));
+        // 24 bit unsigned big endian, mono
+        let bytes: Vec<u8> = samples.iter().flat_map(|sample| ((*sample + 0x80_0000) as u32).to_be_bytes()[1..].to_vec()).collect();
+        let format = PcmFormat { bits_per_sample: 24, encoding: PcmEncoding::Unsigned, endianness: Endianness::Big, channels: 1, channel: 0 };
+        assert_eq!(decode_pcm(&bytes, &format), Ok(expected.clone()));
+        // 8 bit unsigned
+        let bytes: Vec<u8> = samples.iter().map(|sample| (sample / 100 + 128) as u8).collect();
+        let format = PcmFormat { bits_per_sample: 8, encoding: PcmEncoding::Unsigned, endianness: Endianness::Little, channels: 1, channel: 0 };
+        assert_eq!(decode_pcm(&bytes, &format), Ok(expected));
+    }
+
+    #[test]
+    fn test_companded() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let expected: Vec<_> = (1..5).map(|frames| TimecodeFrame::new(10, 0, 0, frames, TwentyFive)).collect();
+        for encoding in [PcmEncoding::ALaw, PcmEncoding::MuLaw] {
+            let format = PcmFormat { bits_per_sample: 8, encoding, endianness: Endianness::Little, channels: 1, channel: 0 };
+            // Compands by choosing the byte that expands closest to the sample
+            let bytes: Vec<u8> = samples.iter().map(|sample| (0..=u8::MAX).min_by_key(|byte| (format.sample(&[*byte]) - sample).abs()).unwrap()).collect();
+            assert_eq!(decode_pcm(&bytes, &format), Ok(expected.clone()));
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let a_law = PcmFormat { bits_per_sample: 8, encoding: PcmEncoding::ALaw, endianness: Endianness::Little, channels: 1, channel: 0 };
+        assert_eq!(a_law.sample(&[0xD5]), 8);
+        assert_eq!(a_law.sample(&[0x55]), -8);
+        assert_eq!(a_law.sample(&[0xAA]), 32256);
+        assert_eq!(a_law.sample(&[0x2A]), -32256);
+        let mu_law = PcmFormat { encoding: PcmEncoding::MuLaw, ..a_law };
+        assert_eq!(mu_law.sample(&[0xFF]), 0);
+        assert_eq!(mu_law.sample(&[0x80]), 32124);
+        assert_eq!(mu_law.sample(&[0x00]), -32124);
+        assert_eq!(mu_law.sample(&[0xF0]), 120);
+    }
+
+    #[test]
+    fn test_pcm_sample() {
+        let format = PcmFormat { bits_per_sample: 24, encoding: PcmEncoding::Signed, endianness: Endianness::Little, channels: 1, channel: 0 };
+        assert_eq!(format.sample(&[0xFF, 0xFF, 0xFF]), -1);
+        assert_eq!(format.sample(&[0x00, 0x00, 0x80]), -0x80_0000);
+        let format = PcmFormat { bits_per_sample: 32, encoding: PcmEncoding::Unsigned, endianness: Endianness::Big, ..format };
+        assert_eq!(format.sample(&[0xFF, 0xFF, 0xFF, 0xFF]), i32::MAX);
+        assert_eq!(format.sample(&[0, 0, 0, 0]), i32::MIN);
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        let format = PcmFormat { bits_per_sample: 12, encoding: PcmEncoding::Signed, endianness: Endianness::Little, channels: 2, channel: 1 };
+        assert_eq!(decode_pcm(&[], &format), Err(PcmFormatError::UnsupportedBitDepth(12)));
+        let format = PcmFormat { bits_per_sample: 16, encoding: PcmEncoding::MuLaw, ..format };
+        assert_eq!(decode_pcm(&[], &format), Err(PcmFormatError::UnsupportedBitDepth(16)));
+        let format = PcmFormat { encoding: PcmEncoding::Signed, channel: 2, ..format };
+        assert_eq!(decode_pcm(&[], &format), Err(PcmFormatError::ChannelOutOfRange { channel: 2, channels: 2 }));
+    }
+}
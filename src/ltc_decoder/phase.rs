@@ -0,0 +1,116 @@
+/// Number of recent frame pulses a `PhaseMeter` compares the frame starts with
+const PULSE_HISTORY: usize = 8;
+
+/// Measures the phase between decoded LTC frames and video frame pulses, e.g. the timestamps of
+/// vsync interrupts of a genlocked source. Pulses and frame starts are sample indices counted the
+/// same way, like `TimecodeFrameExt::start_sample`. SMPTE ST 12-1 expects the frame to start at
+/// the pulse, so the phase should be close to 0
+#[derive(Clone, Debug, Default)]
+pub struct PhaseMeter {
+    /// The most recent pulses, not sorted
+    pulses: [Option<u64>; PULSE_HISTORY],
+    next_pulse: usize,
+    last_phase: Option<i64>,
+    min: i64,
+    max: i64,
+    sum: i64,
+    count: u32,
+}
+
+impl PhaseMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds the sample index of a frame pulse. Pulses may be pushed before or after the frames
+    /// they belong to, as long as they are among the last 8 pulses when the frame is pushed
+    pub fn push_pulse(&mut self, sample_index: u64) {
+        self.pulses[self.next_pulse] = Some(sample_index);
+        self.next_pulse = (self.next_pulse + 1) % PULSE_HISTORY;
+    }
+    /// Compares the sample index a decoded frame started at with the nearest pulse. Returns the
+    /// phase in samples, positive if the frame starts after the pulse. None if no pulse was pushed
+    pub fn push_frame_start(&mut self, start_sample: u64) -> Option<i64> {
+        let phase = self.pulses.iter().flatten()
+            .map(|pulse| start_sample as i64 - *pulse as i64)
+            .min_by_key(|phase| phase.unsigned_abs())?;
+        if self.count == 0 {
+            self.min = phase;
+            self.max = phase;
+        }
+        self.min = self.min.min(phase);
+        self.max = self.max.max(phase);
+        self.sum += phase;
+        self.count += 1;
+        self.last_phase = Some(phase);
+        Some(phase)
+    }
+    /// The phase of the last frame in samples
+    pub fn phase(&self) -> Option<i64> {
+        self.last_phase
+    }
+    /// The phase of the last frame in µs
+    pub fn phase_micros(&self, sampling_rate: f32) -> Option<f32> {
+        self.last_phase.map(|phase| phase as f32 * 1_000_000.0 / sampling_rate)
+    }
+    /// The smallest and largest phase of all frames in samples, which tells how much the phase
+    /// wanders
+    pub fn range(&self) -> Option<(i64, i64)> {
+        (self.count > 0).then_some((self.min, self.max))
+    }
+    /// The average phase of all frames in samples
+    pub fn mean(&self) -> Option<f32> {
+        (self.count > 0).then(|| self.sum as f32 / self.count as f32)
+    }
+    /// Number of frames measured
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+    /// Forgets all pulses and measurements, e.g. after the video source changed
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::{LtcDecoder, PhaseMeter};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_phase() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        let mut meter = PhaseMeter::new();
+        let mut phases = Vec::new();
+        for (index, sample) in samples.iter().enumerate() {
+            // Video frames start 24 samples (0.5 ms) before the LTC frames
+            if index % 1920 == 1920 - 24 {
+                meter.push_pulse(index as u64);
+            }
+            if let Some(frame) = decoder.get_timecode_frame_ext(*sample) {
+                phases.extend(meter.push_frame_start(frame.start_sample));
+            }
+        }
+        assert_eq!(phases, [24; 9]);
+        assert_eq!(meter.phase_micros(48_000.0), Some(500.0));
+        assert_eq!(meter.range(), Some((24, 24)));
+        assert_eq!(meter.mean(), Some(24.0));
+        assert_eq!(meter.count(), 9);
+    }
+
+    #[test]
+    fn test_nearest_pulse() {
+        let mut meter = PhaseMeter::new();
+        assert_eq!(meter.push_frame_start(1000), None);
+        for pulse in [0, 1920, 3840, 5760] {
+            meter.push_pulse(pulse);
+        }
+        assert_eq!(meter.push_frame_start(3830), Some(-10));
+        assert_eq!(meter.push_frame_start(5790), Some(30));
+        assert_eq!(meter.range(), Some((-10, 30)));
+        meter.reset();
+        assert_eq!(meter.phase(), None);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::ltc_decoder::{ComplianceViolation, DecodeErrorKind, SignalLevel};
+use crate::{SyncState, TimecodeFrame};
+
+/// Counters of the decoder, passed to `TimecodeSink::on_stats` after every buffer
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecoderStats {
+    /// Number of frames returned by the decoder
+    pub frames_decoded: u64,
+    /// Number of frames dropped because of an invalid polarity correction bit
+    pub frames_dropped: u64,
+    /// Number of times the decoder lost the heartbeat of the signal
+    pub invalid_bits: u64,
+    pub sync_state: SyncState,
+    pub signal_level: SignalLevel,
+    /// How long the last acquisition took. None until the first frame was decoded
+    pub lock_acquisition: Option<LockAcquisition>,
+}
+
+/// Time from the appearance of the signal (or the loss of sync) until the first frame was decoded
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LockAcquisition {
+    pub samples: u64,
+    pub bits: u64,
+}
+
+/// Receives the output of `LtcDecoder::push_samples`. All methods except on_frame default to no-op
+pub trait TimecodeSink {
+    /// A frame was decoded
+    fn on_frame(&mut self, frame: &TimecodeFrame);
+    /// The decoder left `SyncState::Locked`
+    fn on_sync_lost(&mut self) {}
+    /// Something unexpected was received
+    fn on_error(&mut self, _kind: DecodeErrorKind) {}
+    /// The frame passed to on_frame right before violates SMPTE ST 12-1. Only called if
+    /// `LtcDecoderConfig::strict_validation` is set
+    fn on_violation(&mut self, _frame: &TimecodeFrame, _violation: ComplianceViolation) {}
+    /// The sync-word of a frame ended, which is the boundary to the next frame, e.g. to raise a
+    /// pulse per frame. `offset` is the index of the sample in the buffer that completed it
+    fn on_frame_edge(&mut self, _offset: usize) {}
+    /// A quarter of a frame (20 bits) passed, e.g. to schedule MTC quarter-frame messages.
+    /// `quarter` is 0 at the frame edge (right after on_frame_edge), then 1 to 3. `offset` is the
+    /// index of the sample in the buffer that completed the bit before the boundary
+    fn on_quarter_frame(&mut self, _quarter: u8, _offset: usize) {}
+    /// Called once after each buffer
+    fn on_stats(&mut self, _stats: &DecoderStats) {}
+}
+
+impl<S: TimecodeSink + ?Sized> TimecodeSink for &mut S {
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        (**self).on_frame(frame);
+    }
+    fn on_sync_lost(&mut self) {
+        (**self).on_sync_lost();
+    }
+    fn on_error(&mut self, kind: DecodeErrorKind) {
+        (**self).on_error(kind);
+    }
+    fn on_violation(&mut self, frame: &TimecodeFrame, violation: ComplianceViolation) {
+        (**self).on_violation(frame, violation);
+    }
+    fn on_frame_edge(&mut self, offset: usize) {
+        (**self).on_frame_edge(offset);
+    }
+    fn on_quarter_frame(&mut self, quarter: u8, offset: usize) {
+        (**self).on_quarter_frame(quarter, offset);
+    }
+    fn on_stats(&mut self, stats: &DecoderStats) {
+        (**self).on_stats(stats);
+    }
+}
+
+/// Sink that calls a closure for every decoded frame
+pub struct CallbackSink<F: FnMut(&TimecodeFrame)> {
+    on_frame: F,
+}
+
+impl<F: FnMut(&TimecodeFrame)> CallbackSink<F> {
+    pub fn new(on_frame: F) -> Self {
+        Self { on_frame }
+    }
+}
+
+impl<F: FnMut(&TimecodeFrame)> TimecodeSink for CallbackSink<F> {
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        (self.on_frame)(frame);
+    }
+}
+
+/// Everything a `TimecodeSink` receives, to send it to another thread
+#[cfg(feature = "std")]
+#[derive(PartialEq, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+pub enum TimecodeEvent {
+    Frame(TimecodeFrame),
+    SyncLost,
+    Error(DecodeErrorKind),
+    Violation(TimecodeFrame, ComplianceViolation),
+    Stats(DecoderStats),
+}
+
+/// Sink that sends all events over a channel. Events are dropped once the receiver is gone
+#[cfg(feature = "std")]
+pub struct ChannelSink {
+    sender: std::sync::mpsc::Sender<TimecodeEvent>,
+    send_stats: bool,
+}
+
+#[cfg(feature = "std")]
+impl ChannelSink {
+    /// Sends frames, sync losses, errors and violations. Stats are only sent if send_stats is set
+    pub fn new(sender: std::sync::mpsc::Sender<TimecodeEvent>, send_stats: bool) -> Self {
+        Self { sender, send_stats }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TimecodeSink for ChannelSink {
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        let _ = self.sender.send(TimecodeEvent::Frame(frame.clone()));
+    }
+    fn on_sync_lost(&mut self) {
+        let _ = self.sender.send(TimecodeEvent::SyncLost);
+    }
+    fn on_error(&mut self, kind: DecodeErrorKind) {
+        let _ = self.sender.send(TimecodeEvent::Error(kind));
+    }
+    fn on_violation(&mut self, frame: &TimecodeFrame, violation: ComplianceViolation) {
+        let _ = self.sender.send(TimecodeEvent::Violation(frame.clone(), violation));
+    }
+    fn on_stats(&mut self, stats: &DecoderStats) {
+        if self.send_stats {
+            let _ = self.sender.send(TimecodeEvent::Stats(*stats));
+        }
+    }
+}
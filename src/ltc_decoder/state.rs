@@ -0,0 +1,103 @@
+use crate::ltc_decoder::{DecoderMonitor, LtcDecoder, Sample};
+use crate::FramesPerSecond;
+
+/// What a decoder learned about the signal, returned by `LtcDecoder::save_state`. Small enough to be
+/// persisted with every frame, so a process can resume decoding after a restart without learning
+/// the signal again
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecoderState {
+    /// The sampling rate the state was learned at
+    pub sampling_rate: u32,
+    /// The learned durations of a half-bit and a bit in samples. None if the decoder was not in sync
+    pub bit_timing: Option<(f32, f32)>,
+    /// The frame-rate of the last frame if the decoder was locked
+    pub locked_frames_per_second: Option<FramesPerSecond>,
+    pub frames_decoded: u64,
+    pub frames_dropped: u64,
+    pub invalid_bits: u64,
+}
+
+impl<T: Sample, M: DecoderMonitor<T>> LtcDecoder<T, M> {
+    /// Saves what the decoder learned about the signal and its counters
+    pub fn save_state(&self) -> DecoderState {
+        let locked = self.consecutive_frames >= Self::LOCK_FRAMES;
+        DecoderState {
+            sampling_rate: self.sampling_rate,
+            bit_timing: self.bit_decoder.bit_timing(),
+            locked_frames_per_second: self.clock.last_frame().filter(|_| locked).map(|frame| frame.frames_per_second.clone()),
+            frames_decoded: self.frames_decoded,
+            frames_dropped: self.frames_dropped,
+            invalid_bits: self.invalid_bits,
+        }
+    }
+    /// Continues with a saved state, e.g. after a restart. The first bits are decoded with the
+    /// learned bit timing, and if the decoder was locked, it is locked again with the first frame at
+    /// the same frame-rate instead of the second one. The counters continue as well. The bit
+    /// timing and lock are ignored if the state was saved at another sampling rate
+    pub fn restore_state(&mut self, state: &DecoderState) {
+        self.frames_decoded = state.frames_decoded;
+        self.frames_dropped = state.frames_dropped;
+        self.invalid_bits = state.invalid_bits;
+        if state.sampling_rate != self.sampling_rate {
+            return;
+        }
+        if let Some((half_bit, bit)) = state.bit_timing {
+            self.invalidate();
+            self.bit_decoder.restore_bit_timing(half_bit, bit);
+        }
+        self.resume_frames_per_second = state.locked_frames_per_second.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::ltc_decoder::LtcDecoder;
+    use crate::{SyncState, TimecodeFrame};
+
+    /// Index of the sample that completes the first frame and the sync state after it
+    fn first_frame(decoder: &mut LtcDecoder<i32>, samples: &[i32]) -> Option<(usize, SyncState)> {
+        samples.iter().position(|sample| decoder.get_timecode_frame(*sample).is_some()).map(|index| (index, decoder.sync_state()))
+    }
+
+    #[test]
+    fn test_restore_state() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        samples.iter().for_each(|sample| { decoder.get_timecode_frame(*sample); });
+        let state = decoder.save_state();
+        assert_eq!(state.locked_frames_per_second, Some(TwentyFive));
+        assert_eq!(state.frames_decoded, 9);
+        let (half_bit, bit) = state.bit_timing.unwrap();
+        assert_eq!((half_bit.round(), bit.round()), (12.0, 24.0));
+
+        // The process restarts in the middle of a frame
+        let resumed = ltc_samples(&TimecodeFrame::new(11, 0, 0, 0, TwentyFive), 3, &SignalShape::new(24.0));
+        let resumed = &resumed[700..];
+        let mut fresh = LtcDecoder::<i32>::new(48_000);
+        assert_eq!(first_frame(&mut fresh, resumed), Some((1920 + 1536 - 700, SyncState::Locking)));
+        let mut restored = LtcDecoder::<i32>::new(48_000);
+        restored.restore_state(&state);
+        assert_eq!(first_frame(&mut restored, resumed), Some((1920 + 1536 - 700, SyncState::Locked)));
+        assert_eq!(restored.stats().frames_decoded, 10);
+
+        // Another sampling rate only takes the counters
+        let mut decoder = LtcDecoder::<i32>::new(44_100);
+        decoder.restore_state(&state);
+        assert_eq!(decoder.save_state().bit_timing, None);
+        assert_eq!(decoder.stats().frames_decoded, 9);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &SignalShape::new(24.0));
+        let mut decoder = LtcDecoder::<i32>::new(48_000);
+        samples.iter().for_each(|sample| { decoder.get_timecode_frame(*sample); });
+        let state = decoder.save_state();
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<crate::ltc_decoder::DecoderState>(&json).unwrap(), state);
+    }
+}
@@ -0,0 +1,128 @@
+//! Helpers to synthesize LTC audio in tests without relying on recorded files
+
+#[cfg(any(feature = "wav", feature = "symphonia"))]
+use std::io::Cursor;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::ltc_frame::ltc_frame_data::LtcFrameData;
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// Bits of a frame in the order they are transmitted, followed by the sync-word
+pub(crate) fn frame_bits(tc: &TimecodeFrame) -> [bool; 80] {
+    LtcFrameData::from_timecode_frame(tc).to_bits()
+}
+
+/// Describes how the synthesized audio signal should look like
+pub(crate) struct SignalShape {
+    pub(crate) samples_per_bit: f32,
+    pub(crate) high: i32,
+    pub(crate) low: i32,
+    /// Number of samples the high-level lasts longer (and the low-level shorter) than nominal.
+    /// Simulates an asymmetric duty-cycle
+    pub(crate) asymmetry: f32,
+    /// Lets the signal swing back for one sample right after each edge, like ringing of a hard
+    /// clipped signal
+    pub(crate) ringing: bool,
+    /// Lets the signal swing to the next level for one sample two samples before each edge, like
+    /// pre-echo of lossy codecs
+    pub(crate) pre_echo: bool,
+    /// Maximal number of samples each edge is moved randomly
+    pub(crate) jitter: f32,
+    /// Sets the polarity correction bit wrong if false
+    pub(crate) valid_parity: bool,
+    /// Sets the color frame flag of every frame
+    pub(crate) color_frame: bool,
+}
+
+impl SignalShape {
+    pub(crate) fn new(samples_per_bit: f32) -> Self {
+        Self {
+            samples_per_bit,
+            high: 10_000,
+            low: -10_000,
+            asymmetry: 0.0,
+            ringing: false,
+            pre_echo: false,
+            jitter: 0.0,
+            valid_parity: true,
+            color_frame: false,
+        }
+    }
+}
+
+/// Biphase-mark encodes `frame_count` frames starting at `start` and returns the audio samples
+pub(crate) fn ltc_samples(start: &TimecodeFrame, frame_count: usize, shape: &SignalShape) -> Vec<i32> {
+    let mut edges = Vec::new();
+    let mut time = 0.0;
+    let mut tc = start.clone();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut jitter = || if shape.jitter > 0.0 { rng.gen_range(-shape.jitter..shape.jitter) } else { 0.0 };
+    for _ in 0..frame_count {
+        let mut data = LtcFrameData::from_timecode_frame(&tc);
+        if shape.color_frame {
+            data.set_color_frame(true);
+            data.set_polarity_correction(&tc.frames_per_second);
+        }
+        let mut bits = data.to_bits();
+        if !shape.valid_parity {
+            let polarity_correction_index = if matches!(tc.frames_per_second, FramesPerSecond::TwentyFive | FramesPerSecond::Fifty) { 59 } else { 27 };
+            bits[polarity_correction_index] = !bits[polarity_correction_index];
+        }
+        for bit in bits {
+            edges.push(time + jitter());
+            if bit {
+                edges.push(time + shape.samples_per_bit / 2.0 + jitter());
+            }
+            time += shape.samples_per_bit;
+        }
+        tc.add_frame();
+    }
+    let mut samples = Vec::with_capacity(time as usize);
+    let mut edge_index = 0;
+    let mut high = false;
+    let mut last_edge = 0;
+    for i in 0..time as usize {
+        while edge_index < edges.len() {
+            // Rising edges come earlier and falling edges later to stretch the high-level
+            let shift = if high { shape.asymmetry / 2.0 } else { -shape.asymmetry / 2.0 };
+            if edges[edge_index] + shift > i as f32 {
+                break;
+            }
+            high = !high;
+            edge_index += 1;
+            last_edge = i;
+        }
+        let ringing = shape.ringing && i == last_edge + 1;
+        let pre_echo = shape.pre_echo && edges.get(edge_index).map(|edge| (*edge as usize).checked_sub(2) == Some(i)).unwrap_or(false);
+        samples.push(if high != (ringing || pre_echo) { shape.high } else { shape.low });
+    }
+    samples
+}
+
+/// Writes samples as a mono 16 bit WAV file at 48 kHz to memory, rewound to its start
+#[cfg(any(feature = "wav", feature = "symphonia"))]
+pub(crate) fn wav_data(samples: impl IntoIterator<Item = i32>) -> Cursor<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 48_000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let spec = hound::WavSpecEx { spec, bytes_per_sample: 2 };
+    wav_data_with_spec(spec, samples.into_iter().map(|sample| sample as i16))
+}
+
+/// Like wav_data with any format. The samples of all channels are interleaved
+#[cfg(any(feature = "wav", feature = "symphonia"))]
+pub(crate) fn wav_data_with_spec<S: hound::Sample>(spec: hound::WavSpecEx, samples: impl IntoIterator<Item = S>) -> Cursor<Vec<u8>> {
+    let mut data = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new_with_spec_ex(&mut data, spec).unwrap();
+    for sample in samples {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+    data.set_position(0);
+    data
+}
@@ -0,0 +1,186 @@
+use crate::ltc_frame::ltc_frame_data::LtcFrameData;
+use crate::{TimecodeFrame, UserBits};
+
+/// Generates LTC audio sample by sample, starting at a given timecode
+pub struct LtcEncoder<T: Copy> {
+    sampling_rate: u64,
+    /// Frames per second of the timecode as fraction
+    frame_rate_numerator: u64,
+    frame_rate_denominator: u64,
+    /// The frame that is currently encoded
+    timecode: TimecodeFrame,
+    /// The bits of the current frame in the order they are sent
+    bits: [bool; 80],
+    /// Sent with every frame
+    user_bits: UserBits,
+    color_frame: bool,
+    /// The number of the next sample since the encoder started
+    sample_index: u64,
+    /// Number of the half-bit (since the encoder started) of the last sample
+    half_bit_index: u64,
+    /// The current level of the signal
+    is_high: bool,
+    high: T,
+    low: T,
+}
+
+impl<T: Copy> LtcEncoder<T> {
+    /// Constructor. `high` and `low` are the sample values of the signal. Returns None if the
+    /// frame-rate of `start` is unknown or the sampling rate is 0
+    pub fn new(start: TimecodeFrame, sampling_rate: u32, high: T, low: T) -> Option<Self> {
+        if sampling_rate == 0 {
+            return None;
+        }
+        let frame_rate = start.frames_per_second.frame_rate()?;
+        Some(Self {
+            sampling_rate: sampling_rate as u64,
+            frame_rate_numerator: frame_rate.numerator() as u64,
+            frame_rate_denominator: frame_rate.denominator() as u64,
+            bits: LtcFrameData::from_timecode_frame(&start).to_bits(),
+            user_bits: UserBits::default(),
+            color_frame: false,
+            timecode: start,
+            sample_index: 0,
+            // The first sample starts a bit, which toggles the signal
+            half_bit_index: u64::MAX,
+            is_high: true,
+            high,
+            low,
+        })
+    }
+
+    /// Sends the user bits and binary group flags with every frame. The flag that carries the
+    /// frame-pair flag in 50fps and 60fps timecode is ignored
+    pub fn with_user_bits(mut self, user_bits: UserBits) -> Self {
+        self.user_bits = user_bits;
+        self.bits = self.frame_bits();
+        self
+    }
+    /// Sets the color frame flag of every frame
+    pub fn with_color_frame(mut self, color_frame: bool) -> Self {
+        self.color_frame = color_frame;
+        self.bits = self.frame_bits();
+        self
+    }
+
+    /// The frame that the next sample belongs to
+    pub fn current_timecode(&self) -> &TimecodeFrame {
+        &self.timecode
+    }
+
+    /// Returns the next sample of the LTC signal
+    pub fn next_sample(&mut self) -> T {
+        // The product overflows u64 after a few weeks at high sampling rates
+        let half_bit_index = (self.sample_index as u128 * self.frame_rate_numerator as u128 * 160 / (self.sampling_rate as u128 * self.frame_rate_denominator as u128)) as u64;
+        self.sample_index += 1;
+        if half_bit_index != self.half_bit_index {
+            self.half_bit_index = half_bit_index;
+            let half_bit_in_frame = (half_bit_index % 160) as usize;
+            if half_bit_in_frame == 0 && half_bit_index != 0 {
+                self.timecode.add_frame();
+                self.bits = self.frame_bits();
+            }
+            // Biphase-mark: Every bit starts with a transition, ones have a second one in the middle
+            if half_bit_in_frame.is_multiple_of(2) || self.bits[half_bit_in_frame / 2] {
+                self.is_high = !self.is_high;
+            }
+        }
+        if self.is_high {
+            self.high
+        } else {
+            self.low
+        }
+    }
+
+    /// The bits of the current frame with user bits and flags
+    fn frame_bits(&self) -> [bool; 80] {
+        let frames_per_second = &self.timecode.frames_per_second;
+        let mut data = LtcFrameData::from_timecode_frame(&self.timecode);
+        data.set_user_bits(&self.user_bits, frames_per_second);
+        data.set_color_frame(self.color_frame);
+        data.set_polarity_correction(frames_per_second);
+        data.to_bits()
+    }
+
+    /// Fills a buffer with the next samples
+    pub fn fill(&mut self, buffer: &mut [T]) {
+        for sample in buffer {
+            *sample = self.next_sample();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "decode_ltc"))]
+mod tests {
+    use crate::FramesPerSecond::{Fifty, Sixty, Thirty, ThirtyDropFrame, TwentyFive, TwentyFour, Unknown};
+    use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig};
+    use crate::ltc_encoder::LtcEncoder;
+    use crate::{TimecodeFrame, UserBits};
+
+    fn test_roundtrip(start: TimecodeFrame, sampling_rate: u32) {
+        let mut encoder = LtcEncoder::new(start.clone(), sampling_rate, i16::MAX / 2, i16::MIN / 2).unwrap();
+        let config = LtcDecoderConfig {
+            require_parity: true,
+            ..LtcDecoderConfig::default()
+        };
+        let mut decoder = LtcDecoder::<i16>::new_with_config(sampling_rate, config);
+        let mut expected = start.clone();
+        expected.add_frame();
+        let mut count = 0;
+        for _ in 0..sampling_rate * 2 {
+            if let Some(frame) = decoder.get_timecode_frame(encoder.next_sample()) {
+                assert_eq!(frame, expected);
+                expected.add_frame();
+                count += 1;
+            }
+        }
+        assert!(count > 40);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        test_roundtrip(TimecodeFrame::new(10, 59, 59, 20, TwentyFive), 48_000);
+        test_roundtrip(TimecodeFrame::new(0, 0, 0, 0, Thirty), 44_100);
+        test_roundtrip(TimecodeFrame::new(23, 0, 0, 10, TwentyFour), 44_100);
+        test_roundtrip(TimecodeFrame::new(1, 0, 59, 10, ThirtyDropFrame), 48_000);
+        test_roundtrip(TimecodeFrame::new(2, 30, 59, 40, Fifty), 48_000);
+        test_roundtrip(TimecodeFrame::new(2, 30, 59, 50, Sixty), 48_000);
+        test_roundtrip(TimecodeFrame::new(2, 30, 59, 50, Sixty), 44_100);
+    }
+
+    #[test]
+    fn test_long_running() {
+        let mut encoder = LtcEncoder::new(TimecodeFrame::new(0, 0, 0, 0, ThirtyDropFrame), 192_000, 1, -1).unwrap();
+        // A year at 192 kHz
+        encoder.sample_index = 192_000 * 3600 * 24 * 365;
+        encoder.half_bit_index = (encoder.sample_index as u128 * 30_000 * 160 / (192_000 * 1001)) as u64 - 1;
+        let mut buffer = vec![0; 192_000];
+        encoder.fill(&mut buffer);
+        let mut decoder = LtcDecoder::<i32>::new(192_000);
+        let frames: Vec<_> = buffer.into_iter().filter_map(|sample| decoder.get_timecode_frame(sample)).collect();
+        assert!(frames.len() > 25);
+        assert!(frames.windows(2).all(|frames| frames[0].checked_add_frames(1).as_ref() == Some(&frames[1])));
+    }
+
+    #[test]
+    fn test_user_bits() {
+        for start in [TimecodeFrame::new(10, 0, 0, 0, TwentyFive), TimecodeFrame::new(10, 0, 0, 0, Sixty)] {
+            let user_bits = UserBits { groups: 0x1234_5678, binary_group_flags: UserBits::BGF0 | UserBits::BGF1 };
+            let mut encoder = LtcEncoder::new(start, 48_000, 1000, -1000).unwrap().with_user_bits(user_bits).with_color_frame(true);
+            let mut decoder = LtcDecoder::<i32>::new_with_config(48_000, LtcDecoderConfig { require_parity: true, ..LtcDecoderConfig::default() });
+            let frames: Vec<_> = (0..48_000 / 5).filter_map(|_| decoder.get_timecode_frame_ext(encoder.next_sample())).collect();
+            assert!(frames.len() > 2);
+            assert!(frames.iter().all(|frame| frame.user_bits == user_bits && frame.color_frame_flag));
+        }
+    }
+
+    #[test]
+    fn test_unknown_fps() {
+        assert!(LtcEncoder::new(TimecodeFrame::new(0, 0, 0, 0, Unknown), 48_000, 1, -1).is_none());
+    }
+
+    #[test]
+    fn test_invalid_sampling_rate() {
+        assert!(LtcEncoder::new(TimecodeFrame::new(0, 0, 0, 0, TwentyFive), 0, 1, -1).is_none());
+    }
+}
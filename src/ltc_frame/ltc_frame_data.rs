@@ -2,10 +2,15 @@ use core::fmt::{Debug, Display, Formatter};
 
 use intbits::Bits;
 
-use crate::TimecodeFrame;
+use crate::{FramesPerSecond, TimecodeFrame};
+use crate::UserBits;
+#[cfg(any(test, feature = "encode_ltc"))]
+use crate::ltc_frame::LtcFrame;
+#[cfg(feature = "decode_ltc")]
+use crate::ltc_decoder::FrameBuilder;
 
 ///Contains all the data of a LtcFrame without the SyncWord
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub(crate) struct LtcFrameData {
     data: u64,
 }
@@ -59,6 +64,20 @@ impl LtcFrameData {
             BitIndex::new(51, 8),
             BitIndex::new(56, 10),
             BitIndex::new(57, 20)];
+    /// Flag that tells that the timecode is drop-frame
+    const BIT_INDEX_DROP_FRAME: BitIndex = BitIndex::new(10, 1);
+    /// Flag that tells that the timecode is locked to the color frame sequence of the video
+    const BIT_INDEX_COLOR_FRAME: BitIndex = BitIndex::new(11, 1);
+    /// Flag that tells the odd frame of a frame-pair in 50fps timecode
+    const BIT_INDEX_FRAME_PAIR_FIFTY: BitIndex = BitIndex::new(27, 1);
+    /// Flag that tells the odd frame of a frame-pair in 60fps timecode
+    const BIT_INDEX_FRAME_PAIR_SIXTY: BitIndex = BitIndex::new(59, 1);
+    /// Binary group flags. They change places with the polarity correction bit in 25fps and 50fps
+    const BIT_INDEX_BGF0: BitIndex = BitIndex::new(43, 1);
+    const BIT_INDEX_BGF1: BitIndex = BitIndex::new(58, 1);
+    const BIT_INDEX_BGF2: BitIndex = BitIndex::new(59, 1);
+    const BIT_INDEX_BGF0_TWENTY_FIVE: BitIndex = BitIndex::new(27, 1);
+    const BIT_INDEX_BGF2_TWENTY_FIVE: BitIndex = BitIndex::new(43, 1);
     /// If syncword is completely received, the data will start now
     /// Syncword bits is divided by two to avoid having to work with 16bit values for all bits
     const BIT_INDEX_SYNCWORD_START_FIRST_HALF: [BitIndex; 8] =
@@ -116,6 +135,43 @@ impl LtcFrameData {
         Self::SYNC_WORD_FIRST_HALF == self.get_bits(&Self::BIT_INDEX_SYNCWORD_START_FIRST_HALF) &&
             Self::SYNC_WORD_SECOND_HALF == self.get_bits(&Self::BIT_INDEX_SYNCWORD_START_SECOND_HALF)
     }
+    /// Tells if the polarity correction bit is set correctly. It is set by the generator so that
+    /// every frame contains an even number of zeros. The sync-word contains three zeros, so the
+    /// data needs an odd number of ones
+    pub(crate) fn parity_valid(&self) -> bool {
+        self.data.count_ones() % 2 == 1
+    }
+    /// Tells if the units digits of the timecode are valid BCD digits. The tens digits have too few
+    /// bits to exceed 9
+    pub(crate) fn bcd_valid(&self) -> bool {
+        [&Self::BIT_INDEX_FRAMES, &Self::BIT_INDEX_SECONDS[..], &Self::BIT_INDEX_MINUTES, &Self::BIT_INDEX_HOURS]
+            .iter()
+            .all(|index| self.get_bits(&index[..4]) <= 9)
+    }
+    /// Tells if the drop-frame flag is set
+    pub(crate) fn is_drop_frame(&self) -> bool {
+        self.data.bit(Self::BIT_INDEX_DROP_FRAME.index)
+    }
+    /// Tells if the color frame flag is set
+    pub(crate) fn is_color_frame(&self) -> bool {
+        self.data.bit(Self::BIT_INDEX_COLOR_FRAME.index)
+    }
+    /// Returns the binary groups and flags. In 25fps and 50fps timecode BGF0 and BGF2 are moved
+    /// by the polarity correction bit. The flag that carries the frame-pair flag in 50fps and 60fps
+    /// timecode is read as not set
+    pub(crate) fn get_user_bits(&self, frames_per_second: &FramesPerSecond) -> UserBits {
+        let mut groups = 0;
+        for group in 0..8 {
+            groups |= (self.get_bits(&Self::user_bits_group(group)) as u32) << (group * 4);
+        }
+        let mut binary_group_flags = 0;
+        for (index, flag) in Self::binary_group_flag_indices(frames_per_second) {
+            if self.data.bit(index.index) && !Self::is_frame_pair_flag(&index, frames_per_second) {
+                binary_group_flags |= flag;
+            }
+        }
+        UserBits { groups, binary_group_flags }
+    }
     /// Returns the number of frames in the LtcFrameData
     pub(crate) fn get_frames(&self) -> u8 {
         self.get_bits(&Self::BIT_INDEX_FRAMES)
@@ -132,6 +188,13 @@ impl LtcFrameData {
     pub(crate) fn get_hours(&self) -> u8 {
         self.get_bits(&Self::BIT_INDEX_HOURS)
     }
+    /// Moves the first bits received after the sync-word to the positions they have once all data
+    /// is received. The bits that are not received yet are 0
+    pub(crate) fn aligned_partial(&self, bits: u8) -> Self {
+        Self {
+            data: self.data.checked_shl(64 - bits as u32).unwrap_or(0)
+        }
+    }
     ///Adds a bit at the end of the stream and returns the one on the beginning
     /// When reading from an ltc-audio-stream bit by bit can be passed in until the SyncKeyword matches the position whenn all data is received
     /// The overflow is needed to add it to the current SyncWord in LtcFrame to detect if the frame is complete
@@ -143,10 +206,150 @@ impl LtcFrameData {
     }
 }
 
+///Write data implementation. Also used by the test fixtures to build raw frames
+#[cfg(any(test, feature = "encode_ltc"))]
+impl LtcFrameData {
+    /// Bit that is set to get an even number of zeros in a 25fps or 50fps frame
+    const BIT_INDEX_POLARITY_CORRECTION_TWENTY_FIVE: BitIndex = BitIndex::new(59, 1);
+    /// Bit that is set to get an even number of zeros in all other frames
+    const BIT_INDEX_POLARITY_CORRECTION: BitIndex = BitIndex::new(27, 1);
+
+    /// Constructor for the data to encode a TimecodeFrame
+    pub(crate) fn from_timecode_frame(frame: &TimecodeFrame) -> Self {
+        let mut data = Self { data: 0 };
+        if frame.frames_per_second.has_frame_pairs() {
+            data.set_frames(frame.frames / 2);
+            data.set_frame_pair_flag(&frame.frames_per_second, frame.frames % 2 == 1);
+        } else {
+            data.set_frames(frame.frames);
+        }
+        data.set_seconds(frame.seconds);
+        data.set_minutes(frame.minutes);
+        data.set_hours(frame.hours);
+        data.set_drop_frame(frame.frames_per_second.is_drop_frame());
+        data.set_polarity_correction(&frame.frames_per_second);
+        data
+    }
+    /// Sets the frame number. Counts frame-pairs in 50fps and 60fps timecode, as returned by get_frames
+    pub(crate) fn set_frames(&mut self, frames: u8) {
+        self.set_bits(&Self::BIT_INDEX_FRAMES, frames);
+    }
+    pub(crate) fn set_seconds(&mut self, seconds: u8) {
+        self.set_bits(&Self::BIT_INDEX_SECONDS, seconds);
+    }
+    pub(crate) fn set_minutes(&mut self, minutes: u8) {
+        self.set_bits(&Self::BIT_INDEX_MINUTES, minutes);
+    }
+    pub(crate) fn set_hours(&mut self, hours: u8) {
+        self.set_bits(&Self::BIT_INDEX_HOURS, hours);
+    }
+    /// Sets the binary groups and flags. Inverse of get_user_bits, so a flag that carries the
+    /// frame-pair flag is left untouched
+    pub(crate) fn set_user_bits(&mut self, user_bits: &UserBits, frames_per_second: &FramesPerSecond) {
+        for group in 0..8 {
+            self.set_bits(&Self::user_bits_group(group), user_bits.group(group as usize));
+        }
+        for (index, flag) in Self::binary_group_flag_indices(frames_per_second) {
+            if !Self::is_frame_pair_flag(&index, frames_per_second) {
+                self.data.set_bit(index.index, user_bits.binary_group_flags & flag != 0);
+            }
+        }
+    }
+    pub(crate) fn set_drop_frame(&mut self, drop_frame: bool) {
+        self.data.set_bit(Self::BIT_INDEX_DROP_FRAME.index, drop_frame);
+    }
+    pub(crate) fn set_color_frame(&mut self, color_frame: bool) {
+        self.data.set_bit(Self::BIT_INDEX_COLOR_FRAME.index, color_frame);
+    }
+    /// Sets the flag that tells the odd frame of a frame-pair in 50fps and 60fps timecode
+    pub(crate) fn set_frame_pair_flag(&mut self, frames_per_second: &FramesPerSecond, odd: bool) {
+        self.data.set_bit(Self::frame_pair_index(frames_per_second), odd);
+    }
+    /// Sets the polarity correction bit according to all other bits. Call it after the last setter
+    pub(crate) fn set_polarity_correction(&mut self, frames_per_second: &FramesPerSecond) {
+        let polarity_correction = if matches!(frames_per_second, FramesPerSecond::TwentyFive | FramesPerSecond::Fifty) {
+            Self::BIT_INDEX_POLARITY_CORRECTION_TWENTY_FIVE
+        } else {
+            Self::BIT_INDEX_POLARITY_CORRECTION
+        };
+        self.data.set_bit(polarity_correction.index, false);
+        // Frame plus sync-word (three zeros) need an even number of zeros
+        self.data.set_bit(polarity_correction.index, self.data.count_ones().is_multiple_of(2));
+    }
+    /// Helper function that writes a BCD value to the bits of index. Inverse of get_bits
+    fn set_bits(&mut self, index: &[BitIndex], value: u8) {
+        let mut rest = value;
+        for i in index.iter().rev() {
+            let bit = rest >= i.weight;
+            if bit {
+                rest -= i.weight;
+            }
+            self.data.set_bit(i.index, bit);
+        }
+    }
+    /// Returns all 80 bits including the sync-word in the order they are sent
+    pub(crate) fn to_bits(self) -> [bool; 80] {
+        let mut bits = [false; 80];
+        for (i, bit) in bits.iter_mut().take(64).enumerate() {
+            *bit = self.data.bit(63 - i);
+        }
+        for (i, bit) in bits.iter_mut().skip(64).enumerate() {
+            *bit = LtcFrame::LTC_SYNC_WORD.bit(15 - i);
+        }
+        bits
+    }
+}
+
 #[cfg(feature = "decode_ltc")]
 impl LtcFrameData {
-    pub(crate) fn make_ltc_frame(&self, duration_for_frame_without_syncword_in_s: f32) -> TimecodeFrame {
-        TimecodeFrame::new_from_duration(self.get_hours(), self.get_minutes(), self.get_seconds(), self.get_frames(), duration_for_frame_without_syncword_in_s)
+    pub(crate) fn make_ltc_frame_with_fps(&self, frames_per_second: FramesPerSecond) -> TimecodeFrame {
+        self.build_frame(&self.with_drop_frame_flag(frames_per_second))
+    }
+    /// Builds the frame from the decoded fields. `frames_per_second` is taken as it is
+    pub(crate) fn build_frame<B: FrameBuilder>(&self, frames_per_second: &FramesPerSecond) -> B {
+        let frames = if frames_per_second.has_frame_pairs() {
+            self.get_frames() * 2 + self.data.bit(Self::frame_pair_index(frames_per_second)) as u8
+        } else {
+            self.get_frames()
+        };
+        B::build(self.get_hours(), self.get_minutes(), self.get_seconds(), frames, frames_per_second)
+    }
+    /// 29.97 fps can't be told apart from 30 fps by timing, so the flag decides
+    fn with_drop_frame_flag(&self, frames_per_second: FramesPerSecond) -> FramesPerSecond {
+        if frames_per_second == FramesPerSecond::Thirty && self.is_drop_frame() {
+            FramesPerSecond::ThirtyDropFrame
+        } else {
+            frames_per_second
+        }
+    }
+}
+
+impl LtcFrameData {
+    /// The positions of BGF0 and BGF2, which change places with the polarity correction bit in
+    /// 25fps and 50fps timecode
+    fn binary_group_flag_indices(frames_per_second: &FramesPerSecond) -> [(BitIndex, u8); 3] {
+        let (bgf0, bgf2) = match frames_per_second {
+            FramesPerSecond::TwentyFive | FramesPerSecond::Fifty => (Self::BIT_INDEX_BGF0_TWENTY_FIVE, Self::BIT_INDEX_BGF2_TWENTY_FIVE),
+            _ => (Self::BIT_INDEX_BGF0, Self::BIT_INDEX_BGF2),
+        };
+        [(bgf0, UserBits::BGF0), (Self::BIT_INDEX_BGF1, UserBits::BGF1), (bgf2, UserBits::BGF2)]
+    }
+    /// Tells if the bit at index carries the frame-pair flag instead of a binary group flag
+    fn is_frame_pair_flag(index: &BitIndex, frames_per_second: &FramesPerSecond) -> bool {
+        frames_per_second.has_frame_pairs() && index.index == Self::frame_pair_index(frames_per_second)
+    }
+    /// The four bits of a binary group, 0 for group 1
+    const fn user_bits_group(group: u8) -> [BitIndex; 4] {
+        let first = 4 + group * 8;
+        [BitIndex::new(first, 1), BitIndex::new(first + 1, 2), BitIndex::new(first + 2, 4), BitIndex::new(first + 3, 8)]
+    }
+    /// Index of the frame-pair flag for 50fps and 60fps timecode
+    fn frame_pair_index(frames_per_second: &FramesPerSecond) -> u8 {
+        if *frames_per_second == FramesPerSecond::Fifty {
+            Self::BIT_INDEX_FRAME_PAIR_FIFTY.index
+        } else {
+            Self::BIT_INDEX_FRAME_PAIR_SIXTY.index
+        }
     }
 }
 
@@ -194,3 +397,34 @@ impl Display for LtcFrameData {
                self.get_frames())
     }
 }
+
+#[cfg(all(test, feature = "decode_ltc"))]
+mod tests {
+    use crate::ltc_frame::ltc_frame_data::LtcFrameData;
+    use crate::FramesPerSecond::{Fifty, Thirty, TwentyFive};
+    use crate::{TimecodeFrame, UserBits};
+
+    #[test]
+    fn test_setters() {
+        for frames_per_second in [TwentyFive, Thirty, Fifty] {
+            let user_bits = UserBits { groups: 0x8765_4321, binary_group_flags: UserBits::BGF0 | UserBits::BGF2 };
+            let mut data = LtcFrameData::new_empty();
+            data.set_hours(23);
+            data.set_minutes(59);
+            data.set_seconds(58);
+            data.set_frames(12);
+            data.set_frame_pair_flag(&Fifty, frames_per_second == Fifty);
+            data.set_user_bits(&user_bits, &frames_per_second);
+            data.set_color_frame(true);
+            data.set_polarity_correction(&frames_per_second);
+            assert!(data.parity_valid());
+            assert!(data.bcd_valid());
+            assert!(!data.is_drop_frame());
+            let frames = if frames_per_second == Fifty { 25 } else { 12 };
+            assert_eq!(data.make_ltc_frame_with_fps(frames_per_second.clone()), TimecodeFrame::new(23, 59, 58, frames, frames_per_second.clone()));
+            // The position of BGF0 carries the frame-pair flag in 50fps timecode
+            let expected_flags = if frames_per_second == Fifty { UserBits::BGF2 } else { user_bits.binary_group_flags };
+            assert_eq!(data.get_user_bits(&frames_per_second), UserBits { groups: user_bits.groups, binary_group_flags: expected_flags });
+        }
+    }
+}
@@ -6,6 +6,7 @@ pub(crate) mod ltc_frame_data;
 
 /// Represents 80 bits that represent a ltc-tc-frame
 /// Contains functions to push bits received by an audio signal and read it's value as well as functions to write bits to the audio
+#[derive(Clone)]
 pub(crate) struct LtcFrame {
     ///Are on higher index of all bits received
     sync_word: u16,
@@ -26,7 +27,12 @@ impl PartialEq<Self> for LtcFrame {
 
 ///Implementations that are used to decode and encode timecode
 impl LtcFrame {
-    const LTC_SYNC_WORD: u16 = 0b_0011_1111_1111_1101;
+    pub(crate) const LTC_SYNC_WORD: u16 = 0b_0011_1111_1111_1101;
+    /// Number of bits of a frame including the sync-word
+    #[cfg(feature = "decode_ltc")]
+    pub(crate) const BIT_COUNT: u8 = 80;
+    #[cfg(feature = "decode_ltc")]
+    pub(crate) const SYNC_WORD_BIT_COUNT: u8 = 16;
 
     /// Invalidates the current status of the ltc-frame
     pub(crate) fn invalidate(&mut self) {
@@ -66,31 +72,36 @@ impl LtcFrame {
         }
     }
     ///When a new audio bit is received, this function will shift all received data and add it to the end. Once the sync_word matches, the data is a valid frame
-    pub(crate) fn shift_bit(&mut self, bit: bool) {
+    /// Returns true if the bit completed a sync-word
+    pub(crate) fn shift_bit(&mut self, bit: bool) -> bool {
         let overflow_bit = self.data.shift_bit_with_overflow(bit);
         self.sync_word <<= 1;
         self.sync_word.set_bit(0, overflow_bit);
+        let sync_word_completed = self.data.next_bit_is_start_of_frame();
+        if sync_word_completed {
+            self.frame_data_sample_count = 0;
+        }
+        sync_word_completed
     }
     ///Tells if all data is received by the audio stream after the sync-word
     pub(crate) fn data_valid(&self) -> bool {
         self.sync_word == Self::LTC_SYNC_WORD
     }
     ///Used to count how many samples a timecode-frame has needed to complete do determine FramesPerSecond of LTC
+    /// The count is reset by shift_bit as soon as the sync-word is complete, so it covers all 64 data bits.
+    /// It saturates, as no sync-word may be received for days
     pub(crate) fn sample_received(&mut self) {
-        if self.data.next_bit_is_start_of_frame() {
-            self.frame_data_sample_count = 0;
-        } else {
-            self.frame_data_sample_count += 1;
-        }
+        self.frame_data_sample_count = self.frame_data_sample_count.saturating_add(1);
     }
 
-    ///Returns the data read from audio decoding only if all data has been received after the sync-word
-    /// It may be more efficient to first check if data_valid() returns true due to less memory allocation in ram
-    pub(crate) fn get_data(&mut self) -> Option<(LtcFrameData, usize)> {
-        if self.data_valid() {
-            Some((self.data.clone(), self.frame_data_sample_count))
-        } else {
-            None
-        }
+    ///Returns the data received since the sync-word, aligned as if the frame was complete. The count
+    /// of bits received has to be tracked by the caller and must not exceed 64
+    pub(crate) fn get_partial_data(&self, bits: u8) -> LtcFrameData {
+        self.data.aligned_partial(bits)
+    }
+    ///Returns the data read from audio decoding only if all data has been received after the sync-word.
+    /// The data is a copied u64, nothing is allocated
+    pub(crate) fn get_data(&self) -> Option<(LtcFrameData, usize)> {
+        self.data_valid().then_some((self.data, self.frame_data_sample_count))
     }
 }
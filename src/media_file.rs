@@ -0,0 +1,150 @@
+//! Helpers to decode timecode from compressed media files (FLAC, MP3, AAC, OGG...) via symphonia
+
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::ltc_decoder::LtcDecoder;
+use crate::TimecodeFrame;
+
+/// Errors that may occur when decoding timecode from a media file
+#[derive(Debug)]
+pub enum MediaDecodeError {
+    /// The file could not be opened
+    Io(std::io::Error),
+    /// The file could not be read or decoded by symphonia
+    Symphonia(Error),
+    /// The file does not contain an audio track, or its sampling rate is unknown or 0
+    NoAudioTrack,
+    /// The selected channel does not exist in the audio track
+    ChannelOutOfRange { channel: u16, channel_count: u16 },
+}
+
+impl Display for MediaDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MediaDecodeError::Io(e) => write!(f, "Could not open media file: {}", e),
+            MediaDecodeError::Symphonia(e) => write!(f, "Could not decode media file: {}", e),
+            MediaDecodeError::NoAudioTrack => write!(f, "No audio track found"),
+            MediaDecodeError::ChannelOutOfRange { channel, channel_count } => write!(f, "Channel {} selected but the audio track only has {} channels", channel, channel_count),
+        }
+    }
+}
+
+impl std::error::Error for MediaDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MediaDecodeError::Io(e) => Some(e),
+            MediaDecodeError::Symphonia(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for MediaDecodeError {
+    fn from(e: std::io::Error) -> Self {
+        MediaDecodeError::Io(e)
+    }
+}
+
+impl From<Error> for MediaDecodeError {
+    fn from(e: Error) -> Self {
+        MediaDecodeError::Symphonia(e)
+    }
+}
+
+/// Decodes all timecode-frames of one channel (0 is the first channel) of the first audio track in
+/// a media file. Returns the frames together with the index of the sample (per channel) at which
+/// they were decoded
+pub fn decode_media_file<P: AsRef<Path>>(path: P, channel: u16) -> Result<Vec<(u64, TimecodeFrame)>, MediaDecodeError> {
+    let path = path.as_ref();
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let source = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let probed = symphonia::default::get_probe().format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())?;
+    let mut format = probed.format;
+    let track = format.tracks().iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or(MediaDecodeError::NoAudioTrack)?;
+    let track_id = track.id;
+    // A sampling rate of 0 is as unusable as a missing one
+    let sample_rate = track.codec_params.sample_rate.filter(|rate| *rate > 0).ok_or(MediaDecodeError::NoAudioTrack)?;
+    let mut audio_decoder = symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut decoder = LtcDecoder::<i32>::new(sample_rate);
+    let mut frames = Vec::new();
+    let mut index: u64 = 0;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match audio_decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Corrupt packets are skipped, the ltc decoder will resync
+            Err(Error::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let spec = *decoded.spec();
+        let channel_count = spec.channels.count();
+        if channel as usize >= channel_count {
+            return Err(MediaDecodeError::ChannelOutOfRange { channel, channel_count: channel_count as u16 });
+        }
+        let mut buffer = SampleBuffer::<i32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        for sample in buffer.samples().iter().skip(channel as usize).step_by(channel_count) {
+            if let Some(frame) = decoder.get_timecode_frame(*sample) {
+                frames.push((index, frame));
+            }
+            index += 1;
+        }
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::FramesPerSecond::Thirty;
+    use crate::ltc_decoder::test_signal::{ltc_samples, wav_data, SignalShape};
+    use crate::media_file::{decode_media_file, MediaDecodeError};
+    use crate::TimecodeFrame;
+
+    /// A path in the temp directory that concurrent test runs don't share
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("timecode_coder_{}_{}", std::process::id(), name))
+    }
+
+    fn assert_decoded(path: &Path) {
+        let frames = decode_media_file(path, 0).unwrap();
+        assert_eq!(frames.len(), 59);
+        assert_eq!(frames[0].1, TimecodeFrame::new(23, 59, 59, 1, Thirty));
+        assert!(matches!(decode_media_file(path, 1), Err(MediaDecodeError::ChannelOutOfRange { channel: 1, channel_count: 1 })));
+    }
+
+    #[test]
+    fn test_decode_media_file() {
+        let samples = ltc_samples(&TimecodeFrame::new(23, 59, 59, 0, Thirty), 60, &SignalShape::new(20.0));
+        let path = temp_path("decode_media_file.wav");
+        std::fs::write(&path, wav_data(samples).into_inner()).unwrap();
+        assert_decoded(&path);
+        std::fs::remove_file(path).unwrap();
+        // The same signal as FLAC
+        assert_decoded(Path::new("testfiles/LTC_23595900_2s_30fps_48000x16.flac"));
+    }
+}
@@ -0,0 +1,190 @@
+#[cfg(feature = "decode_ltc")]
+use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig, Sample};
+#[cfg(feature = "encode_ltc")]
+use crate::ltc_encoder::LtcEncoder;
+#[cfg(feature = "decode_ltc")]
+use crate::mtc::quarter_frames;
+#[cfg(feature = "encode_ltc")]
+use crate::mtc::MtcDecoder;
+
+/// Decodes LTC audio and returns MTC quarter-frame messages at the sample they have to be sent.
+/// The messages are timed with the extrapolated timecode, so piece 0 is sent at the start of a frame
+/// although LTC frames are only decoded at 64/80 of their duration
+#[cfg(feature = "decode_ltc")]
+pub struct LtcToMtcBridge<T: Sample> {
+    decoder: LtcDecoder<T>,
+    /// Messages of the current two-frame cycle. None until the next cycle starts
+    messages: Option<[[u8; 2]; 8]>,
+    /// The quarter-frame (0..8 within the cycle) of the last sample
+    last_quarter: Option<u8>,
+}
+
+#[cfg(feature = "decode_ltc")]
+impl<T: Sample> LtcToMtcBridge<T> {
+    pub fn new(sampling_rate: u32) -> Self {
+        Self::new_with_config(sampling_rate, LtcDecoderConfig::default())
+    }
+    pub fn new_with_config(sampling_rate: u32, config: LtcDecoderConfig) -> Self {
+        Self {
+            decoder: LtcDecoder::new_with_config(sampling_rate, config),
+            messages: None,
+            last_quarter: None,
+        }
+    }
+    /// Push received audio-sample-points one after another. Returns the quarter-frame message
+    /// (status and data byte) to send at this sample
+    pub fn push_sample(&mut self, sample: T) -> Option<[u8; 2]> {
+        self.decoder.get_timecode_frame(sample);
+        let Some((frame, sub_frame)) = self.decoder.extrapolated_now() else {
+            self.messages = None;
+            self.last_quarter = None;
+            return None;
+        };
+        let frame_count = frame.to_frame_count()?;
+        let quarter = (frame_count % 2) as u8 * 4 + (sub_frame * 4.0) as u8 % 4;
+        if self.last_quarter == Some(quarter) {
+            return None;
+        }
+        let follows_last_quarter = self.last_quarter.map(|last| (last + 1) % 8) == Some(quarter);
+        self.last_quarter = Some(quarter);
+        if quarter == 0 {
+            // A cycle describes the frame in which piece 0 is sent
+            self.messages = quarter_frames(&frame);
+        } else if !follows_last_quarter {
+            // Jumped, wait for the next cycle
+            self.messages = None;
+        }
+        self.messages.map(|messages| messages[quarter as usize])
+    }
+    /// The underlying LTC decoder, e.g. to query its sync state
+    pub fn decoder(&self) -> &LtcDecoder<T> {
+        &self.decoder
+    }
+}
+
+/// Generates LTC audio from received MTC. The LTC restarts at the received timecode when the MTC
+/// jumps and stops when no MTC is received anymore
+#[cfg(feature = "encode_ltc")]
+pub struct MtcToLtcBridge<T: Copy> {
+    decoder: MtcDecoder,
+    encoder: Option<LtcEncoder<T>>,
+    sampling_rate: u32,
+    high: T,
+    low: T,
+    samples_since_frame: u32,
+}
+
+#[cfg(feature = "encode_ltc")]
+impl<T: Copy> MtcToLtcBridge<T> {
+    /// The LTC stops if no frame was received for this number of frame durations
+    const TIMEOUT_FRAMES: u32 = 3;
+
+    /// Constructor. `high` and `low` are the sample values of the signal. The output stays at `low`
+    /// while no MTC is received
+    pub fn new(sampling_rate: u32, high: T, low: T) -> Self {
+        Self {
+            decoder: MtcDecoder::new(),
+            encoder: None,
+            sampling_rate,
+            high,
+            low,
+            samples_since_frame: 0,
+        }
+    }
+    /// Push received MIDI bytes in the moment they are received, between the samples they arrived
+    pub fn push_byte(&mut self, byte: u8) {
+        let Some(frame) = self.decoder.push_byte(byte) else {
+            return;
+        };
+        self.samples_since_frame = 0;
+        let in_sync = self.encoder.as_ref().map(|encoder| {
+            let current = encoder.current_timecode();
+            *current == frame || current.checked_add_frames(1).as_ref() == Some(&frame)
+        });
+        if in_sync != Some(true) {
+            // The received frame starts now
+            self.encoder = LtcEncoder::new(frame, self.sampling_rate, self.high, self.low);
+        }
+    }
+    /// Returns the next sample of the LTC signal
+    pub fn next_sample(&mut self) -> T {
+        self.samples_since_frame = self.samples_since_frame.saturating_add(1);
+        let Some(encoder) = self.encoder.as_mut() else {
+            return self.low;
+        };
+        let frames_per_second = encoder.current_timecode().frames_per_second.frames_per_second_u8().unwrap_or(1) as u32;
+        if self.samples_since_frame > self.sampling_rate * Self::TIMEOUT_FRAMES / frames_per_second {
+            self.encoder = None;
+            self.decoder.invalidate();
+            return self.low;
+        }
+        encoder.next_sample()
+    }
+    /// Fills a buffer with the next samples
+    pub fn fill(&mut self, buffer: &mut [T]) {
+        for sample in buffer {
+            *sample = self.next_sample();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "decode_ltc", feature = "encode_ltc"))]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::LtcDecoder;
+    use crate::ltc_encoder::LtcEncoder;
+    use crate::mtc::{LtcToMtcBridge, MtcDecoder, MtcToLtcBridge};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_ltc_to_mtc() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let mut encoder = LtcEncoder::new(start.clone(), 48_000, i16::MAX / 2, i16::MIN / 2).unwrap();
+        let mut bridge = LtcToMtcBridge::<i16>::new(48_000);
+        let mut mtc_decoder = MtcDecoder::new();
+        let mut frames = 0;
+        for i in 0..48_000 * 2 {
+            let Some(message) = bridge.push_sample(encoder.next_sample()) else {
+                continue;
+            };
+            // Quarter-frames are sent every 480 samples, on the boundaries of the LTC
+            assert!(i % 480 < 5 || i % 480 > 475, "Message at sample {}", i);
+            mtc_decoder.push_byte(message[0]);
+            if let Some(frame) = mtc_decoder.push_byte(message[1]) {
+                let expected = start.checked_add_frames((i + 960) / 1920).unwrap();
+                assert_eq!(frame, expected);
+                frames += 1;
+            }
+        }
+        assert!(frames > 40);
+    }
+
+    #[test]
+    fn test_mtc_to_ltc() {
+        let mut bridge = MtcToLtcBridge::new(48_000, i16::MAX / 2, i16::MIN / 2);
+        let mut ltc_decoder = LtcDecoder::<i16>::new(48_000);
+        let mut frame = TimecodeFrame::new(20, 0, 0, 0, TwentyFive);
+        let mut decoded = Vec::new();
+        for cycle in 0..25 {
+            for message in crate::mtc::quarter_frames(&frame).unwrap() {
+                bridge.push_byte(message[0]);
+                bridge.push_byte(message[1]);
+                for _ in 0..480 {
+                    decoded.extend(ltc_decoder.get_timecode_frame(bridge.next_sample()));
+                }
+            }
+            if cycle < 24 {
+                frame.add_frame();
+                frame.add_frame();
+            }
+        }
+        assert!(decoded.len() > 40);
+        assert!(decoded.windows(2).all(|frames| frames[0].checked_add_frames(1).as_ref() == Some(&frames[1])));
+        // The LTC stops without MTC
+        for _ in 0..48_000 {
+            decoded.extend(ltc_decoder.get_timecode_frame(bridge.next_sample()));
+        }
+        // The last cycle covers two frames, then the LTC runs until the timeout of three frames
+        assert!(decoded.last().unwrap().to_frame_count().unwrap() <= frame.to_frame_count().unwrap() + 5);
+    }
+}
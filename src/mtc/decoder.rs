@@ -0,0 +1,229 @@
+use crate::mtc::{frames_per_second_from_rate_code, QUARTER_FRAME_STATUS};
+use crate::{SyncState, TimecodeFrame};
+
+const SYSEX_START: u8 = 0xF0;
+const SYSEX_END: u8 = 0xF7;
+/// Full-frame message without SYSEX_START and SYSEX_END. The third byte is the device id
+const FULL_FRAME_HEADER: [u8; 4] = [0x7F, 0x7F, 0x01, 0x01];
+const FULL_FRAME_LENGTH: usize = 8;
+
+/// What the next data byte belongs to
+enum PendingMessage {
+    None,
+    QuarterFrame,
+    SysEx,
+}
+
+/// Assembles MIDI Timecode quarter-frame and full-frame messages into TimecodeFrames. Only forward
+/// running MTC is supported
+pub struct MtcDecoder {
+    pending: PendingMessage,
+    sysex: [u8; FULL_FRAME_LENGTH],
+    sysex_length: usize,
+    /// Nibbles of the eight quarter-frame pieces
+    pieces: [u8; 8],
+    /// The piece that is expected next. None if out of sync
+    next_piece: Option<u8>,
+    /// Number of pieces received in order since piece 0
+    pieces_in_order: u8,
+    /// The last returned frame
+    last_frame: Option<TimecodeFrame>,
+    /// The sync state that was returned by poll_sync_state_change last
+    reported_sync_state: SyncState,
+}
+
+impl MtcDecoder {
+    pub fn new() -> Self {
+        Self {
+            pending: PendingMessage::None,
+            sysex: [0; FULL_FRAME_LENGTH],
+            sysex_length: 0,
+            pieces: [0; 8],
+            next_piece: None,
+            pieces_in_order: 0,
+            last_frame: None,
+            reported_sync_state: SyncState::Unlocked,
+        }
+    }
+
+    /// Push received MIDI bytes one after another in this function. Returns the current frame every
+    /// time a frame boundary is passed and after full-frame messages. Other MIDI messages are ignored
+    pub fn push_byte(&mut self, byte: u8) -> Option<TimecodeFrame> {
+        if byte >= 0xF8 {
+            // Real-time messages may be sent in between the bytes of other messages
+            return None;
+        }
+        if byte & 0x80 != 0 {
+            self.pending = match byte {
+                QUARTER_FRAME_STATUS => PendingMessage::QuarterFrame,
+                SYSEX_START => {
+                    self.sysex_length = 0;
+                    PendingMessage::SysEx
+                }
+                SYSEX_END if matches!(self.pending, PendingMessage::SysEx) => {
+                    self.pending = PendingMessage::None;
+                    return self.full_frame_received();
+                }
+                _ => PendingMessage::None,
+            };
+            return None;
+        }
+        match self.pending {
+            PendingMessage::None => None,
+            PendingMessage::QuarterFrame => {
+                self.pending = PendingMessage::None;
+                self.quarter_frame_received(byte)
+            }
+            PendingMessage::SysEx => {
+                if self.sysex_length < FULL_FRAME_LENGTH {
+                    self.sysex[self.sysex_length] = byte;
+                }
+                self.sysex_length = self.sysex_length.saturating_add(1);
+                None
+            }
+        }
+    }
+
+    /// Tells if the decoder is locked to the received MTC. MTC carries no timing, so the caller has
+    /// to call invalidate if no MTC is received for some time
+    pub fn sync_state(&self) -> SyncState {
+        match (&self.last_frame, self.next_piece) {
+            (Some(_), _) => SyncState::Locked,
+            (None, Some(_)) => SyncState::Locking,
+            (None, None) => SyncState::Unlocked,
+        }
+    }
+
+    /// Returns the new sync state if it changed since the last call
+    pub fn poll_sync_state_change(&mut self) -> Option<SyncState> {
+        let sync_state = self.sync_state();
+        if sync_state == self.reported_sync_state {
+            return None;
+        }
+        self.reported_sync_state = sync_state;
+        Some(sync_state)
+    }
+
+    /// Forgets all received messages, e.g. if no MTC was received for some time
+    pub fn invalidate(&mut self) {
+        self.next_piece = None;
+        self.pieces_in_order = 0;
+        self.last_frame = None;
+    }
+
+    fn quarter_frame_received(&mut self, data: u8) -> Option<TimecodeFrame> {
+        let piece = data >> 4;
+        if piece == 0 {
+            self.pieces_in_order = 0;
+        } else if self.next_piece != Some(piece) {
+            self.invalidate();
+            return None;
+        }
+        self.pieces[piece as usize] = data & 0x0F;
+        self.pieces_in_order = self.pieces_in_order.saturating_add(1);
+        self.next_piece = Some((piece + 1) % 8);
+        match piece {
+            // Half of the message cycle, which lasts two frames
+            3 => {
+                let frame = self.last_frame.as_mut()?;
+                frame.add_frame();
+                Some(frame.clone())
+            }
+            7 if self.pieces_in_order == 8 => {
+                let frames_per_second = frames_per_second_from_rate_code(self.pieces[7] >> 1);
+                let mut frame = TimecodeFrame::new(
+                    self.pieces[6] | (self.pieces[7] & 0x01) << 4,
+                    self.pieces[4] | self.pieces[5] << 4,
+                    self.pieces[2] | self.pieces[3] << 4,
+                    self.pieces[0] | self.pieces[1] << 4,
+                    frames_per_second,
+                );
+                // The pieces describe the frame in which piece 0 was sent. Two frames passed since
+                frame.add_frame();
+                frame.add_frame();
+                self.last_frame = Some(frame.clone());
+                Some(frame)
+            }
+            _ => None,
+        }
+    }
+
+    fn full_frame_received(&mut self) -> Option<TimecodeFrame> {
+        if self.sysex_length != FULL_FRAME_LENGTH || self.sysex[0] != FULL_FRAME_HEADER[0] || self.sysex[2..4] != FULL_FRAME_HEADER[2..4] {
+            return None;
+        }
+        let hours = self.sysex[4];
+        let frame = TimecodeFrame::new(hours & 0x1F, self.sysex[5], self.sysex[6], self.sysex[7], frames_per_second_from_rate_code(hours >> 5));
+        // Quarter-frames start over after a full-frame message
+        self.next_piece = None;
+        self.pieces_in_order = 0;
+        self.last_frame = Some(frame.clone());
+        Some(frame)
+    }
+}
+
+impl Default for MtcDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::{ThirtyDropFrame, TwentyFive};
+    use crate::mtc::{quarter_frames, MtcDecoder};
+    use crate::{SyncState, TimecodeFrame};
+
+    #[test]
+    fn test_quarter_frames() {
+        let mut decoder = MtcDecoder::new();
+        let mut frame = TimecodeFrame::new(10, 59, 59, 20, TwentyFive);
+        let mut decoded = Vec::new();
+        let mut expected = Vec::new();
+        // Start in the middle of a message cycle
+        let mut bytes = quarter_frames(&frame).unwrap()[5..].concat();
+        for _ in 0..4 {
+            frame.add_frame();
+            frame.add_frame();
+            bytes.extend(quarter_frames(&frame).unwrap().concat());
+        }
+        for byte in bytes {
+            decoded.extend(decoder.push_byte(byte));
+        }
+        let mut frame = TimecodeFrame::new(10, 59, 59, 24, TwentyFive);
+        for _ in 0..7 {
+            expected.push(frame.clone());
+            frame.add_frame();
+        }
+        assert_eq!(decoded, expected);
+        assert_eq!(decoder.sync_state(), SyncState::Locked);
+    }
+
+    #[test]
+    fn test_out_of_order() {
+        let mut decoder = MtcDecoder::new();
+        let messages = quarter_frames(&TimecodeFrame::new(1, 0, 0, 0, TwentyFive)).unwrap();
+        for message in &messages[..4] {
+            decoder.push_byte(message[0]);
+            decoder.push_byte(message[1]);
+        }
+        assert_eq!(decoder.poll_sync_state_change(), Some(SyncState::Locking));
+        for message in &messages[5..] {
+            assert_eq!(decoder.push_byte(message[0]), None);
+            assert_eq!(decoder.push_byte(message[1]), None);
+        }
+        assert_eq!(decoder.poll_sync_state_change(), Some(SyncState::Unlocked));
+    }
+
+    #[test]
+    fn test_full_frame() {
+        let mut decoder = MtcDecoder::new();
+        // 01:02:03;04 at 29.97 drop-frame, interrupted by a timing clock
+        let bytes = [0xF0, 0x7F, 0x7F, 0x01, 0xF8, 0x01, 0x41, 0x02, 0x03, 0x04, 0xF7];
+        let decoded: Vec<_> = bytes.into_iter().filter_map(|byte| decoder.push_byte(byte)).collect();
+        assert_eq!(decoded, [TimecodeFrame::new(1, 2, 3, 4, ThirtyDropFrame)]);
+        // Other SysEx messages are ignored
+        let bytes = [0xF0, 0x7E, 0x7F, 0x06, 0x01, 0xF7];
+        assert!(bytes.into_iter().all(|byte| decoder.push_byte(byte).is_none()));
+    }
+}
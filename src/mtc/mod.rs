@@ -0,0 +1,84 @@
+//! MIDI Timecode (MTC) messages as raw bytes, without depending on any MIDI I/O
+
+use crate::{FramesPerSecond, TimecodeFrame};
+
+#[cfg(any(feature = "decode_ltc", feature = "encode_ltc"))]
+mod bridge;
+mod decoder;
+
+#[cfg(feature = "decode_ltc")]
+pub use bridge::LtcToMtcBridge;
+#[cfg(feature = "encode_ltc")]
+pub use bridge::MtcToLtcBridge;
+pub use decoder::MtcDecoder;
+
+/// Status byte of a MTC quarter-frame message
+pub const QUARTER_FRAME_STATUS: u8 = 0xF1;
+
+/// The rate code of MTC. MTC knows no other rates than these four
+fn rate_code(frames_per_second: &FramesPerSecond) -> Option<u8> {
+    match frames_per_second {
+        FramesPerSecond::TwentyFour => Some(0),
+        FramesPerSecond::TwentyFive => Some(1),
+        FramesPerSecond::ThirtyDropFrame => Some(2),
+        FramesPerSecond::Thirty => Some(3),
+        FramesPerSecond::Unknown | FramesPerSecond::Fifty | FramesPerSecond::Sixty => None,
+    }
+}
+
+/// Inverse of rate_code
+fn frames_per_second_from_rate_code(rate_code: u8) -> FramesPerSecond {
+    match rate_code & 0x03 {
+        0 => FramesPerSecond::TwentyFour,
+        1 => FramesPerSecond::TwentyFive,
+        2 => FramesPerSecond::ThirtyDropFrame,
+        _ => FramesPerSecond::Thirty,
+    }
+}
+
+/// The eight quarter-frame messages (status and data byte) for a frame. The messages describe the
+/// frame in which the first message is sent and are sent evenly spread over two frames, so they are
+/// created for every second frame. None if the frame-rate can't be sent as MTC
+pub fn quarter_frames(frame: &TimecodeFrame) -> Option<[[u8; 2]; 8]> {
+    let rate_code = rate_code(&frame.frames_per_second)?;
+    let nibbles = [
+        frame.frames & 0x0F,
+        (frame.frames >> 4) & 0x01,
+        frame.seconds & 0x0F,
+        (frame.seconds >> 4) & 0x03,
+        frame.minutes & 0x0F,
+        (frame.minutes >> 4) & 0x03,
+        frame.hours & 0x0F,
+        ((frame.hours >> 4) & 0x01) | (rate_code << 1),
+    ];
+    let mut messages = [[0; 2]; 8];
+    for (piece, (message, nibble)) in messages.iter_mut().zip(nibbles).enumerate() {
+        *message = [QUARTER_FRAME_STATUS, ((piece as u8) << 4) | nibble];
+    }
+    Some(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::{Fifty, ThirtyDropFrame, TwentyFive};
+    use crate::mtc::quarter_frames;
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_quarter_frames() {
+        let messages = quarter_frames(&TimecodeFrame::new(23, 59, 58, 24, TwentyFive)).unwrap();
+        assert_eq!(messages, [
+            [0xF1, 0x08],
+            [0xF1, 0x11],
+            [0xF1, 0x2A],
+            [0xF1, 0x33],
+            [0xF1, 0x4B],
+            [0xF1, 0x53],
+            [0xF1, 0x67],
+            [0xF1, 0x73],
+        ]);
+        let messages = quarter_frames(&TimecodeFrame::new(1, 0, 0, 2, ThirtyDropFrame)).unwrap();
+        assert_eq!(messages[7], [0xF1, 0x74]);
+        assert!(quarter_frames(&TimecodeFrame::new(1, 0, 0, 2, Fifty)).is_none());
+    }
+}
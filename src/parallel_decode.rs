@@ -0,0 +1,115 @@
+//! Decodes long recordings on all cores by splitting the samples into chunks
+
+use rayon::prelude::*;
+
+use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig, Sample};
+use crate::TimecodeFrame;
+
+/// Chunks are never shorter than this many seconds, so the warm-up overlap stays negligible
+const MIN_CHUNK_SECONDS: usize = 10;
+/// Each chunk starts decoding this many seconds before its first sample, so the decoder is in sync
+/// when the chunk begins. One second holds more than enough sync words at any frame rate and is
+/// longer than the longest fixed latency (seven frames at 60 fps), so frames held back across the
+/// start of a chunk are returned by that chunk
+const OVERLAP_SECONDS: usize = 1;
+
+/// Decodes all timecode-frames in the samples of one channel, using all threads of the rayon
+/// thread-pool. Returns the same frames and sample indices as pushing the samples one after
+/// another into a single `LtcDecoder` with the same config, including `output_offset` and
+/// `fixed_latency_samples`, as every chunk is decoded from one second before its start
+pub fn decode_samples_parallel<T: Sample + Send + Sync>(samples: &[T], sampling_rate: u32, config: LtcDecoderConfig) -> Vec<(u64, TimecodeFrame)> {
+    let min_chunk_len = sampling_rate as usize * MIN_CHUNK_SECONDS;
+    let chunk_len = samples.len().div_ceil(rayon::current_num_threads() * 4).max(min_chunk_len);
+    decode_chunked(samples, sampling_rate, config, chunk_len)
+}
+
+fn decode_chunked<T: Sample + Send + Sync>(samples: &[T], sampling_rate: u32, config: LtcDecoderConfig, chunk_len: usize) -> Vec<(u64, TimecodeFrame)> {
+    let overlap = sampling_rate as usize * OVERLAP_SECONDS;
+    let chunk_count = samples.len().div_ceil(chunk_len.max(1));
+    (0..chunk_count)
+        .into_par_iter()
+        .map(|chunk| {
+            let start = chunk * chunk_len;
+            let end = (start + chunk_len).min(samples.len());
+            decode_range(samples, sampling_rate, config.clone(), start.saturating_sub(overlap), start, end)
+        })
+        .flatten()
+        .collect()
+}
+
+/// Decodes the samples from `warm_up_start` up to `end`, keeping only frames decoded at or after `start`
+fn decode_range<T: Sample>(samples: &[T], sampling_rate: u32, config: LtcDecoderConfig, warm_up_start: usize, start: usize, end: usize) -> Vec<(u64, TimecodeFrame)> {
+    let mut decoder = LtcDecoder::<T>::new_with_config(sampling_rate, config);
+    samples[warm_up_start..end]
+        .iter()
+        .enumerate()
+        .filter_map(|(offset, sample)| {
+            let index = warm_up_start + offset;
+            decoder.get_timecode_frame(*sample)
+                .filter(|_| index >= start)
+                .map(|frame| (index as u64, frame))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig, TimecodeOffset};
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::parallel_decode::{decode_chunked, decode_samples_parallel};
+    use crate::FramesPerSecond::{Thirty, TwentyFive};
+    use crate::TimecodeFrame;
+
+    fn decode_sequential(samples: &[i32], sampling_rate: u32, config: LtcDecoderConfig) -> Vec<(u64, TimecodeFrame)> {
+        let mut decoder = LtcDecoder::<i32>::new_with_config(sampling_rate, config);
+        samples.iter()
+            .enumerate()
+            .filter_map(|(index, sample)| decoder.get_timecode_frame(*sample).map(|frame| (index as u64, frame)))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_match_sequential_decode() {
+        let start = TimecodeFrame::new(9, 59, 50, 0, TwentyFive);
+        let samples = ltc_samples(&start, 500, &SignalShape::new(24.0));
+        let expected = decode_sequential(&samples, 48_000, LtcDecoderConfig::default());
+        assert_eq!(expected.len(), 499);
+        for chunk_len in [48_000, 100_003, 1_000_000] {
+            assert_eq!(decode_chunked(&samples, 48_000, LtcDecoderConfig::default(), chunk_len), expected);
+        }
+    }
+
+    #[test]
+    fn test_chunks_match_sequential_decode_with_config() {
+        let start = TimecodeFrame::new(9, 59, 50, 0, TwentyFive);
+        let samples = ltc_samples(&start, 500, &SignalShape::new(24.0));
+        let configs = [
+            LtcDecoderConfig { output_offset: TimecodeOffset::Frames(-3), ..LtcDecoderConfig::default() },
+            LtcDecoderConfig { output_offset: TimecodeOffset::Samples(5_000), ..LtcDecoderConfig::default() },
+            LtcDecoderConfig { fixed_latency_samples: Some(4_000), ..LtcDecoderConfig::default() },
+            LtcDecoderConfig { fixed_latency_samples: Some(1_000_000), output_offset: TimecodeOffset::Frames(2), ..LtcDecoderConfig::default() },
+            LtcDecoderConfig { locked_decimation: 4, ..LtcDecoderConfig::default() },
+            LtcDecoderConfig { drift_time_constant: 0, ..LtcDecoderConfig::default() },
+        ];
+        for config in configs {
+            let expected = decode_sequential(&samples, 48_000, config.clone());
+            for chunk_len in [48_000, 100_003, 1_000_000] {
+                assert_eq!(decode_chunked(&samples, 48_000, config.clone(), chunk_len), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_samples_parallel() {
+        let start = TimecodeFrame::new(1, 0, 0, 0, Thirty);
+        let samples = ltc_samples(&start, 900, &SignalShape::new(20.0));
+        let frames = decode_samples_parallel(&samples, 48_000, LtcDecoderConfig::default());
+        assert_eq!(frames, decode_sequential(&samples, 48_000, LtcDecoderConfig::default()));
+        assert_eq!(frames.last().unwrap().1, TimecodeFrame::new(1, 0, 29, 29, Thirty));
+    }
+
+    #[test]
+    fn test_empty_samples() {
+        assert!(decode_samples_parallel::<i32>(&[], 48_000, LtcDecoderConfig::default()).is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! Decodes redundant LTC feeds (main and backup) and outputs a single timecode
+
+use crate::ltc_decoder::{LtcDecoder, LtcDecoderConfig, Sample};
+use crate::{SyncState, TimecodeFrame};
+
+/// One of the two inputs of a `RedundantLtcDecoder`
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LtcSource {
+    Main,
+    Backup,
+}
+
+/// Runs a decoder per feed and returns the frames of the source that is locked, preferring main.
+/// Both feeds have to be sampled with the same clock
+pub struct RedundantLtcDecoder<T: Sample> {
+    main: LtcDecoder<T>,
+    backup: LtcDecoder<T>,
+    active: LtcSource,
+    max_divergence_frames: f64,
+    /// The last returned frame
+    last_frame: Option<TimecodeFrame>,
+}
+
+impl<T: Sample> RedundantLtcDecoder<T> {
+    /// Feeds that are further apart are reported as diverged
+    const DEFAULT_MAX_DIVERGENCE_FRAMES: f64 = 1.0;
+
+    pub fn new(sampling_rate: u32) -> Self {
+        Self::new_with_config(sampling_rate, LtcDecoderConfig::default())
+    }
+    pub fn new_with_config(sampling_rate: u32, config: LtcDecoderConfig) -> Self {
+        Self {
+            main: LtcDecoder::new_with_config(sampling_rate, config.clone()),
+            backup: LtcDecoder::new_with_config(sampling_rate, config),
+            active: LtcSource::Main,
+            max_divergence_frames: Self::DEFAULT_MAX_DIVERGENCE_FRAMES,
+            last_frame: None,
+        }
+    }
+    /// Sets how many frames the feeds may be apart before is_diverged returns true
+    pub fn with_max_divergence_frames(mut self, max_divergence_frames: f64) -> Self {
+        self.max_divergence_frames = max_divergence_frames;
+        self
+    }
+
+    /// Push the samples of both feeds that were taken at the same time. Returns the frames of the
+    /// active source. A frame of the other source is returned if it continues the returned frames
+    /// before the active source delivers it, so no frame is lost while the active source fails
+    pub fn push_samples(&mut self, main: T, backup: T) -> Option<TimecodeFrame> {
+        let main_frame = self.main.get_timecode_frame(main);
+        let backup_frame = self.backup.get_timecode_frame(backup);
+        self.active = self.select_source();
+        let (active_frame, other_frame) = match self.active {
+            LtcSource::Main => (main_frame, backup_frame),
+            LtcSource::Backup => (backup_frame, main_frame),
+        };
+        let expected = self.last_frame.as_ref().and_then(|frame| frame.checked_add_frames(1));
+        let frame = match (active_frame, other_frame) {
+            (Some(frame), _) if self.last_frame.as_ref() != Some(&frame) => Some(frame),
+            (_, Some(frame)) if expected.as_ref() == Some(&frame) => Some(frame),
+            _ => None,
+        };
+        if frame.is_some() {
+            self.last_frame = frame.clone();
+        }
+        frame
+    }
+
+    /// The source whose frames are returned
+    pub fn active_source(&self) -> LtcSource {
+        self.active
+    }
+
+    /// How many frames main is ahead of backup. None if not both feeds are received or their
+    /// frame-rates differ
+    pub fn divergence_frames(&self) -> Option<f64> {
+        let position = |decoder: &LtcDecoder<T>| {
+            let (frame, sub_frame) = decoder.extrapolated_now()?;
+            Some((frame.to_frame_count()? as f64 + sub_frame as f64, frame.frames_per_second))
+        };
+        let (main, main_frames_per_second) = position(&self.main)?;
+        let (backup, backup_frames_per_second) = position(&self.backup)?;
+        if main_frames_per_second != backup_frames_per_second {
+            return None;
+        }
+        Some(main - backup)
+    }
+
+    /// Tells if both feeds are received but carry different timecode. Frame-rates that differ
+    /// count as diverged
+    pub fn is_diverged(&self) -> bool {
+        let both_received = self.main.extrapolated_now().is_some() && self.backup.extrapolated_now().is_some();
+        both_received && self.divergence_frames().map(|frames| frames.abs() > self.max_divergence_frames).unwrap_or(true)
+    }
+
+    pub fn main(&self) -> &LtcDecoder<T> {
+        &self.main
+    }
+    pub fn backup(&self) -> &LtcDecoder<T> {
+        &self.backup
+    }
+
+    /// Returns to main as soon as main is as good as backup
+    fn select_source(&self) -> LtcSource {
+        let main = Self::confidence(self.main.sync_state());
+        let backup = Self::confidence(self.backup.sync_state());
+        if main >= backup {
+            LtcSource::Main
+        } else {
+            LtcSource::Backup
+        }
+    }
+
+    fn confidence(sync_state: SyncState) -> u8 {
+        match sync_state {
+            SyncState::Unlocked => 0,
+            SyncState::Locking => 1,
+            SyncState::Freewheeling => 2,
+            SyncState::Locked => 3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::redundant_decoder::{LtcSource, RedundantLtcDecoder};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_failover() {
+        let start = TimecodeFrame::new(10, 0, 0, 0, TwentyFive);
+        let samples = ltc_samples(&start, 25, &SignalShape::new(24.0));
+        let mut decoder = RedundantLtcDecoder::<i32>::new(48_000);
+        let mut frames = Vec::new();
+        // Backup is 100 samples late. Main fails after 10 frames
+        for i in 0..samples.len() {
+            let main = if i < 10 * 1920 { samples[i] } else { 0 };
+            let backup = if i >= 100 { samples[i - 100] } else { 0 };
+            frames.extend(decoder.push_samples(main, backup).map(|frame| (i, frame)));
+            if i == 9 * 1920 {
+                assert_eq!(decoder.active_source(), LtcSource::Main);
+                assert!(!decoder.is_diverged());
+                let divergence = decoder.divergence_frames().unwrap();
+                assert!((divergence - 100.0 / 1920.0).abs() < 0.01, "{}", divergence);
+            }
+        }
+        assert_eq!(decoder.active_source(), LtcSource::Backup);
+        assert!(!decoder.is_diverged());
+        // No frame is lost when switching to backup
+        assert!(frames.windows(2).all(|frames| frames[0].1.checked_add_frames(1).as_ref() == Some(&frames[1].1)));
+        assert_eq!(frames.last().unwrap().1, TimecodeFrame::new(10, 0, 0, 24, TwentyFive));
+    }
+
+    #[test]
+    fn test_divergence() {
+        let main = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let backup = ltc_samples(&TimecodeFrame::new(10, 0, 0, 5, TwentyFive), 10, &SignalShape::new(24.0));
+        let mut decoder = RedundantLtcDecoder::<i32>::new(48_000).with_max_divergence_frames(2.0);
+        for (main, backup) in main.into_iter().zip(backup) {
+            decoder.push_samples(main, backup);
+        }
+        assert!(decoder.is_diverged());
+        assert!((decoder.divergence_frames().unwrap() + 5.0).abs() < 0.01);
+        assert_eq!(decoder.active_source(), LtcSource::Main);
+    }
+}
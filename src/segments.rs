@@ -0,0 +1,259 @@
+//! Splits the timecode of a WAV file into segments of continuous timecode, e.g. to tell how many
+//! timecode breaks a tape has
+
+use std::io::Read;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::wav_file::{for_each_frame, for_each_frame_ext, WavDecodeError};
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// Why a segment ended
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SegmentBreak {
+    /// No timecode was decoded for more than 1.5 frame durations
+    Dropout,
+    /// The next frame followed in time, but its timecode does not follow the last frame
+    Discontinuity,
+    /// The next frame has another frame-rate
+    RateChange,
+}
+
+/// Frames of continuous timecode, where every frame follows the previous one without gap
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimecodeSegment {
+    pub start: TimecodeFrame,
+    /// The last frame of the segment
+    pub end: TimecodeFrame,
+    /// From the first sample of the first frame to the end of the last frame
+    pub sample_range: Range<u64>,
+    pub frame_count: u32,
+    /// None for the last segment
+    pub break_after: Option<SegmentBreak>,
+}
+
+impl TimecodeSegment {
+    pub fn frames_per_second(&self) -> &FramesPerSecond {
+        &self.start.frames_per_second
+    }
+}
+
+/// Continuity of the timecode of a file, aggregated for quality control
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContinuityReport {
+    pub frames_decoded: u64,
+    /// Frames with the same timecode as the frame right before
+    pub duplicated_frames: u64,
+    /// Frame numbers missing where the timecode advanced by up to `ContinuityReport::MAX_SKIPPED_FRAMES`
+    /// frames at once. Larger jumps are discontinuities
+    pub skipped_frames: u64,
+    /// Frames where the polarity correction bit does not lead to an even number of zeros
+    pub parity_failures: u64,
+    /// Frames with flags set that SMPTE ST 12-1 leaves unassigned at their frame-rate, which tells
+    /// that the generator writes garbage into them
+    pub unassigned_flags_frames: u64,
+    pub rate_changes: u32,
+    pub dropouts: u32,
+    /// Frames whose timecode does not follow the frame right before, including duplicated and
+    /// skipped frames
+    pub discontinuities: u32,
+    pub segments: Vec<TimecodeSegment>,
+}
+
+impl ContinuityReport {
+    pub const MAX_SKIPPED_FRAMES: u32 = 10;
+
+    fn count_break(&mut self, segment_break: SegmentBreak) {
+        match segment_break {
+            SegmentBreak::Dropout => self.dropouts += 1,
+            SegmentBreak::Discontinuity => self.discontinuities += 1,
+            SegmentBreak::RateChange => self.rate_changes += 1,
+        }
+    }
+}
+
+/// Decodes a channel (0 is the first channel) of a WAV file and aggregates the continuity of its
+/// timecode
+pub fn continuity_report_file<P: AsRef<Path>>(path: P, channel: u16) -> Result<ContinuityReport, WavDecodeError> {
+    continuity_report(hound::WavReader::open(path)?, channel)
+}
+
+/// Same as continuity_report_file, but reads the WAV data from any reader
+pub fn continuity_report<R: Read>(reader: hound::WavReader<R>, channel: u16) -> Result<ContinuityReport, WavDecodeError> {
+    let mut frames = Vec::new();
+    let mut parity_failures = 0;
+    let mut unassigned_flags_frames = 0;
+    let sampling_rate = for_each_frame_ext(reader, channel, |_, frame| {
+        if !frame.parity_valid {
+            parity_failures += 1;
+        }
+        if frame.unassigned_flags_set {
+            unassigned_flags_frames += 1;
+        }
+        frames.push((frame.start_sample, frame.frame));
+    })?;
+    let segments = split_segments(&frames, sampling_rate);
+    let mut report = ContinuityReport {
+        frames_decoded: frames.len() as u64,
+        duplicated_frames: 0,
+        skipped_frames: 0,
+        parity_failures,
+        unassigned_flags_frames,
+        rate_changes: 0,
+        dropouts: 0,
+        discontinuities: 0,
+        segments: Vec::new(),
+    };
+    for (segment, next) in segments.iter().zip(segments.iter().skip(1)) {
+        let Some(segment_break) = segment.break_after else {
+            continue;
+        };
+        report.count_break(segment_break);
+        if segment_break != SegmentBreak::Discontinuity {
+            continue;
+        }
+        let step = next.start.to_frame_count().zip(segment.end.to_frame_count()).map(|(next, end)| next as i64 - end as i64);
+        match step {
+            Some(0) => report.duplicated_frames += 1,
+            Some(step) if step > 1 && step <= ContinuityReport::MAX_SKIPPED_FRAMES as i64 + 1 => report.skipped_frames += step as u64 - 1,
+            _ => {}
+        }
+    }
+    report.segments = segments;
+    Ok(report)
+}
+
+/// Decodes a channel (0 is the first channel) of a WAV file and splits it into segments of
+/// continuous timecode. The number of breaks is one less than the number of segments
+pub fn find_segments_file<P: AsRef<Path>>(path: P, channel: u16) -> Result<Vec<TimecodeSegment>, WavDecodeError> {
+    find_segments(hound::WavReader::open(path)?, channel)
+}
+
+/// Same as find_segments_file, but reads the WAV data from any reader
+pub fn find_segments<R: Read>(reader: hound::WavReader<R>, channel: u16) -> Result<Vec<TimecodeSegment>, WavDecodeError> {
+    let mut frames = Vec::new();
+    let sampling_rate = for_each_frame(reader, channel, |_, start, frame| frames.push((start, frame)))?;
+    Ok(split_segments(&frames, sampling_rate))
+}
+
+/// Splits frames with the sample they started at into segments
+pub(crate) fn split_segments(frames: &[(u64, TimecodeFrame)], sampling_rate: u32) -> Vec<TimecodeSegment> {
+    let mut segments: Vec<TimecodeSegment> = Vec::new();
+    for (start_sample, frame) in frames {
+        let frame_samples = frame_duration_samples(&frame.frames_per_second, sampling_rate);
+        let end_sample = start_sample + frame_samples.unwrap_or(1);
+        if let Some(segment) = segments.last_mut() {
+            match segment_break(segment, *start_sample, frame, frame_samples) {
+                None => {
+                    segment.end = frame.clone();
+                    segment.sample_range.end = end_sample;
+                    segment.frame_count += 1;
+                    continue;
+                }
+                break_after => segment.break_after = break_after,
+            }
+        }
+        segments.push(TimecodeSegment {
+            start: frame.clone(),
+            end: frame.clone(),
+            sample_range: *start_sample..end_sample,
+            frame_count: 1,
+            break_after: None,
+        });
+    }
+    segments
+}
+
+/// Tells why a frame does not continue a segment. None if it does
+fn segment_break(segment: &TimecodeSegment, start_sample: u64, frame: &TimecodeFrame, frame_samples: Option<u64>) -> Option<SegmentBreak> {
+    if frame.frames_per_second != *segment.frames_per_second() {
+        return Some(SegmentBreak::RateChange);
+    }
+    // The end of the segment is where the frame should start
+    let gap = start_sample.saturating_sub(segment.sample_range.end);
+    if frame_samples.is_some_and(|frame_samples| 2 * gap > frame_samples) {
+        return Some(SegmentBreak::Dropout);
+    }
+    let mut expected = segment.end.clone();
+    expected.add_frame();
+    (expected != *frame).then_some(SegmentBreak::Discontinuity)
+}
+
+/// None if the frame-rate is unknown
+fn frame_duration_samples(frames_per_second: &FramesPerSecond, sampling_rate: u32) -> Option<u64> {
+    let frame_rate = frames_per_second.frame_rate()?;
+    Some((sampling_rate as u64 * frame_rate.denominator() as u64 + frame_rate.numerator() as u64 / 2) / frame_rate.numerator() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::FramesPerSecond::{Thirty, TwentyFive, TwentyFour};
+    use crate::ltc_decoder::test_signal::{ltc_samples, wav_data, SignalShape};
+    use crate::segments::{continuity_report, find_segments, SegmentBreak};
+    use crate::TimecodeFrame;
+
+    fn wav(samples: impl Iterator<Item = i32>) -> hound::WavReader<Cursor<Vec<u8>>> {
+        hound::WavReader::new(wav_data(samples)).unwrap()
+    }
+
+    #[test]
+    fn test_segments() {
+        let shape = SignalShape::new(24.0);
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 20, &shape).into_iter()
+            // Jump without gap
+            .chain(ltc_samples(&TimecodeFrame::new(10, 5, 0, 0, TwentyFive), 20, &shape))
+            // Dropout of a second
+            .chain(std::iter::repeat_n(0, 48_000))
+            .chain(ltc_samples(&TimecodeFrame::new(10, 5, 2, 0, TwentyFive), 20, &shape))
+            .chain(ltc_samples(&TimecodeFrame::new(10, 5, 3, 0, Thirty), 20, &SignalShape::new(20.0)));
+        let segments = find_segments(wav(samples), 0).unwrap();
+        let breaks: Vec<_> = segments.iter().map(|segment| segment.break_after).collect();
+        assert_eq!(breaks, [Some(SegmentBreak::Discontinuity), Some(SegmentBreak::Dropout), Some(SegmentBreak::RateChange), None]);
+        assert_eq!(segments[0].start, TimecodeFrame::new(10, 0, 0, 1, TwentyFive));
+        assert_eq!(segments[0].end, TimecodeFrame::new(10, 0, 0, 19, TwentyFive));
+        assert_eq!(segments[0].sample_range, 1920..20 * 1920);
+        assert_eq!(segments[0].frame_count, 19);
+        // The first frame after the jump is decoded
+        assert_eq!(segments[1].start, TimecodeFrame::new(10, 5, 0, 0, TwentyFive));
+        assert_eq!(segments[1].frame_count, 20);
+        assert_eq!(segments[3].frames_per_second(), &Thirty);
+    }
+
+    #[test]
+    fn test_continuity_report() {
+        let shape = SignalShape::new(24.0);
+        let mut invalid_parity = SignalShape::new(24.0);
+        invalid_parity.valid_parity = false;
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 5, &shape).into_iter()
+            // 10:00:00:04 twice
+            .chain(ltc_samples(&TimecodeFrame::new(10, 0, 0, 4, TwentyFive), 5, &shape))
+            // 10:00:00:09 to 10:00:00:11 skipped
+            .chain(ltc_samples(&TimecodeFrame::new(10, 0, 0, 12, TwentyFive), 5, &invalid_parity))
+            .chain(ltc_samples(&TimecodeFrame::new(10, 0, 0, 17, Thirty), 5, &SignalShape::new(20.0)));
+        let report = continuity_report(wav(samples), 0).unwrap();
+        // The first frame after the rate change is lost while the decoder gets in sync again
+        assert_eq!(report.frames_decoded, 18);
+        assert_eq!(report.duplicated_frames, 1);
+        assert_eq!(report.skipped_frames, 3);
+        assert_eq!(report.parity_failures, 5);
+        assert_eq!(report.unassigned_flags_frames, 0);
+        assert_eq!(report.rate_changes, 1);
+        assert_eq!(report.dropouts, 0);
+        assert_eq!(report.discontinuities, 2);
+        assert_eq!(report.segments.len(), 4);
+
+        let mut color_frame = SignalShape::new(25.0);
+        color_frame.color_frame = true;
+        let report = continuity_report(wav(ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFour), 5, &color_frame).into_iter()), 0).unwrap();
+        assert_eq!(report.frames_decoded, 4);
+        assert_eq!(report.parity_failures, 0);
+        assert_eq!(report.unassigned_flags_frames, 4);
+    }
+}
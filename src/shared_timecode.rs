@@ -0,0 +1,133 @@
+//! Passes the latest decoded frame from the audio thread (or an interrupt) to other threads without
+//! locks or allocations
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::ltc_decoder::TimecodeSink;
+use crate::{FramesPerSecond, TimecodeFrame};
+
+/// Holds the latest frame packed into an atomic, so it can be a `static` on embedded targets or be
+/// shared in an `Arc` with std. There must be a single writer: pass `&mut &shared` as sink to
+/// `LtcDecoder::push_samples` or call store. Any number of threads may read
+pub struct SharedTimecode {
+    /// Stored flag, sequence number and the fields of the frame. See pack
+    packed: AtomicU32,
+}
+
+impl SharedTimecode {
+    const STORED: u32 = 1 << 31;
+    const SEQUENCE_SHIFT: u32 = 26;
+    const SEQUENCE_MASK: u32 = 0b1_1111;
+    const FRAMES_PER_SECOND: [FramesPerSecond; 7] = [
+        FramesPerSecond::Unknown,
+        FramesPerSecond::TwentyFour,
+        FramesPerSecond::TwentyFive,
+        FramesPerSecond::Thirty,
+        FramesPerSecond::ThirtyDropFrame,
+        FramesPerSecond::Fifty,
+        FramesPerSecond::Sixty,
+    ];
+
+    pub const fn new() -> Self {
+        Self { packed: AtomicU32::new(0) }
+    }
+
+    /// Stores a frame and advances the sequence number. Frames with fields out of range are not
+    /// stored, as they don't fit the packing
+    pub fn store(&self, frame: &TimecodeFrame) {
+        if let Some(packed) = Self::pack(frame) {
+            let sequence = self.next_sequence();
+            self.packed.store(Self::STORED | (sequence << Self::SEQUENCE_SHIFT) | packed, Ordering::Release);
+        }
+    }
+    /// Removes the frame, e.g. because the decoder lost sync. The sequence number advances
+    pub fn clear(&self) {
+        let sequence = self.next_sequence();
+        self.packed.store(sequence << Self::SEQUENCE_SHIFT, Ordering::Release);
+    }
+    /// The latest stored frame and its sequence number, which changes with every store and clear
+    /// (wrapping at 32), so readers can tell a new frame from the one they have seen. None if no
+    /// frame was stored or it was cleared
+    pub fn latest(&self) -> Option<(TimecodeFrame, u8)> {
+        let packed = self.packed.load(Ordering::Acquire);
+        if packed & Self::STORED == 0 {
+            return None;
+        }
+        let field = |shift: u32, bits: u32| ((packed >> shift) & ((1 << bits) - 1)) as u8;
+        let frames_per_second = Self::FRAMES_PER_SECOND.get(field(23, 3) as usize)?.clone();
+        let frame = TimecodeFrame::new(field(18, 5), field(12, 6), field(6, 6), field(0, 6), frames_per_second);
+        Some((frame, field(Self::SEQUENCE_SHIFT, 5)))
+    }
+
+    /// The fields in the lowest 26 bits: frame-rate (3), hours (5), minutes (6), seconds (6) and
+    /// frames (6)
+    fn pack(frame: &TimecodeFrame) -> Option<u32> {
+        let frames_per_second = Self::FRAMES_PER_SECOND.iter().position(|frames_per_second| *frames_per_second == frame.frames_per_second)? as u32;
+        if frame.hours > 23 || frame.minutes > 59 || frame.seconds > 59 || frame.frames > 63 {
+            return None;
+        }
+        Some((frames_per_second << 23) | ((frame.hours as u32) << 18) | ((frame.minutes as u32) << 12) | ((frame.seconds as u32) << 6) | frame.frames as u32)
+    }
+    /// Only the single writer changes the sequence number, so it doesn't need to be atomic
+    fn next_sequence(&self) -> u32 {
+        ((self.packed.load(Ordering::Relaxed) >> Self::SEQUENCE_SHIFT) + 1) & Self::SEQUENCE_MASK
+    }
+}
+
+impl Default for SharedTimecode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimecodeSink for &SharedTimecode {
+    fn on_frame(&mut self, frame: &TimecodeFrame) {
+        self.store(frame);
+    }
+    fn on_sync_lost(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::FramesPerSecond::{Sixty, TwentyFive};
+    use crate::ltc_decoder::LtcDecoder;
+    use crate::ltc_decoder::test_signal::{ltc_samples, SignalShape};
+    use crate::shared_timecode::SharedTimecode;
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_store() {
+        static SHARED: SharedTimecode = SharedTimecode::new();
+        assert_eq!(SHARED.latest(), None);
+        let frame = TimecodeFrame::new(23, 59, 58, 59, Sixty);
+        SHARED.store(&frame);
+        assert_eq!(SHARED.latest(), Some((frame.clone(), 1)));
+        // Out of range, not stored
+        SHARED.store(&TimecodeFrame::new(24, 0, 0, 0, TwentyFive));
+        assert_eq!(SHARED.latest(), Some((frame, 1)));
+        SHARED.clear();
+        assert_eq!(SHARED.latest(), None);
+        for _ in 0..31 {
+            SHARED.store(&TimecodeFrame::new(1, 2, 3, 4, TwentyFive));
+        }
+        assert_eq!(SHARED.latest(), Some((TimecodeFrame::new(1, 2, 3, 4, TwentyFive), 1)));
+    }
+
+    #[test]
+    fn test_decode_in_other_thread() {
+        let shared = Arc::new(SharedTimecode::new());
+        let samples = ltc_samples(&TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, &SignalShape::new(24.0));
+        let writer = shared.clone();
+        thread::spawn(move || {
+            let mut decoder = LtcDecoder::<i32>::new(48_000);
+            decoder.push_samples(&samples, &mut &*writer);
+        }).join().unwrap();
+        // Nine frames were decoded
+        assert_eq!(shared.latest(), Some((TimecodeFrame::new(10, 0, 0, 9, TwentyFive), 9)));
+    }
+}
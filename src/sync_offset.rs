@@ -0,0 +1,127 @@
+//! Aligns two recordings by their LTC, e.g. for multicam or dual-system sound
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use crate::wav_file::{for_each_frame, WavDecodeError};
+use crate::FramesPerSecond;
+
+/// The sample at which each timecode starts, by frame count and frame-rate
+type FrameStarts = HashMap<(u32, FramesPerSecond), u64>;
+
+/// Relative position of two recordings that carry the same timecode
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct SyncOffset {
+    /// How many samples later the same timecode is recorded in the second file than in the first.
+    /// Counted at the sampling rate of the first file
+    pub offset_samples: i64,
+    /// The offset in frames of the timecode
+    pub offset_frames: f64,
+    /// How much faster the timecode of the second file passes than in the first, measured over
+    /// the overlap. None if the overlap is shorter than a second
+    pub drift_ppm: Option<f64>,
+    /// Number of frames decoded from both files
+    pub overlap_frames: u32,
+}
+
+/// Decodes the LTC on a channel (0 is the first channel) of two WAV files and calculates their
+/// offset at the first timecode they have in common. None if the files have no timecode in common
+pub fn sync_offset_files<P: AsRef<Path>, Q: AsRef<Path>>(first: P, first_channel: u16, second: Q, second_channel: u16) -> Result<Option<SyncOffset>, WavDecodeError> {
+    let first = hound::WavReader::new(BufReader::new(File::open(first).map_err(hound::Error::from)?))?;
+    let second = hound::WavReader::new(BufReader::new(File::open(second).map_err(hound::Error::from)?))?;
+    sync_offset(first, first_channel, second, second_channel)
+}
+
+/// Same as sync_offset_files, but reads the WAV data from any readers
+pub fn sync_offset<R: Read, S: Read>(first: hound::WavReader<R>, first_channel: u16, second: hound::WavReader<S>, second_channel: u16) -> Result<Option<SyncOffset>, WavDecodeError> {
+    let (first_rate, first_frames) = frame_starts(first, first_channel)?;
+    let (second_rate, second_frames) = frame_starts(second, second_channel)?;
+    // Start in the first file, start in the second file at the rate of the first file and fps
+    let mut common: Vec<(f64, f64, f32)> = second_frames.iter()
+        .filter_map(|((count, frames_per_second), second_start)| {
+            let first_start = first_frames.get(&(*count, frames_per_second.clone()))?;
+            Some((*first_start as f64, *second_start as f64 * first_rate as f64 / second_rate as f64, frames_per_second.fps()?))
+        })
+        .collect();
+    common.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (Some(first_common), Some(last_common)) = (common.first(), common.last()) else {
+        return Ok(None);
+    };
+    let offset_samples = first_common.1 - first_common.0;
+    let duration = last_common.0 - first_common.0;
+    let drift_ppm = (duration >= first_rate as f64).then(|| drift_ppm(&common));
+    Ok(Some(SyncOffset {
+        offset_samples: offset_samples.round() as i64,
+        offset_frames: offset_samples * first_common.2 as f64 / first_rate as f64,
+        drift_ppm,
+        overlap_frames: common.len() as u32,
+    }))
+}
+
+/// Slope of the offset over the overlap by linear regression, as the start of single frames is
+/// only accurate to a few samples. The offset grows if the timecode of the second file runs slower
+fn drift_ppm(common: &[(f64, f64, f32)]) -> f64 {
+    let count = common.len() as f64;
+    let mean_start = common.iter().map(|(first, _, _)| first).sum::<f64>() / count;
+    let mean_offset = common.iter().map(|(first, second, _)| second - first).sum::<f64>() / count;
+    let covariance: f64 = common.iter().map(|(first, second, _)| (first - mean_start) * (second - first - mean_offset)).sum();
+    let variance: f64 = common.iter().map(|(first, _, _)| (first - mean_start).powi(2)).sum();
+    -covariance / variance * 1_000_000.0
+}
+
+/// The sampling rate and the sample at which each timecode starts. Timecode that repeats keeps
+/// its first start. Frames with unknown frame-rate are skipped
+fn frame_starts<R: Read>(reader: hound::WavReader<R>, channel: u16) -> Result<(u32, FrameStarts), WavDecodeError> {
+    let mut starts = HashMap::new();
+    let sampling_rate = for_each_frame(reader, channel, |_, start, frame| {
+        if let (Some(count), Some(_)) = (frame.to_frame_count(), frame.frames_per_second.fps()) {
+            starts.entry((count, frame.frames_per_second)).or_insert(start);
+        }
+    })?;
+    Ok((sampling_rate, starts))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::ltc_decoder::test_signal::{ltc_samples, wav_data, SignalShape};
+    use crate::sync_offset::sync_offset;
+    use crate::TimecodeFrame;
+
+    fn wav(silence: usize, start: &TimecodeFrame, frame_count: usize, samples_per_bit: f32) -> hound::WavReader<Cursor<Vec<u8>>> {
+        let samples = std::iter::repeat_n(0, silence).chain(ltc_samples(start, frame_count, &SignalShape::new(samples_per_bit)));
+        hound::WavReader::new(wav_data(samples)).unwrap()
+    }
+
+    #[test]
+    fn test_sync_offset() {
+        let first = wav(0, &TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 75, 24.0);
+        // 10:00:00:05 is recorded at 9600 in the first file and at 300 in the second
+        let second = wav(300, &TimecodeFrame::new(10, 0, 0, 5, TwentyFive), 75, 24.0);
+        let offset = sync_offset(first, 0, second, 0).unwrap().unwrap();
+        assert!((offset.offset_samples + 9300).abs() <= 2, "{:?}", offset);
+        assert!((offset.offset_frames + 9300.0 / 1920.0).abs() < 0.01, "{:?}", offset);
+        assert!(offset.drift_ppm.unwrap().abs() < 50.0, "{:?}", offset);
+        assert_eq!(offset.overlap_frames, 69);
+    }
+
+    #[test]
+    fn test_drift() {
+        let first = wav(0, &TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 100, 24.0);
+        // The timecode of the second file runs 1300 ppm slower. The samples per bit are exact in f32
+        let second = wav(0, &TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 100, 24.03125);
+        let offset = sync_offset(first, 0, second, 0).unwrap().unwrap();
+        assert!((offset.drift_ppm.unwrap() + 1300.0).abs() < 20.0, "{:?}", offset);
+    }
+
+    #[test]
+    fn test_no_common_timecode() {
+        let first = wav(0, &TimecodeFrame::new(10, 0, 0, 0, TwentyFive), 10, 24.0);
+        let second = wav(0, &TimecodeFrame::new(11, 0, 0, 0, TwentyFive), 10, 24.0);
+        assert_eq!(sync_offset(first, 0, second, 0).unwrap(), None);
+    }
+}
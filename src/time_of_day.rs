@@ -0,0 +1,101 @@
+//! Conversions between time-of-day timecode and the time types of the chrono and time crates
+
+use crate::{FramesPerSecond, TimecodeFrame};
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+const NANOS_PER_DAY: u64 = 86_400 * 1_000_000_000;
+
+impl TimecodeFrame {
+    /// Real time since midnight at the start of the frame. For drop-frame timecode this is the
+    /// time elapsed at 29.97 fps, which differs from the labeled time by less than a frame per hour
+    fn nanos_since_midnight(&self) -> Option<u64> {
+        let frame_rate = self.frames_per_second.frame_rate()?;
+        let nanos = self.to_frame_count()? as u128 * frame_rate.denominator() as u128 * NANOS_PER_SECOND / frame_rate.numerator() as u128;
+        Some((nanos as u64).min(NANOS_PER_DAY - 1))
+    }
+    /// The frame that contains the point in time. Drop-frame timecode runs slower than real time,
+    /// so the last fraction of a second before midnight stays in the last frame of the day
+    fn from_nanos_since_midnight(nanos: u64, frames_per_second: FramesPerSecond) -> Option<Self> {
+        let frame_rate = frames_per_second.frame_rate()?;
+        let count = nanos as u128 * frame_rate.numerator() as u128 / (frame_rate.denominator() as u128 * NANOS_PER_SECOND);
+        let count = (count as u32).min(frames_per_second.frames_per_day()? - 1);
+        Self::from_frame_count(count, frames_per_second)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TimecodeFrame {
+    /// Time of day at the start of the frame. None if the frame-rate is unknown or the timecode
+    /// is invalid
+    pub fn to_naive_time(&self) -> Option<chrono::NaiveTime> {
+        let nanos = self.nanos_since_midnight()?;
+        chrono::NaiveTime::from_num_seconds_from_midnight_opt((nanos / 1_000_000_000) as u32, (nanos % 1_000_000_000) as u32)
+    }
+    /// The frame that contains the time of day. None if the frame-rate is unknown
+    pub fn from_naive_time(time: chrono::NaiveTime, frames_per_second: FramesPerSecond) -> Option<Self> {
+        use chrono::Timelike;
+        // Leap seconds are reported as nanoseconds beyond one second
+        let nanos = time.num_seconds_from_midnight() as u64 * 1_000_000_000 + time.nanosecond().min(999_999_999) as u64;
+        Self::from_nanos_since_midnight(nanos, frames_per_second)
+    }
+}
+
+#[cfg(feature = "time")]
+impl TimecodeFrame {
+    /// Time of day at the start of the frame. None if the frame-rate is unknown or the timecode
+    /// is invalid
+    pub fn to_time(&self) -> Option<time::Time> {
+        let nanos = self.nanos_since_midnight()?;
+        let seconds = nanos / 1_000_000_000;
+        time::Time::from_hms_nano((seconds / 3600) as u8, (seconds / 60 % 60) as u8, (seconds % 60) as u8, (nanos % 1_000_000_000) as u32).ok()
+    }
+    /// The frame that contains the time of day. None if the frame-rate is unknown
+    pub fn from_time(time: time::Time, frames_per_second: FramesPerSecond) -> Option<Self> {
+        let (hours, minutes, seconds, nanos) = time.as_hms_nano();
+        let seconds = hours as u64 * 3600 + minutes as u64 * 60 + seconds as u64;
+        Self::from_nanos_since_midnight(seconds * 1_000_000_000 + nanos as u64, frames_per_second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FramesPerSecond::{ThirtyDropFrame, TwentyFive, Unknown};
+    use crate::TimecodeFrame;
+
+    #[test]
+    fn test_nanos_since_midnight() {
+        let frame = TimecodeFrame::new(1, 2, 3, 4, TwentyFive);
+        assert_eq!(frame.nanos_since_midnight(), Some(3_723_160_000_000));
+        assert_eq!(TimecodeFrame::from_nanos_since_midnight(3_723_199_999_999, TwentyFive), Some(frame));
+        assert_eq!(TimecodeFrame::new(1, 0, 0, 0, Unknown).nanos_since_midnight(), None);
+        // Drop-frame timecode stays within a frame of the real time
+        let nanos = TimecodeFrame::new(1, 0, 0, 0, ThirtyDropFrame).nanos_since_midnight().unwrap();
+        assert!(nanos.abs_diff(3_600_000_000_000) < 33_366_667);
+        // The real time just before midnight is beyond the last drop-frame frame
+        let last_frame = TimecodeFrame::new(23, 59, 59, 29, ThirtyDropFrame);
+        assert_eq!(TimecodeFrame::from_nanos_since_midnight(86_399_950_000_000, ThirtyDropFrame), Some(last_frame.clone()));
+        assert_eq!(TimecodeFrame::from_nanos_since_midnight(86_399_999_999_999, ThirtyDropFrame), Some(last_frame));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        let time = chrono::NaiveTime::from_hms_milli_opt(10, 20, 30, 500).unwrap();
+        let frame = TimecodeFrame::from_naive_time(time, TwentyFive).unwrap();
+        assert_eq!(frame, TimecodeFrame::new(10, 20, 30, 12, TwentyFive));
+        assert_eq!(frame.to_naive_time(), Some(chrono::NaiveTime::from_hms_milli_opt(10, 20, 30, 480).unwrap()));
+        let time = chrono::NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap();
+        assert_eq!(TimecodeFrame::from_naive_time(time, ThirtyDropFrame), Some(TimecodeFrame::new(23, 59, 59, 29, ThirtyDropFrame)));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time() {
+        let time = time::Time::from_hms_milli(23, 59, 59, 999).unwrap();
+        let frame = TimecodeFrame::from_time(time, TwentyFive).unwrap();
+        assert_eq!(frame, TimecodeFrame::new(23, 59, 59, 24, TwentyFive));
+        assert_eq!(frame.to_time(), Some(time::Time::from_hms_milli(23, 59, 59, 960).unwrap()));
+        let frame = TimecodeFrame::from_time(time, ThirtyDropFrame).unwrap();
+        assert_eq!(frame, TimecodeFrame::new(23, 59, 59, 29, ThirtyDropFrame));
+    }
+}
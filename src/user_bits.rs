@@ -0,0 +1,216 @@
+//! The 32 user bits of an LTC frame and the binary group flags that tell how to interpret them
+
+/// The eight 4-bit binary groups of a frame and the binary group flags (BGF)
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserBits {
+    /// Binary group 1 in the lowest nibble, binary group 8 in the highest nibble
+    pub groups: u32,
+    /// BGF0 in bit 0, BGF1 in bit 1, BGF2 in bit 2
+    pub binary_group_flags: u8,
+}
+
+/// Content of the user bits as signalled by BGF0 and BGF2
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UserBitsFormat {
+    Unspecified,
+    /// Four characters of an 8-bit character set (ISO/IEC 646 or 2022)
+    EightBitCharacters,
+    /// Date and time zone according to SMPTE ST 309
+    Date,
+    /// Page/line multiplex
+    PageLine,
+}
+
+/// What the timecode counts, as signalled by the clock flag (BGF1)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimecodeKind {
+    /// The generator is synchronized to an external clock, the timecode is the time of day
+    TimeOfDay,
+    /// The timecode runs freely, e.g. elapsed time since the start of a recording
+    Elapsed,
+}
+
+/// Date and time zone carried in the user bits according to SMPTE ST 309
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserBitsDate {
+    /// Two-digit year, 0..=99
+    pub year: u8,
+    pub month: u8,
+    pub day: u8,
+    /// Time zone code of ST 309, 0x00..=0x3F
+    pub time_zone: u8,
+}
+
+/// Conventions of generators that fill the user bits without signalling the content with the
+/// binary group flags, e.g. field recorders and cameras set to write the date or the reel number.
+/// Digits are counted as displayed, binary group 8 first
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UserBitsPreset {
+    /// Date as BCD YYMMDD in the first six digits
+    DateYyMmDd,
+    /// Date as BCD DDMMYY in the first six digits
+    DateDdMmYy,
+    /// Date as BCD MMDDYY in the first six digits
+    DateMmDdYy,
+    /// Date and time zone according to SMPTE ST 309, also if the flags don't signal it
+    St309Date,
+    /// Reel or roll number as eight BCD digits
+    ReelNumber,
+    /// Four 8-bit characters like with `UserBits::characters`, also if the flags don't signal them
+    Characters,
+}
+
+/// The content of the user bits as interpreted by a `UserBitsPreset`
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UserBitsValue {
+    /// The time zone is 0 for presets without time zone
+    Date(UserBitsDate),
+    Number(u32),
+    Characters([u8; 4]),
+}
+
+impl UserBits {
+    pub const BGF0: u8 = 0b001;
+    /// Set if the time is synchronized to an external clock
+    pub const BGF1: u8 = 0b010;
+    pub const BGF2: u8 = 0b100;
+    /// Set in the time zone byte if the date is a Modified Julian Date instead of YYMMDD
+    const DATE_FLAG_MJD: u8 = 0b0100_0000;
+
+    /// The value of a binary group, 0 for group 1 up to 7 for group 8
+    pub fn group(&self, index: usize) -> u8 {
+        ((self.groups >> (index * 4)) & 0xF) as u8
+    }
+
+    /// Tells if the frame carries time of day, from the clock flag (BGF1)
+    pub fn timecode_kind(&self) -> TimecodeKind {
+        if self.binary_group_flags & Self::BGF1 != 0 {
+            TimecodeKind::TimeOfDay
+        } else {
+            TimecodeKind::Elapsed
+        }
+    }
+
+    pub fn format(&self) -> UserBitsFormat {
+        match (self.binary_group_flags & Self::BGF2 != 0, self.binary_group_flags & Self::BGF0 != 0) {
+            (false, false) => UserBitsFormat::Unspecified,
+            (false, true) => UserBitsFormat::EightBitCharacters,
+            (true, false) => UserBitsFormat::Date,
+            (true, true) => UserBitsFormat::PageLine,
+        }
+    }
+
+    /// The four characters if the flags signal an 8-bit character set. Each character is made of
+    /// two groups, the lower group holds the low nibble
+    pub fn characters(&self) -> Option<[u8; 4]> {
+        if self.format() != UserBitsFormat::EightBitCharacters {
+            return None;
+        }
+        Some(self.groups.to_le_bytes())
+    }
+
+    /// The date if the flags signal a date. Binary groups 1 and 2 hold the day, 3 and 4 the month,
+    /// 5 and 6 the year and 7 and 8 the time zone. None for dates in Modified Julian Date format
+    /// and for invalid values
+    pub fn date(&self) -> Option<UserBitsDate> {
+        if self.format() != UserBitsFormat::Date {
+            return None;
+        }
+        self.st309_date()
+    }
+
+    /// Interprets the user bits according to a preset, regardless of the binary group flags. None
+    /// if the user bits hold no valid value for the preset
+    pub fn interpret(&self, preset: UserBitsPreset) -> Option<UserBitsValue> {
+        let date = |year: usize, month: usize, day: usize| {
+            Self::valid_date(UserBitsDate { year: self.bcd(year)?, month: self.bcd(month)?, day: self.bcd(day)?, time_zone: 0 })
+        };
+        match preset {
+            UserBitsPreset::DateYyMmDd => date(6, 4, 2).map(UserBitsValue::Date),
+            UserBitsPreset::DateDdMmYy => date(2, 4, 6).map(UserBitsValue::Date),
+            UserBitsPreset::DateMmDdYy => date(2, 6, 4).map(UserBitsValue::Date),
+            UserBitsPreset::St309Date => self.st309_date().map(UserBitsValue::Date),
+            UserBitsPreset::ReelNumber => (0..8).rev()
+                .try_fold(0, |number, group| (self.group(group) < 10).then(|| number * 10 + self.group(group) as u32))
+                .map(UserBitsValue::Number),
+            UserBitsPreset::Characters => Some(UserBitsValue::Characters(self.groups.to_le_bytes())),
+        }
+    }
+
+    fn st309_date(&self) -> Option<UserBitsDate> {
+        let time_zone = self.group(6) | self.group(7) << 4;
+        if time_zone & Self::DATE_FLAG_MJD != 0 {
+            return None;
+        }
+        Self::valid_date(UserBitsDate {
+            year: self.bcd(4)?,
+            month: self.bcd(2)?,
+            day: self.bcd(0)?,
+            time_zone: time_zone & 0x3F,
+        })
+    }
+    fn valid_date(date: UserBitsDate) -> Option<UserBitsDate> {
+        ((1..=12).contains(&date.month) && (1..=31).contains(&date.day)).then_some(date)
+    }
+    /// Two BCD digits, the units in the binary group at index units and the tens in the next one
+    fn bcd(&self, units: usize) -> Option<u8> {
+        let (units, tens) = (self.group(units), self.group(units + 1));
+        (units < 10 && tens < 10).then_some(tens * 10 + units)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::user_bits::{TimecodeKind, UserBits, UserBitsDate, UserBitsFormat, UserBitsPreset, UserBitsValue};
+
+    #[test]
+    fn test_characters() {
+        let user_bits = UserBits { groups: u32::from_le_bytes(*b"R042"), binary_group_flags: UserBits::BGF0 };
+        assert_eq!(user_bits.format(), UserBitsFormat::EightBitCharacters);
+        assert_eq!(user_bits.group(0), b'R' & 0xF);
+        assert_eq!(user_bits.group(1), b'R' >> 4);
+        assert_eq!(user_bits.characters(), Some(*b"R042"));
+        assert_eq!(user_bits.timecode_kind(), TimecodeKind::Elapsed);
+        let user_bits = UserBits { binary_group_flags: UserBits::BGF1, ..user_bits };
+        assert_eq!(user_bits.timecode_kind(), TimecodeKind::TimeOfDay);
+        assert_eq!(user_bits.format(), UserBitsFormat::Unspecified);
+        assert_eq!(user_bits.characters(), None);
+    }
+
+    #[test]
+    fn test_date() {
+        // 31 December 2024 in time zone 0x0A
+        let user_bits = UserBits { groups: 0x0A_24_12_31, binary_group_flags: UserBits::BGF2 };
+        assert_eq!(user_bits.format(), UserBitsFormat::Date);
+        assert_eq!(user_bits.date(), Some(UserBitsDate { year: 24, month: 12, day: 31, time_zone: 0x0A }));
+        assert_eq!(user_bits.characters(), None);
+        assert_eq!(UserBits { groups: 0x0A_24_13_31, ..user_bits }.date(), None);
+        assert_eq!(UserBits { groups: 0x0A_24_1A_31, ..user_bits }.date(), None);
+        assert_eq!(UserBits { groups: 0x4A_24_12_31, ..user_bits }.date(), None);
+        assert_eq!(UserBits { binary_group_flags: 0, ..user_bits }.date(), None);
+    }
+
+    #[test]
+    fn test_presets() {
+        // Displayed as 241231 07 without flags
+        let user_bits = UserBits { groups: 0x24_12_31_07, binary_group_flags: 0 };
+        let date = |year, month, day| Some(UserBitsValue::Date(UserBitsDate { year, month, day, time_zone: 0 }));
+        assert_eq!(user_bits.interpret(UserBitsPreset::DateYyMmDd), date(24, 12, 31));
+        assert_eq!(user_bits.interpret(UserBitsPreset::DateMmDdYy), None);
+        assert_eq!(UserBits { groups: 0x31_12_24_07, ..user_bits }.interpret(UserBitsPreset::DateDdMmYy), date(24, 12, 31));
+        assert_eq!(UserBits { groups: 0x12_31_24_07, ..user_bits }.interpret(UserBitsPreset::DateMmDdYy), date(24, 12, 31));
+        assert_eq!(UserBits { groups: 0x0A_24_12_31, ..user_bits }.interpret(UserBitsPreset::St309Date),
+                   Some(UserBitsValue::Date(UserBitsDate { year: 24, month: 12, day: 31, time_zone: 0x0A })));
+        assert_eq!(user_bits.interpret(UserBitsPreset::ReelNumber), Some(UserBitsValue::Number(24_123_107)));
+        assert_eq!(UserBits { groups: 0x0000_001A, ..user_bits }.interpret(UserBitsPreset::ReelNumber), None);
+        let reel = UserBits { groups: u32::from_le_bytes(*b"A001"), ..user_bits };
+        assert_eq!(reel.characters(), None);
+        assert_eq!(reel.interpret(UserBitsPreset::Characters), Some(UserBitsValue::Characters(*b"A001")));
+    }
+}
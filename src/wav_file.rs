@@ -0,0 +1,176 @@
+//! Helpers to decode timecode from WAV files
+
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::Path;
+
+use crate::ltc_decoder::{float_to_int_sample, LtcDecoder, TimecodeFrameExt};
+use crate::TimecodeFrame;
+
+/// Errors that may occur when decoding timecode from a WAV file
+#[derive(Debug)]
+pub enum WavDecodeError {
+    /// The file could not be read or is not a valid WAV file
+    Wav(hound::Error),
+    /// The selected channel does not exist in the file
+    ChannelOutOfRange { channel: u16, channel_count: u16 },
+    /// The sample format or the sampling rate (e.g. 0) of the file is not supported
+    UnsupportedFormat,
+}
+
+impl Display for WavDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WavDecodeError::Wav(e) => write!(f, "Could not read WAV file: {}", e),
+            WavDecodeError::ChannelOutOfRange { channel, channel_count } => write!(f, "Channel {} selected but the file only has {} channels", channel, channel_count),
+            WavDecodeError::UnsupportedFormat => write!(f, "Unsupported sample format or sampling rate"),
+        }
+    }
+}
+
+impl std::error::Error for WavDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WavDecodeError::Wav(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<hound::Error> for WavDecodeError {
+    fn from(e: hound::Error) -> Self {
+        WavDecodeError::Wav(e)
+    }
+}
+
+/// Decodes all timecode-frames of one channel (0 is the first channel) in a WAV file. Files may
+/// have any number of channels and use WAVE_FORMAT_EXTENSIBLE, e.g. with 24 bits packed in 32.
+/// Returns the frames together with the index of the sample (per channel) at which they were
+/// decoded
+pub fn decode_wav_file<P: AsRef<Path>>(path: P, channel: u16) -> Result<Vec<(u64, TimecodeFrame)>, WavDecodeError> {
+    decode_wav(hound::WavReader::open(path)?, channel)
+}
+
+/// Same as decode_wav_file, but reads the WAV data from any reader
+pub fn decode_wav<R: Read>(reader: hound::WavReader<R>, channel: u16) -> Result<Vec<(u64, TimecodeFrame)>, WavDecodeError> {
+    let mut frames = Vec::new();
+    for_each_frame(reader, channel, |index, _, frame| frames.push((index, frame)))?;
+    Ok(frames)
+}
+
+/// Calls on_frame with the index of the sample at which a frame was decoded, the index of the
+/// sample at which it started and the frame. Returns the sampling rate
+pub(crate) fn for_each_frame<R: Read, F: FnMut(u64, u64, TimecodeFrame)>(reader: hound::WavReader<R>, channel: u16, mut on_frame: F) -> Result<u32, WavDecodeError> {
+    for_each_frame_ext(reader, channel, |index, frame| on_frame(index, frame.start_sample, frame.frame))
+}
+
+/// Like for_each_frame, but passes the flags, user bits, parity and timing of the frames as well
+pub(crate) fn for_each_frame_ext<R: Read, F: FnMut(u64, TimecodeFrameExt)>(reader: hound::WavReader<R>, channel: u16, mut on_frame: F) -> Result<u32, WavDecodeError> {
+    let spec = reader.spec();
+    if channel >= spec.channels {
+        return Err(WavDecodeError::ChannelOutOfRange { channel, channel_count: spec.channels });
+    }
+    // hound accepts headers with a sampling rate of 0, which the decoder can't work with
+    if spec.sample_rate == 0 {
+        return Err(WavDecodeError::UnsupportedFormat);
+    }
+    let mut decoder = LtcDecoder::<i32>::new(spec.sample_rate);
+    let mut push_sample = |index: usize, sample: i32| {
+        if let Some(frame) = decoder.get_timecode_frame_ext(sample) {
+            on_frame(index as u64, frame);
+        }
+    };
+    match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Int, _) => {
+            let samples = reader.into_samples::<i32>()
+                .skip(channel as usize)
+                .step_by(spec.channels as usize);
+            for (index, sample) in samples.enumerate() {
+                push_sample(index, sample?);
+            }
+        }
+        (hound::SampleFormat::Float, 32) => {
+            let samples = reader.into_samples::<f32>()
+                .skip(channel as usize)
+                .step_by(spec.channels as usize);
+            for (index, sample) in samples.enumerate() {
+                push_sample(index, float_to_int_sample(sample?));
+            }
+        }
+        _ => return Err(WavDecodeError::UnsupportedFormat),
+    }
+    Ok(spec.sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ltc_decoder::test_signal::{ltc_samples, wav_data, wav_data_with_spec, SignalShape};
+    use crate::FramesPerSecond::TwentyFive;
+    use crate::TimecodeFrame;
+    use crate::wav_file::{decode_wav, decode_wav_file, WavDecodeError};
+
+    #[test]
+    fn test_decode_wav_file() {
+        let frames = decode_wav_file("testfiles/LTC_00100000_2mins_25fps_44100x8.wav", 0).unwrap();
+        assert_eq!(frames.first().unwrap().1, TimecodeFrame::new(0, 10, 0, 1, TwentyFive));
+        assert_eq!(frames.last().unwrap().1, TimecodeFrame::new(0, 12, 0, 24, TwentyFive));
+        assert!(frames.windows(2).all(|f| f[0].0 < f[1].0));
+    }
+
+    #[test]
+    fn test_decode_float_wav() {
+        let start = TimecodeFrame::new(1, 2, 3, 4, TwentyFive);
+        let samples = ltc_samples(&start, 50, &SignalShape::new(24.0));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let samples = samples.into_iter().flat_map(|sample| [0.0, sample as f32 / 20_000.0]);
+        let data = wav_data_with_spec(hound::WavSpecEx { spec, bytes_per_sample: 4 }, samples);
+        let frames = decode_wav(hound::WavReader::new(data).unwrap(), 1).unwrap();
+        assert_eq!(frames.len(), 49);
+        assert_eq!(frames.last().unwrap().1, TimecodeFrame::new(1, 2, 5, 3, TwentyFive));
+    }
+
+    #[test]
+    fn test_decode_extensible_wav() {
+        let start = TimecodeFrame::new(1, 2, 3, 4, TwentyFive);
+        let samples = ltc_samples(&start, 50, &SignalShape::new(24.0));
+        // Written as WAVE_FORMAT_EXTENSIBLE with 24 bits packed in 32
+        let spec = hound::WavSpecEx {
+            spec: hound::WavSpec {
+                channels: 6,
+                sample_rate: 48_000,
+                bits_per_sample: 24,
+                sample_format: hound::SampleFormat::Int,
+            },
+            bytes_per_sample: 4,
+        };
+        let samples = samples.into_iter().flat_map(|sample| (0..6).map(move |channel| if channel == 4 { sample * 400 } else { 0 }));
+        let data = wav_data_with_spec(spec, samples);
+        assert_eq!(u16::from_le_bytes([data.get_ref()[20], data.get_ref()[21]]), 0xFFFE);
+        let frames = decode_wav(hound::WavReader::new(data).unwrap(), 4).unwrap();
+        assert_eq!(frames.len(), 49);
+        assert_eq!(frames.last().unwrap().1, TimecodeFrame::new(1, 2, 5, 3, TwentyFive));
+        assert_eq!(frames.last().unwrap().0, 49 * 1920 + 1536);
+    }
+
+    #[test]
+    fn test_zero_sample_rate() {
+        let samples = ltc_samples(&TimecodeFrame::new(1, 2, 3, 4, TwentyFive), 5, &SignalShape::new(24.0));
+        let mut data = wav_data(samples);
+        // The sampling rate and the byte rate derived from it in the fmt chunk
+        data.get_mut()[24..32].fill(0);
+        let reader = hound::WavReader::new(data).unwrap();
+        assert_eq!(reader.spec().sample_rate, 0);
+        assert!(matches!(decode_wav(reader, 0), Err(WavDecodeError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn test_channel_out_of_range() {
+        let result = decode_wav_file("testfiles/LTC_00100000_2mins_25fps_44100x8.wav", 2);
+        assert!(matches!(result, Err(WavDecodeError::ChannelOutOfRange { channel: 2, channel_count: _ })));
+    }
+}